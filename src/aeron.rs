@@ -16,11 +16,12 @@
 
 use std::ffi::CString;
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use rand::distributions::Uniform;
 
-use crate::client_conductor::ClientConductor;
+use crate::channel_uri::ChannelUri;
+use crate::client_conductor::{ClientConductor, ClosedResources};
 use crate::cnc_file_descriptor;
 use crate::concurrent::agent_invoker::AgentInvoker;
 use crate::concurrent::agent_runner::{AgentRunner, AgentStopper};
@@ -30,13 +31,15 @@ use crate::concurrent::broadcast::copy_broadcast_receiver::CopyBroadcastReceiver
 use crate::concurrent::counters::CountersReader;
 use crate::concurrent::ring_buffer::ManyToOneRingBuffer;
 use crate::concurrent::strategies::SleepingIdleStrategy;
-use crate::context::{Context, OnAvailableCounter, OnAvailableImage, OnCloseClient, OnUnavailableCounter, OnUnavailableImage};
+use crate::context::{
+    Context, OnAvailableCounter, OnAvailableImage, OnChannelErrored, OnCloseClient, OnUnavailableCounter, OnUnavailableImage,
+};
 use crate::counter::Counter;
 use crate::driver_proxy::DriverProxy;
 use crate::exclusive_publication::ExclusivePublication;
 use crate::publication::Publication;
 use crate::subscription::Subscription;
-use crate::utils::errors::{AeronError, DriverInteractionError, GenericError};
+use crate::utils::errors::{AeronError, DriverInteractionError, GenericError, IllegalStateError};
 use crate::utils::memory_mapped_file::MemoryMappedFile;
 use crate::utils::misc::{semantic_version_major, semantic_version_to_string, unix_time_ms};
 use crate::utils::types::Moment;
@@ -73,6 +76,18 @@ pub struct Aeron {
     conductor_invoker: AgentInvoker<ClientConductor>,
 }
 
+/// Result of Aeron::close().
+#[derive(Debug, Default)]
+pub struct CloseSummary {
+    /// Publications, subscriptions, images and counters closed by the client.
+    pub resources: ClosedResources,
+    pub lingering_images_released: usize,
+    pub log_buffers_released: usize,
+    /// Log buffers still referenced by the application when close timeout has expired.
+    pub log_buffers_in_use: usize,
+    pub errors: Vec<AeronError>,
+}
+
 const IDLE_SLEEP_MS: Moment = 4;
 const IDLE_SLEEP_MS_1: Moment = 1;
 const IDLE_SLEEP_MS_16: Moment = 16;
@@ -115,6 +130,7 @@ impl Aeron {
             context.available_counter_handler(),
             context.unavailable_counter_handler(),
             context.close_client_handler(),
+            context.channel_errored_handler(),
             context.media_driver_timeout(),
             context.resource_linger_timeout(),
             cnc_file_descriptor::client_liveness_timeout(&cnc_buf) as u64,
@@ -166,6 +182,72 @@ impl Aeron {
         self.conductor.lock().expect("Mutex poisoned").is_closed()
     }
 
+    /**
+     * Close the client explicitly: close all its resources, stop the conductor agent (or invoker), notify
+     * the driver with client close and release lingering images and log buffers. Log buffers still referenced
+     * by the application are waited for until the timeout expires. The CnC file is unmapped on return.
+     *
+     * @param timeout to wait for the log buffers to be released by the application.
+     * @return summary of the closed resources, or AeronError::CloseIncomplete holding the summary with errors.
+     */
+    pub fn close(mut self, timeout: Duration) -> Result<CloseSummary, AeronError> {
+        let mut summary = CloseSummary::default();
+
+        {
+            let mut conductor = self.conductor.lock().expect("Mutex poisoned");
+            // Resources may be already closed e.g. on client timeout
+            if !conductor.is_closed() {
+                summary.resources = conductor.close_all_resources(unix_time_ms());
+            }
+        }
+
+        self.stop_conductor();
+
+        if let Err(err) = self.conductor.lock().expect("Mutex poisoned").close_client() {
+            summary.errors.push(err);
+        }
+
+        let deadline = Instant::now() + timeout;
+        loop {
+            {
+                let mut conductor = self.conductor.lock().expect("Mutex poisoned");
+                let (images_released, log_buffers_released) = conductor.release_lingering_resources();
+                summary.lingering_images_released += images_released;
+                summary.log_buffers_released += log_buffers_released;
+                summary.log_buffers_in_use = conductor.log_buffers_in_use();
+            }
+
+            if summary.log_buffers_in_use == 0 || Instant::now() >= deadline {
+                break;
+            }
+
+            std::thread::sleep(Duration::from_millis(IDLE_SLEEP_MS_1));
+        }
+
+        if summary.log_buffers_in_use > 0 {
+            summary
+                .errors
+                .push(IllegalStateError::LogBuffersStillInUse(summary.log_buffers_in_use).into());
+        }
+
+        // Unmaps CnC file
+        drop(self);
+
+        if summary.errors.is_empty() {
+            Ok(summary)
+        } else {
+            Err(AeronError::CloseIncomplete(Box::new(summary)))
+        }
+    }
+
+    fn stop_conductor(&mut self) {
+        if self.context.use_conductor_agent_invoker() {
+            self.conductor_invoker.close();
+        } else if let Some(mut conductor_stopper) = self.conductor_stopper.take() {
+            conductor_stopper.stop();
+        }
+    }
+
     /**
      * Create an Aeron instance and connect to the media driver.
      * <p>
@@ -314,6 +396,22 @@ impl Aeron {
         )
     }
 
+    /**
+     * Add a new {@link Subscription} spying on the local publications of a UDP channel, without the messages
+     * having to go through the network.
+     *
+     * The spy channel is derived from the channel by adding the aeron-spy prefix. Spying on IPC channels is not
+     * supported.
+     *
+     * @param channel  of the publications to spy on.
+     * @param stream_id within the channel scope.
+     * @return registration id for the subscription
+     */
+    pub fn add_spy_subscription(&mut self, channel: CString, stream_id: i32) -> Result<i64, AeronError> {
+        let spy_channel = ChannelUri::spy_channel(&channel.to_string_lossy())?;
+        self.add_subscription(CString::new(spy_channel).expect("CString conv error"), stream_id)
+    }
+
     /**
      * Retrieve the Subscription associated with the given registration_id.
      *
@@ -357,9 +455,8 @@ impl Aeron {
      * Allocate a counter on the media driver and return a {@link Counter} for it.
      *
      * @param type_id      for the counter.
-     * @param key_buffer   containing the optional key for the counter.
-     * @param key_length   of the key in the key_buffer.
-     * @param label       for the counter.
+     * @param key_buffer   containing the optional key for the counter, see CounterKeyBuilder.
+     * @param label        for the counter.
      * @return registration id for the Counter
      */
     pub fn add_counter(&mut self, type_id: i32, key_buffer: &[u8], label: &str) -> Result<i64, AeronError> {
@@ -468,6 +565,20 @@ impl Aeron {
             .remove_close_client_handler(handler);
     }
 
+    /**
+     * Add a handler to the list to be called when the channel endpoint of a publication or subscription goes
+     * into ERRORED state.
+     *
+     * @param handler to be added to the channel errored handlers list.
+     */
+    pub fn add_channel_errored_handler(&mut self, handler: Box<dyn OnChannelErrored>) {
+        let _ignored = self
+            .conductor
+            .lock()
+            .expect("Mutex poisoned")
+            .add_channel_errored_handler(handler);
+    }
+
     /**
      * Return the AgentInvoker for the client conductor.
      *
@@ -590,10 +701,6 @@ impl Aeron {
 
 impl Drop for Aeron {
     fn drop(&mut self) {
-        if self.context.use_conductor_agent_invoker() {
-            self.conductor_invoker.close();
-        } else {
-            self.conductor_stopper.as_mut().unwrap().stop();
-        }
+        self.stop_conductor();
     }
 }
@@ -0,0 +1,379 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::ffi::OsString;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use lazy_static::lazy_static;
+
+use crate::concurrent::atomic_buffer::AtomicBuffer;
+use crate::concurrent::atomic_counter::AtomicCounter;
+use crate::concurrent::counters::{CountersManager, CountersReader, COUNTER_LENGTH, METADATA_LENGTH};
+use crate::utils::errors::{AeronError, IllegalStateError};
+use crate::utils::memory_mapped_file::MemoryMappedFile;
+use crate::utils::types::Index;
+use crate::utils::{bit_utils, misc};
+
+/**
+ * Counters file published by an application so its counters can be read by aeron_stat and aeron_exporter
+ * the same way as the counters of the driver in the CnC file.
+ *
+ * File Layout
+ * <pre>
+ *  +-----------------------------+
+ *  |          Meta Data          |
+ *  +-----------------------------+
+ *  |   Counters Metadata Buffer  |
+ *  +-----------------------------+
+ *  |    Counters Values Buffer   |
+ *  +-----------------------------+
+ * </pre>
+ * <p>
+ * Meta Data Layout {@link #APP_COUNTERS_VERSION}
+ * <pre>
+ *   0                   1                   2                   3
+ *   0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+ *  +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+ *  |                  App Counters File Version                    |
+ *  +---------------------------------------------------------------+
+ *  |               Counters Metadata buffer length                 |
+ *  +---------------------------------------------------------------+
+ *  |                Counters Values buffer length                  |
+ *  +---------------------------------------------------------------+
+ *  |                         Padding                               |
+ *  +---------------------------------------------------------------+
+ *  |                       Start Timestamp                         |
+ *  |                                                               |
+ *  +---------------------------------------------------------------+
+ *  |                             PID                               |
+ *  |                                                               |
+ *  +---------------------------------------------------------------+
+ * </pre>
+ *
+ * The version is written last so readers can wait for a non zero version before reading the rest.
+ */
+pub const APP_COUNTERS_VERSION: i32 = 1;
+
+/// Time a freed counter is kept before its id is reused so readers can notice it was freed.
+pub const FREE_TO_REUSE_TIMEOUT_MS: u64 = 1000;
+
+#[derive(Copy, Clone)]
+#[repr(C, packed(4))]
+struct MetaDataDefn {
+    version: i32,
+    counter_metadata_buffer_length: i32,
+    counter_values_buffer_length: i32,
+    padding: i32,
+    start_timestamp: i64,
+    pid: i64,
+}
+
+lazy_static! {
+    pub static ref META_DATA_LENGTH: Index =
+        bit_utils::align(std::mem::size_of::<MetaDataDefn>() as Index, misc::CACHE_LINE_LENGTH * 2);
+}
+
+fn meta_data(file: &MemoryMappedFile) -> MetaDataDefn {
+    file.atomic_buffer(0, file.memory_size()).get::<MetaDataDefn>(0)
+}
+
+pub fn version_volatile(file: &MemoryMappedFile) -> i32 {
+    file.atomic_buffer(0, file.memory_size())
+        .get_volatile::<i32>(offset_of!(MetaDataDefn, version) as Index)
+}
+
+pub fn create_counter_metadata_buffer(file: &MemoryMappedFile) -> AtomicBuffer {
+    let meta_data = meta_data(file);
+
+    file.atomic_buffer(*META_DATA_LENGTH, meta_data.counter_metadata_buffer_length as Index)
+}
+
+pub fn create_counter_values_buffer(file: &MemoryMappedFile) -> AtomicBuffer {
+    let meta_data = meta_data(file);
+
+    file.atomic_buffer(
+        *META_DATA_LENGTH + meta_data.counter_metadata_buffer_length as Index,
+        meta_data.counter_values_buffer_length as Index,
+    )
+}
+
+pub fn start_timestamp(file: &MemoryMappedFile) -> i64 {
+    meta_data(file).start_timestamp
+}
+
+pub fn pid(file: &MemoryMappedFile) -> i64 {
+    meta_data(file).pid
+}
+
+/**
+ * Length of the file needed to hold the given number of counters.
+ */
+pub fn file_length(max_counters: i32) -> Index {
+    *META_DATA_LENGTH + max_counters * (METADATA_LENGTH + COUNTER_LENGTH)
+}
+
+/**
+ * Map an existing application counters file and check its version.
+ *
+ * @param path of the file created with AppCounters::create_file.
+ * @return mapped file to create the counter buffers from.
+ */
+pub fn map_existing<P: AsRef<Path> + Into<OsString>>(path: P) -> Result<MemoryMappedFile, AeronError> {
+    let file = MemoryMappedFile::map_existing(path, false)?;
+    let version = version_volatile(&file);
+
+    if version != APP_COUNTERS_VERSION {
+        return Err(IllegalStateError::AppCountersVersionNotSupported {
+            file_version: version,
+            app_version: APP_COUNTERS_VERSION,
+        }
+        .into());
+    }
+
+    Ok(file)
+}
+
+/**
+ * Counter allocated by AppCounters. It frees its record when dropped and keeps the counters file mapped
+ * until then, so it may outlive the AppCounters it was allocated by.
+ */
+pub struct AppCounter {
+    // dropped before the file as it frees its record in the file
+    atomic_counter: AtomicCounter,
+    _file: Arc<MemoryMappedFile>,
+}
+
+impl AppCounter {
+    /// Inherited from AtomicCounter
+    pub fn id(&self) -> i32 {
+        self.atomic_counter.id()
+    }
+
+    /// Inherited from AtomicCounter
+    pub fn increment(&self) {
+        self.atomic_counter.increment()
+    }
+
+    /// Inherited from AtomicCounter
+    pub fn increment_ordered(&self) {
+        self.atomic_counter.increment_ordered()
+    }
+
+    /// Inherited from AtomicCounter
+    pub fn set(&self, value: i64) {
+        self.atomic_counter.set(value)
+    }
+
+    /// Inherited from AtomicCounter
+    pub fn set_ordered(&self, value: i64) {
+        self.atomic_counter.set_ordered(value)
+    }
+
+    /// Inherited from AtomicCounter
+    pub fn set_weak(&self, value: i64) {
+        self.atomic_counter.set_weak(value)
+    }
+
+    /// Inherited from AtomicCounter
+    pub fn get_and_add(&self, value: i64) -> i64 {
+        self.atomic_counter.get_and_add(value)
+    }
+
+    /// Inherited from AtomicCounter
+    pub fn get_and_add_ordered(&self, increment: i64) -> i64 {
+        self.atomic_counter.get_and_add_ordered(increment)
+    }
+
+    /// Inherited from AtomicCounter
+    pub fn get_and_set(&self, value: i64) -> i64 {
+        self.atomic_counter.get_and_set(value)
+    }
+
+    /// Inherited from AtomicCounter
+    pub fn compare_and_set(&self, expected_value: i64, update_value: i64) -> bool {
+        self.atomic_counter.compare_and_set(expected_value, update_value)
+    }
+
+    /// Inherited from AtomicCounter
+    pub fn get(&self) -> i64 {
+        self.atomic_counter.get()
+    }
+
+    /// Inherited from AtomicCounter
+    pub fn get_weak(&self) -> i64 {
+        self.atomic_counter.get_weak()
+    }
+}
+
+/**
+ * Allocates counters of an application in a memory mapped counters file.
+ *
+ * Counters are allocated with the same record layout as the counters of the driver, so the file can be
+ * read with CountersReader by other processes. The file stays mapped while AppCounters or any of its
+ * counters is alive.
+ */
+pub struct AppCounters {
+    file: Arc<MemoryMappedFile>,
+    metadata_buffer: AtomicBuffer,
+    values_buffer: AtomicBuffer,
+    manager: Arc<Mutex<CountersManager>>,
+}
+
+impl AppCounters {
+    /**
+     * Create a new counters file for the given number of counters. An existing file is overwritten.
+     *
+     * @param path         of the file to create, e.g. in /dev/shm to avoid disk IO.
+     * @param max_counters number of counters the file can hold.
+     */
+    pub fn create_file<P: AsRef<Path> + Into<OsString>>(path: P, max_counters: i32) -> Result<Self, AeronError> {
+        let metadata_length = max_counters * METADATA_LENGTH;
+        let values_length = max_counters * COUNTER_LENGTH;
+        let length = file_length(max_counters);
+
+        let file = MemoryMappedFile::create_new(path, 0, length)?;
+        let buffer = file.atomic_buffer(0, length);
+        buffer.set_memory(0, length, 0);
+
+        buffer.put::<MetaDataDefn>(
+            0,
+            MetaDataDefn {
+                version: 0,
+                counter_metadata_buffer_length: metadata_length,
+                counter_values_buffer_length: values_length,
+                padding: 0,
+                start_timestamp: misc::unix_time_ms() as i64,
+                pid: std::process::id() as i64,
+            },
+        );
+        buffer.put_ordered::<i32>(offset_of!(MetaDataDefn, version) as Index, APP_COUNTERS_VERSION);
+
+        let metadata_buffer = create_counter_metadata_buffer(&file);
+        let values_buffer = create_counter_values_buffer(&file);
+
+        Ok(Self {
+            file: Arc::new(file),
+            metadata_buffer,
+            values_buffer,
+            manager: Arc::new(Mutex::new(CountersManager::new_opt(
+                metadata_buffer,
+                values_buffer,
+                misc::unix_time_ms,
+                FREE_TO_REUSE_TIMEOUT_MS,
+            ))),
+        })
+    }
+
+    /**
+     * Allocate a counter with the given type, key and label. Value of the counter starts at 0.
+     *
+     * @param type_id to categorise the counter, should not clash with type ids of the driver.
+     * @param key     identifying the counter to tools, at most MAX_KEY_LENGTH bytes.
+     * @param label   human readable label of the counter, at most MAX_LABEL_LENGTH bytes.
+     * @return counter which frees its record when dropped.
+     */
+    pub fn add_counter(&self, type_id: i32, key: &[u8], label: &str) -> Result<AppCounter, AeronError> {
+        let counter_id = self.manager.lock().expect("Mutex poisoned").allocate_opt(
+            type_id,
+            Some(key),
+            Option::<fn(&mut AtomicBuffer)>::None,
+            label,
+        )?;
+
+        Ok(AppCounter {
+            atomic_counter: AtomicCounter::new_opt(self.values_buffer, counter_id, self.manager.clone()),
+            _file: self.file.clone(),
+        })
+    }
+
+    pub fn counters_reader(&self) -> CountersReader {
+        CountersReader::new(self.metadata_buffer, self.values_buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::concurrent::counters::RECORD_ALLOCATED;
+
+    #[test]
+    fn should_read_counters_from_mapped_file() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let path = tmp_dir.path().join("app-counters.dat");
+        let app_counters = AppCounters::create_file(path.clone(), 4).unwrap();
+
+        let requests = app_counters.add_counter(2001, &7_i64.to_le_bytes(), "requests").unwrap();
+        let errors = app_counters.add_counter(2002, &[], "errors").unwrap();
+        requests.set_ordered(42);
+        errors.increment();
+
+        let file = map_existing(path).unwrap();
+        assert_eq!(pid(&file), std::process::id() as i64);
+
+        let reader = CountersReader::new(create_counter_metadata_buffer(&file), create_counter_values_buffer(&file));
+        let mut counters = Vec::new();
+        reader.for_each(|counter_id, type_id, key_buffer, label| {
+            counters.push((
+                type_id,
+                key_buffer.get::<i64>(0),
+                label.into_string().unwrap(),
+                reader.counter_value(counter_id).unwrap(),
+            ));
+        });
+
+        assert_eq!(
+            counters,
+            vec![(2001, 7, String::from("requests"), 42), (2002, 0, String::from("errors"), 1)]
+        );
+    }
+
+    #[test]
+    fn should_free_counter_on_drop_and_reject_when_full() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let app_counters = AppCounters::create_file(tmp_dir.path().join("app-counters.dat"), 1).unwrap();
+
+        let counter = app_counters.add_counter(2001, &[], "first").unwrap();
+        let counter_id = counter.id();
+        assert!(app_counters.add_counter(2001, &[], "second").is_err());
+
+        drop(counter);
+        assert_ne!(
+            app_counters.counters_reader().counter_state(counter_id).unwrap(),
+            RECORD_ALLOCATED
+        );
+    }
+
+    #[test]
+    fn should_keep_file_mapped_for_counter_outliving_app_counters() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let path = tmp_dir.path().join("app-counters.dat");
+        let app_counters = AppCounters::create_file(path.clone(), 2).unwrap();
+        let counter = app_counters.add_counter(2001, &[], "requests").unwrap();
+        let counter_id = counter.id();
+
+        drop(app_counters);
+        counter.increment();
+        counter.set_ordered(7);
+        assert_eq!(counter.get(), 7);
+        drop(counter);
+
+        let file = map_existing(path).unwrap();
+        let reader = CountersReader::new(create_counter_metadata_buffer(&file), create_counter_values_buffer(&file));
+        assert_eq!(reader.counter_value(counter_id).unwrap(), 7);
+        assert_ne!(reader.counter_state(counter_id).unwrap(), RECORD_ALLOCATED);
+    }
+}
@@ -0,0 +1,238 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::fs::File;
+use std::io::BufReader;
+use std::process;
+
+use aeron_rs::packet_dissector::{PacketDissector, SessionKey, SessionStats};
+use aeron_rs::pcap::{PcapReader, UdpDatagram};
+use aeron_rs::protocol::frame::Frame;
+use chrono::{Local, TimeZone};
+
+struct CmdOpts {
+    capture_file: Option<String>,
+    ports: Vec<u16>,
+    stream_id: Option<i32>,
+    session_id: Option<i32>,
+    print_frames: bool,
+    print_stats: bool,
+}
+
+impl Default for CmdOpts {
+    fn default() -> Self {
+        Self {
+            capture_file: None,
+            ports: Vec::new(),
+            stream_id: None,
+            session_id: None,
+            print_frames: true,
+            print_stats: false,
+        }
+    }
+}
+
+fn usage() -> String {
+    String::from(
+        "Usage: aeron_dissect [options] <capture file>\n\
+         \n\
+         Decodes Aeron frames of UDP datagrams in a pcap or pcapng capture, e.g. written by tcpdump -w.\n\
+         \n\
+         Options:\n\
+         \x20 -p, --port <port>          only datagrams from or to the port, may be repeated\n\
+         \x20 -s, --stream <id>          only frames of the stream id\n\
+         \x20 -e, --session <id>         only frames of the session id\n\
+         \x20 -n, --no-frames            don't print a summary of each frame\n\
+         \x20 -S, --stats                print statistics per session at the end\n\
+         \x20 -h, --help                 print this help",
+    )
+}
+
+fn exit_with_error(message: &str) -> ! {
+    eprintln!("{}\n\n{}", message, usage());
+    process::exit(1);
+}
+
+fn parse_number<T: std::str::FromStr>(option: &str, value: Option<String>) -> T {
+    value
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_else(|| exit_with_error(&format!("{} expects a number", option)))
+}
+
+fn parse_cmd_line() -> CmdOpts {
+    let mut opts = CmdOpts::default();
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-p" | "--port" => opts.ports.push(parse_number("--port", args.next())),
+            "-s" | "--stream" => opts.stream_id = Some(parse_number("--stream", args.next())),
+            "-e" | "--session" => opts.session_id = Some(parse_number("--session", args.next())),
+            "-n" | "--no-frames" => opts.print_frames = false,
+            "-S" | "--stats" => opts.print_stats = true,
+            "-h" | "--help" => {
+                println!("{}", usage());
+                process::exit(0);
+            },
+            _ if arg.starts_with('-') => exit_with_error(&format!("Unknown option: {}", arg)),
+            _ if opts.capture_file.is_none() => opts.capture_file = Some(arg),
+            _ => exit_with_error(&format!("Unexpected argument: {}", arg)),
+        }
+    }
+
+    opts
+}
+
+impl CmdOpts {
+    fn is_datagram_selected(&self, datagram: &UdpDatagram) -> bool {
+        self.ports.is_empty()
+            || self.ports.contains(&datagram.source.port())
+            || self.ports.contains(&datagram.destination.port())
+    }
+
+    fn is_session_selected(&self, key: Option<SessionKey>) -> bool {
+        if self.stream_id.is_none() && self.session_id.is_none() {
+            return true;
+        }
+
+        key.is_some_and(|key| {
+            self.stream_id.is_none_or(|stream_id| stream_id == key.stream_id)
+                && self.session_id.is_none_or(|session_id| session_id == key.session_id)
+        })
+    }
+}
+
+fn format_timestamp(timestamp_ns: i64) -> String {
+    Local.timestamp_nanos(timestamp_ns).format("%Y-%m-%d %H:%M:%S%.6f").to_string()
+}
+
+fn format_option<T: ToString>(value: Option<T>) -> String {
+    value.map_or_else(String::new, |value| value.to_string())
+}
+
+fn print_stats(settings: &CmdOpts, dissector: &PacketDissector) {
+    let sessions: Vec<(&SessionKey, &SessionStats)> = dissector
+        .sessions()
+        .iter()
+        .filter(|(key, _stats)| settings.is_session_selected(Some(**key)))
+        .collect();
+
+    println!(
+        "\nDATAGRAMS: {}, MALFORMED: {}\n",
+        dissector.datagrams(),
+        dissector.malformed_datagrams()
+    );
+    println!(
+        "SESSION_ID, STREAM_ID, DATA_FRAMES, DATA_BYTES, PAD_FRAMES, HEARTBEATS, EOS, SETUPS, GAPS, GAP_BYTES, \
+         RETRANSMITS, RETRANSMIT_BYTES, NAKS, STATUS_MESSAGES, MIN_RECEIVER_WINDOW, MAX_RECEIVER_WINDOW, \
+         LAST_RECEIVER_WINDOW, RTT_MEASUREMENTS, ERRORS"
+    );
+
+    for (key, stats) in &sessions {
+        println!(
+            "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            key.session_id,
+            key.stream_id,
+            stats.data_frames,
+            stats.data_bytes,
+            stats.pad_frames,
+            stats.heartbeats,
+            stats.end_of_stream,
+            stats.setups,
+            stats.gaps,
+            stats.gap_bytes,
+            stats.retransmits,
+            stats.retransmit_bytes,
+            stats.naks,
+            stats.status_messages,
+            format_option(stats.min_receiver_window),
+            format_option(stats.max_receiver_window),
+            format_option(stats.last_receiver_window),
+            stats.rtt_measurements,
+            stats.errors
+        );
+    }
+
+    if sessions.iter().any(|(_key, stats)| !stats.nak_ranges.is_empty()) {
+        println!("\nSESSION_ID, STREAM_ID, NAK_RANGE (TERM_ID:TERM_OFFSET+LENGTH), COUNT");
+
+        for (key, stats) in &sessions {
+            for (range, count) in &stats.nak_ranges {
+                println!("{},{},{},{}", key.session_id, key.stream_id, range, count);
+            }
+        }
+    }
+}
+
+fn main() {
+    pretty_env_logger::init();
+
+    let settings = parse_cmd_line();
+    let capture_file = settings
+        .capture_file
+        .clone()
+        .unwrap_or_else(|| exit_with_error("Capture file is required"));
+
+    let file = File::open(&capture_file)
+        .unwrap_or_else(|err| exit_with_error(&format!("Can't open {}: {}", capture_file, err)));
+    let reader = PcapReader::new(BufReader::new(file))
+        .unwrap_or_else(|err| exit_with_error(&format!("Can't read {}: {}", capture_file, err)));
+
+    let mut dissector = PacketDissector::new();
+
+    for datagram in reader {
+        let datagram = match datagram {
+            Ok(datagram) => datagram,
+            Err(err) => {
+                eprintln!("Stopped reading {}: {}", capture_file, err);
+                break;
+            }
+        };
+
+        if !settings.is_datagram_selected(&datagram) {
+            continue;
+        }
+
+        let prefix = format!(
+            "{} {} -> {}",
+            format_timestamp(datagram.timestamp_ns),
+            datagram.source,
+            datagram.destination
+        );
+        let print_frames = settings.print_frames;
+
+        let result = dissector.dissect(&datagram.payload, |frame: &Frame| {
+            if print_frames && settings.is_session_selected(SessionKey::of(frame)) {
+                println!("{} {}", prefix, frame);
+            }
+        });
+
+        if let Err(err) = result {
+            if print_frames && settings.stream_id.is_none() && settings.session_id.is_none() {
+                let truncated = if datagram.payload.len() < datagram.original_length {
+                    " (truncated by capture)"
+                } else {
+                    ""
+                };
+                println!("{} malformed {} bytes{}: {}", prefix, datagram.original_length, truncated, err);
+            }
+        }
+    }
+
+    if settings.print_stats {
+        print_stats(&settings, &dissector);
+    }
+}
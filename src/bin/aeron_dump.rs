@@ -0,0 +1,284 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::ffi::CString;
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use aeron_rs::aeron::Aeron;
+use aeron_rs::concurrent::atomic_buffer::AtomicBuffer;
+use aeron_rs::concurrent::logbuffer::header::Header;
+use aeron_rs::concurrent::strategies::{SleepingIdleStrategy, Strategy};
+use aeron_rs::context::Context;
+use aeron_rs::example_config::{DEFAULT_CHANNEL, DEFAULT_STREAM_ID};
+use aeron_rs::fragment_assembler::FragmentAssembler;
+use aeron_rs::image::Image;
+use aeron_rs::utils::errors::AeronError;
+use aeron_rs::utils::misc::hex_dump;
+use aeron_rs::utils::types::Index;
+use lazy_static::lazy_static;
+use regex::bytes::Regex;
+
+lazy_static! {
+    pub static ref RUNNING: AtomicBool = AtomicBool::from(true);
+}
+
+fn sig_int_handler() {
+    RUNNING.store(false, Ordering::SeqCst);
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum PayloadFormat {
+    None,
+    Hex,
+    Utf8,
+}
+
+struct CmdOpts {
+    base_path: String,
+    channel: String,
+    stream_id: i32,
+    spy: bool,
+    reassemble: bool,
+    payload_format: PayloadFormat,
+    max_bytes: usize,
+    include_filters: Vec<Regex>,
+    exclude_filters: Vec<Regex>,
+    fragment_limit: i32,
+}
+
+impl Default for CmdOpts {
+    fn default() -> Self {
+        Self {
+            base_path: Context::default_aeron_path(),
+            channel: String::from(DEFAULT_CHANNEL),
+            stream_id: DEFAULT_STREAM_ID.parse().unwrap(),
+            spy: false,
+            reassemble: false,
+            payload_format: PayloadFormat::Utf8,
+            max_bytes: 256,
+            include_filters: Vec::new(),
+            exclude_filters: Vec::new(),
+            fragment_limit: 10,
+        }
+    }
+}
+
+fn usage() -> String {
+    String::from(
+        "Usage: aeron_dump [options]\n\
+         \n\
+         Subscribes to a channel and stream, or spies on the local publications of a UDP channel, and prints every\n\
+         message with its header. Filters are matched against the whole payload, a message is printed when it\n\
+         matches any of the --grep filters and none of the --exclude filters.\n\
+         \n\
+         Options:\n\
+         \x20 -d, --dir <path>            Aeron directory of the driver (default: /dev/shm/aeron-$USER)\n\
+         \x20 -c, --channel <uri>         channel to subscribe to (default: aeron:udp?endpoint=localhost:40123)\n\
+         \x20 -s, --stream <id>           stream id (default: 1001)\n\
+         \x20 -S, --spy                   spy on the local publications of the channel instead of subscribing\n\
+         \x20 -a, --reassemble            reassemble fragmented messages before printing them\n\
+         \x20 -p, --payload <format>      hex, utf8 or none (default: utf8)\n\
+         \x20 -m, --max-bytes <bytes>     bytes of the payload to print, 0 for all (default: 256)\n\
+         \x20 -g, --grep <regex>          only messages with a matching payload, may be repeated\n\
+         \x20 -x, --exclude <regex>       skip messages with a matching payload, may be repeated\n\
+         \x20 -f, --fragments <count>     fragment limit of a poll (default: 10)\n\
+         \x20 -h, --help                  print this help",
+    )
+}
+
+fn exit_with_error(message: &str) -> ! {
+    eprintln!("{}\n\n{}", message, usage());
+    process::exit(1);
+}
+
+fn parse_number<T: std::str::FromStr>(option: &str, value: Option<String>) -> T {
+    value
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_else(|| exit_with_error(&format!("{} expects a number", option)))
+}
+
+fn parse_regex(option: &str, value: Option<String>) -> Regex {
+    let value = value.unwrap_or_else(|| exit_with_error(&format!("{} expects a regex", option)));
+    Regex::new(&value).unwrap_or_else(|err| exit_with_error(&format!("Invalid regex for {}: {}", option, err)))
+}
+
+fn parse_cmd_line() -> CmdOpts {
+    let mut opts = CmdOpts::default();
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-d" | "--dir" => opts.base_path = args.next().unwrap_or_else(|| exit_with_error("--dir expects a path")),
+            "-c" | "--channel" => opts.channel = args.next().unwrap_or_else(|| exit_with_error("--channel expects a uri")),
+            "-s" | "--stream" => opts.stream_id = parse_number("--stream", args.next()),
+            "-S" | "--spy" => opts.spy = true,
+            "-a" | "--reassemble" => opts.reassemble = true,
+            "-p" | "--payload" => {
+                opts.payload_format = match args.next().as_deref() {
+                    Some("hex") => PayloadFormat::Hex,
+                    Some("utf8") => PayloadFormat::Utf8,
+                    Some("none") => PayloadFormat::None,
+                    _ => exit_with_error("--payload expects hex, utf8 or none"),
+                }
+            },
+            "-m" | "--max-bytes" => opts.max_bytes = parse_number("--max-bytes", args.next()),
+            "-g" | "--grep" => opts.include_filters.push(parse_regex("--grep", args.next())),
+            "-x" | "--exclude" => opts.exclude_filters.push(parse_regex("--exclude", args.next())),
+            "-f" | "--fragments" => opts.fragment_limit = parse_number("--fragments", args.next()),
+            "-h" | "--help" => {
+                println!("{}", usage());
+                process::exit(0);
+            },
+            _ => exit_with_error(&format!("Unknown option: {}", arg)),
+        }
+    }
+
+    opts
+}
+
+fn str_to_c(val: &str) -> CString {
+    CString::new(val).unwrap_or_else(|_| exit_with_error(&format!("Invalid channel: {}", val)))
+}
+
+fn available_image_handler(image: &Image) {
+    println!(
+        "Available image session_id={} position={} from {}",
+        image.session_id(),
+        image.position(),
+        image.source_identity().to_string_lossy()
+    );
+}
+
+fn unavailable_image_handler(image: &Image) {
+    println!(
+        "Unavailable image session_id={} position={}",
+        image.session_id(),
+        image.position()
+    );
+}
+
+fn error_handler(error: AeronError) {
+    eprintln!("Error: {}", error);
+}
+
+fn matches_filters(settings: &CmdOpts, payload: &[u8]) -> bool {
+    (settings.include_filters.is_empty() || settings.include_filters.iter().any(|regex| regex.is_match(payload)))
+        && !settings.exclude_filters.iter().any(|regex| regex.is_match(payload))
+}
+
+fn print_message(settings: &CmdOpts, buffer: &AtomicBuffer, offset: Index, length: Index, header: &Header) {
+    let payload = buffer.as_sub_slice(offset, length);
+    if !matches_filters(settings, payload) {
+        return;
+    }
+
+    println!(
+        "session_id={} position={} flags={:#04x} reserved_value={} length={}",
+        header.session_id(),
+        header.position(),
+        header.flags(),
+        header.reserved_value(),
+        length
+    );
+
+    let shown = if settings.max_bytes == 0 {
+        payload
+    } else {
+        &payload[..payload.len().min(settings.max_bytes)]
+    };
+    let truncated = if shown.len() < payload.len() {
+        format!(" ... ({} of {} bytes)", shown.len(), payload.len())
+    } else {
+        String::new()
+    };
+
+    match settings.payload_format {
+        PayloadFormat::Hex if !shown.is_empty() => println!("{}{}", hex_dump(shown), truncated),
+        PayloadFormat::Utf8 if !shown.is_empty() => {
+            println!("  {}{}", String::from_utf8_lossy(shown).escape_debug(), truncated)
+        },
+        _ => {},
+    }
+}
+
+fn main() {
+    pretty_env_logger::init();
+    ctrlc::set_handler(move || {
+        sig_int_handler();
+    })
+    .expect("Error setting Ctrl-C handler");
+
+    let settings = parse_cmd_line();
+
+    let mut context = Context::new();
+    context.set_aeron_dir(settings.base_path.clone());
+    context.set_available_image_handler(Box::new(available_image_handler));
+    context.set_unavailable_image_handler(Box::new(unavailable_image_handler));
+    context.set_error_handler(Box::new(error_handler));
+
+    let mut aeron = Aeron::new(context).unwrap_or_else(|err| exit_with_error(&format!("Can't connect to driver: {}", err)));
+
+    let channel = str_to_c(&settings.channel);
+    let subscription_id = if settings.spy {
+        aeron.add_spy_subscription(channel, settings.stream_id)
+    } else {
+        aeron.add_subscription(channel, settings.stream_id)
+    }
+    .unwrap_or_else(|err| exit_with_error(&format!("Can't add subscription: {}", err)));
+
+    let mut subscription = aeron.find_subscription(subscription_id);
+    while subscription.is_err() && RUNNING.load(Ordering::SeqCst) {
+        std::thread::yield_now();
+        subscription = aeron.find_subscription(subscription_id);
+    }
+    let subscription = match subscription {
+        Ok(subscription) => subscription,
+        Err(_) => return,
+    };
+
+    println!(
+        "Dumping {} on stream {}",
+        subscription.lock().expect("Mutex poisoned").channel().to_string_lossy(),
+        settings.stream_id
+    );
+
+    let idle_strategy = SleepingIdleStrategy::new(1000);
+    let mut message_handler = |buffer: &AtomicBuffer, offset: Index, length: Index, header: &Header| {
+        print_message(&settings, buffer, offset, length, header)
+    };
+
+    if settings.reassemble {
+        let mut fragment_assembler = FragmentAssembler::new(&mut message_handler, None);
+        let mut fragment_handler = fragment_assembler.handler();
+
+        while RUNNING.load(Ordering::SeqCst) {
+            let fragments_read = subscription
+                .lock()
+                .expect("Mutex poisoned")
+                .poll(&mut fragment_handler, settings.fragment_limit);
+            idle_strategy.idle_opt(fragments_read);
+        }
+    } else {
+        while RUNNING.load(Ordering::SeqCst) {
+            let fragments_read = subscription
+                .lock()
+                .expect("Mutex poisoned")
+                .poll(&mut message_handler, settings.fragment_limit);
+            idle_strategy.idle_opt(fragments_read);
+        }
+    }
+}
@@ -0,0 +1,145 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::net::TcpListener;
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use aeron_rs::app_counters;
+use aeron_rs::cnc_file_descriptor::{self, CNC_FILE, CNC_VERSION};
+use aeron_rs::concurrent::counters::CountersReader;
+use aeron_rs::context::Context;
+use aeron_rs::prometheus_exporter;
+use aeron_rs::utils::memory_mapped_file::MemoryMappedFile;
+use aeron_rs::utils::misc::{semantic_version_major, semantic_version_to_string};
+use lazy_static::lazy_static;
+
+lazy_static! {
+    pub static ref RUNNING: AtomicBool = AtomicBool::from(true);
+}
+
+fn sig_int_handler() {
+    RUNNING.store(false, Ordering::SeqCst);
+}
+
+struct CmdOpts {
+    base_path: String,
+    counters_file: Option<String>,
+    listen_address: String,
+}
+
+impl Default for CmdOpts {
+    fn default() -> Self {
+        Self {
+            base_path: Context::default_aeron_path(),
+            counters_file: None,
+            listen_address: String::from("127.0.0.1:9464"),
+        }
+    }
+}
+
+fn usage() -> &'static str {
+    "Usage: aeron_exporter [-d <aeron dir>] [-c <app counters file>] [-l <listen address, default 127.0.0.1:9464>]"
+}
+
+fn exit_with_error(message: &str) -> ! {
+    eprintln!("{}\n\n{}", message, usage());
+    process::exit(1);
+}
+
+fn parse_cmd_line() -> CmdOpts {
+    let mut opts = CmdOpts::default();
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-d" | "--dir" => opts.base_path = args.next().unwrap_or_else(|| exit_with_error("--dir expects a path")),
+            "-c" | "--counters-file" => {
+                opts.counters_file = Some(
+                    args.next()
+                        .unwrap_or_else(|| exit_with_error("--counters-file expects a path")),
+                )
+            },
+            "-l" | "--listen" => {
+                opts.listen_address = args.next().unwrap_or_else(|| exit_with_error("--listen expects an address"))
+            },
+            _ => {
+                println!("{}", usage());
+                process::exit(if arg == "-h" || arg == "--help" { 0 } else { 1 });
+            },
+        }
+    }
+
+    opts
+}
+
+fn map_cnc_counters(filename: String) -> (String, MemoryMappedFile, CountersReader) {
+    let cnc_file = MemoryMappedFile::map_existing(filename.clone(), false).expect("Can't map file");
+    let cnc_version = cnc_file_descriptor::cnc_version_volatile(&cnc_file);
+
+    if semantic_version_major(cnc_version) != semantic_version_major(CNC_VERSION) {
+        panic!(
+            "CNC version is not supported: file={} app={}",
+            semantic_version_to_string(cnc_version),
+            semantic_version_to_string(CNC_VERSION)
+        );
+    }
+
+    let counters = CountersReader::new(
+        cnc_file_descriptor::create_counter_metadata_buffer(&cnc_file),
+        cnc_file_descriptor::create_counter_values_buffer(&cnc_file),
+    );
+
+    (filename, cnc_file, counters)
+}
+
+/// Map a counters file published by an application with AppCounters.
+fn map_app_counters(filename: String) -> (String, MemoryMappedFile, CountersReader) {
+    let file = app_counters::map_existing(filename.clone()).expect("Can't map app counters file");
+
+    let counters = CountersReader::new(
+        app_counters::create_counter_metadata_buffer(&file),
+        app_counters::create_counter_values_buffer(&file),
+    );
+
+    (filename, file, counters)
+}
+
+fn main() {
+    pretty_env_logger::init();
+    ctrlc::set_handler(move || {
+        sig_int_handler();
+    })
+    .expect("Error setting Ctrl-C handler");
+
+    let settings = parse_cmd_line();
+    let (filename, _file, counters) = match settings.counters_file {
+        Some(counters_file) => map_app_counters(counters_file),
+        None => map_cnc_counters(settings.base_path + "/" + CNC_FILE),
+    };
+
+    let listener = TcpListener::bind(&settings.listen_address).expect("Can't bind listen address");
+
+    println!(
+        "Exporting counters of {} on http://{}/metrics",
+        filename, settings.listen_address
+    );
+
+    prometheus_exporter::serve(listener, &counters, &RUNNING, |err| eprintln!("Request failed: {}", err))
+        .expect("Can't serve metrics");
+
+    println!("Exiting...")
+}
@@ -0,0 +1,165 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::net::SocketAddr;
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use aeron_rs::concurrent::agent_runner::Agent;
+use aeron_rs::concurrent::strategies::{SleepingIdleStrategy, Strategy};
+use aeron_rs::loss_proxy::config::{ProxyConfig, Route};
+use aeron_rs::loss_proxy::LossProxy;
+use lazy_static::lazy_static;
+
+lazy_static! {
+    pub static ref RUNNING: AtomicBool = AtomicBool::from(true);
+}
+
+fn sig_int_handler() {
+    RUNNING.store(false, Ordering::SeqCst);
+}
+
+#[derive(Default)]
+struct CmdOpts {
+    config_file: Option<String>,
+    routes: Vec<Route>,
+    seed: Option<u64>,
+}
+
+fn usage() -> String {
+    String::from(
+        "Usage: aeron_loss_proxy [options]\n\
+         \n\
+         Forwards UDP datagrams between endpoints and drops, delays, duplicates or reorders the Aeron frames\n\
+         matching the rules of the config file.\n\
+         \n\
+         Options:\n\
+         \x20 -c, --config <file>             routes, seed and rules, one per line:\n\
+         \x20                                 route <listen> <target>\n\
+         \x20                                 seed <number>\n\
+         \x20                                 drop|delay|duplicate|reorder [type=<types>] [stream=<id>] [session=<id>]\n\
+         \x20                                     [dir=forward|reverse] [rate=<0..1>] [delay=<duration>]\n\
+         \x20                                     [jitter=<duration>] [copies=<number>] [hold=<duration>]\n\
+         \x20 -r, --route <listen> <target>   forward datagrams received on listen to target, may be repeated\n\
+         \x20 -s, --seed <number>             seed of the random rate of rules\n\
+         \x20 -h, --help                      print this help",
+    )
+}
+
+fn exit_with_error(message: &str) -> ! {
+    eprintln!("{}\n\n{}", message, usage());
+    process::exit(1);
+}
+
+fn parse_number<T: std::str::FromStr>(option: &str, value: Option<String>) -> T {
+    value
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_else(|| exit_with_error(&format!("{} expects a number", option)))
+}
+
+fn parse_address(value: Option<String>) -> SocketAddr {
+    value
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_else(|| exit_with_error("--route expects <listen> and <target> as ip:port"))
+}
+
+fn parse_cmd_line() -> CmdOpts {
+    let mut opts = CmdOpts::default();
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-c" | "--config" => {
+                opts.config_file = Some(
+                    args.next()
+                        .unwrap_or_else(|| exit_with_error("--config expects a file")),
+                )
+            },
+            "-r" | "--route" => {
+                let listen = parse_address(args.next());
+                let target = parse_address(args.next());
+                opts.routes.push(Route { listen, target });
+            },
+            "-s" | "--seed" => opts.seed = Some(parse_number("--seed", args.next())),
+            "-h" | "--help" => {
+                println!("{}", usage());
+                process::exit(0);
+            },
+            _ => exit_with_error(&format!("Unknown option: {}", arg)),
+        }
+    }
+
+    opts
+}
+
+fn main() {
+    pretty_env_logger::init();
+    ctrlc::set_handler(move || {
+        sig_int_handler();
+    })
+    .expect("Error setting Ctrl-C handler");
+
+    let settings = parse_cmd_line();
+    let mut config = match &settings.config_file {
+        Some(config_file) => ProxyConfig::from_file(config_file)
+            .unwrap_or_else(|err| exit_with_error(&format!("Can't read {}: {}", config_file, err))),
+        None => ProxyConfig::default(),
+    };
+    config.routes.extend(settings.routes);
+    if settings.seed.is_some() {
+        config.seed = settings.seed;
+    }
+
+    if config.routes.is_empty() {
+        exit_with_error("At least one route is required");
+    }
+
+    let mut proxy =
+        LossProxy::from_config(&config).unwrap_or_else(|err| exit_with_error(&format!("Can't start proxy: {}", err)));
+
+    for route in &config.routes {
+        println!("Forwarding {} -> {}", route.listen, route.target);
+    }
+    println!("Applying {} rules", config.rules.len());
+
+    let idle_strategy = SleepingIdleStrategy::new(1);
+
+    while RUNNING.load(Ordering::SeqCst) {
+        match proxy.do_work() {
+            Ok(work_count) => idle_strategy.idle_opt(work_count),
+            Err(err) => {
+                eprintln!("Proxy failed: {}", err);
+                break;
+            },
+        }
+    }
+
+    proxy.on_close().expect("Can't close proxy");
+
+    let stats = proxy.stats();
+    println!(
+        "\nDATAGRAMS: {}, UNDECODED: {}, FRAMES: {}, FORWARDED: {}, DROPPED: {}, DELAYED: {}, DUPLICATED: {}, \
+         REORDERED: {}",
+        stats.datagrams_received,
+        stats.undecoded_datagrams,
+        stats.frames_received,
+        stats.frames_forwarded,
+        stats.frames_dropped,
+        stats.frames_delayed,
+        stats.frames_duplicated,
+        stats.frames_reordered
+    );
+}
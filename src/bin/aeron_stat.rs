@@ -14,17 +14,22 @@
  * limitations under the License.
  */
 
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use std::{process, thread};
 
+use aeron_rs::app_counters;
 use aeron_rs::cnc_file_descriptor;
+use aeron_rs::concurrent::atomic_buffer::AtomicBuffer;
 use aeron_rs::concurrent::counters::CountersReader;
 use aeron_rs::context::Context;
+use aeron_rs::driver_counters::DriverCounter;
 use aeron_rs::utils::memory_mapped_file::MemoryMappedFile;
-use aeron_rs::utils::misc::{semantic_version_major, semantic_version_to_string};
+use aeron_rs::utils::misc::{csv_escape, json_escape, semantic_version_major, semantic_version_to_string, unix_time_ms};
 use chrono::Local;
 use lazy_static::lazy_static;
+use regex::Regex;
 
 lazy_static! {
     pub static ref RUNNING: AtomicBool = AtomicBool::from(true);
@@ -34,22 +39,110 @@ fn sig_int_handler() {
     RUNNING.store(false, Ordering::SeqCst);
 }
 
+#[derive(PartialEq)]
+enum OutputFormat {
+    Text,
+    JsonLines,
+    Csv,
+}
+
 struct CmdOpts {
     base_path: String,
+    counters_file: Option<String>,
     update_interval_ms: u64,
+    label_filter: Option<Regex>,
+    type_filter: Option<Regex>,
+    id_filter: Option<Regex>,
+    single_shot: bool,
+    format: OutputFormat,
 }
 
 impl Default for CmdOpts {
     fn default() -> Self {
         Self {
             base_path: Context::default_aeron_path(),
+            counters_file: None,
             update_interval_ms: 1000,
+            label_filter: None,
+            type_filter: None,
+            id_filter: None,
+            single_shot: false,
+            format: OutputFormat::Text,
         }
     }
 }
 
+fn usage() -> String {
+    String::from(
+        "Usage: aeron_stat [options]\n\
+         \n\
+         Options:\n\
+         \x20 -d, --dir <path>           Aeron directory of the driver (default: /dev/shm/aeron-$USER)\n\
+         \x20 -c, --counters-file <file> read an application counters file written by AppCounters instead of the CnC file\n\
+         \x20 -u, --interval-ms <ms>     update interval (default: 1000)\n\
+         \x20 -l, --label <regex>        show only counters with matching label\n\
+         \x20 -t, --type <regex>         show only counters with matching type id or type name (e.g. 'sub-pos|snd-pos')\n\
+         \x20 -i, --id <regex>           show only counters with matching counter id\n\
+         \x20 -1, --once                 print counters once and exit\n\
+         \x20 -f, --format <format>      output format: text, json (JSON lines) or csv (default: text)\n\
+         \x20 -h, --help                 print this help",
+    )
+}
+
+fn exit_with_error(message: &str) -> ! {
+    eprintln!("{}\n\n{}", message, usage());
+    process::exit(1);
+}
+
+fn parse_regex(option: &str, value: Option<String>) -> Option<Regex> {
+    let value = value.unwrap_or_else(|| exit_with_error(&format!("{} expects a regex", option)));
+
+    match Regex::new(&value) {
+        Ok(regex) => Some(regex),
+        Err(err) => exit_with_error(&format!("Invalid regex for {}: {}", option, err)),
+    }
+}
+
 fn parse_cmd_line() -> CmdOpts {
-    CmdOpts::default()
+    let mut opts = CmdOpts::default();
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-d" | "--dir" => opts.base_path = args.next().unwrap_or_else(|| exit_with_error("--dir expects a path")),
+            "-c" | "--counters-file" => {
+                opts.counters_file = Some(
+                    args.next()
+                        .unwrap_or_else(|| exit_with_error("--counters-file expects a file")),
+                )
+            },
+            "-u" | "--interval-ms" => {
+                opts.update_interval_ms = args
+                    .next()
+                    .and_then(|value| value.parse().ok())
+                    .unwrap_or_else(|| exit_with_error("--interval-ms expects a number of milliseconds"))
+            },
+            "-l" | "--label" => opts.label_filter = parse_regex("--label", args.next()),
+            "-t" | "--type" => opts.type_filter = parse_regex("--type", args.next()),
+            "-i" | "--id" => opts.id_filter = parse_regex("--id", args.next()),
+            "-1" | "--once" => opts.single_shot = true,
+            "-f" | "--format" => {
+                opts.format = match args.next().as_deref() {
+                    Some("text") => OutputFormat::Text,
+                    Some("json") => OutputFormat::JsonLines,
+                    Some("csv") => OutputFormat::Csv,
+                    _ => exit_with_error("--format expects one of: text, json, csv"),
+                }
+            },
+            "-h" | "--help" => {
+                println!("{}", usage());
+                process::exit(0);
+            },
+            _ => exit_with_error(&format!("Unknown option: {}", arg)),
+        }
+    }
+
+    opts
 }
 
 fn get_date() -> String {
@@ -57,18 +150,131 @@ fn get_date() -> String {
     Local::now().to_string()
 }
 
-fn main() {
-    pretty_env_logger::init();
-    ctrlc::set_handler(move || {
-        println!("received Ctrl+C!");
-        sig_int_handler();
-    })
-    .expect("Error setting Ctrl-C handler");
+struct CounterSample {
+    id: i32,
+    type_id: i32,
+    type_name: &'static str,
+    label: String,
+    value: i64,
+    delta: Option<i64>,
+    rate_per_sec: Option<f64>,
+}
 
-    let settings = parse_cmd_line();
-    let filename = settings.base_path + "/" + cnc_file_descriptor::CNC_FILE;
+impl CmdOpts {
+    fn is_selected(&self, id: i32, type_id: i32, type_name: &str, label: &str) -> bool {
+        self.label_filter.as_ref().is_none_or(|regex| regex.is_match(label))
+            && self
+                .type_filter
+                .as_ref()
+                .is_none_or(|regex| regex.is_match(&type_id.to_string()) || regex.is_match(type_name))
+            && self.id_filter.as_ref().is_none_or(|regex| regex.is_match(&id.to_string()))
+    }
+}
+
+/// Previous values are keyed by counter id and label as counter ids are reused after counters are freed.
+type PreviousValues = HashMap<i32, (String, i64)>;
 
-    println!("Using file: {}", filename);
+fn sample_counters(
+    settings: &CmdOpts,
+    counters: &CountersReader,
+    previous: &PreviousValues,
+    elapsed: Option<Duration>,
+) -> Vec<CounterSample> {
+    let mut samples = Vec::new();
+
+    counters.for_each(|counter_id, type_id, key_buffer, label| {
+        let type_name = DriverCounter::decode(type_id, key_buffer).type_name();
+        let label = label.to_string_lossy().into_owned();
+
+        if !settings.is_selected(counter_id, type_id, type_name, &label) {
+            return;
+        }
+
+        let value = counters.counter_value(counter_id).unwrap_or_default() as i64;
+        let delta = match previous.get(&counter_id) {
+            Some((previous_label, previous_value)) if *previous_label == label => Some(value.wrapping_sub(*previous_value)),
+            _ => None,
+        };
+        let rate_per_sec = match (delta, elapsed) {
+            (Some(delta), Some(elapsed)) if elapsed.as_secs_f64() > 0.0 => Some(delta as f64 / elapsed.as_secs_f64()),
+            _ => None,
+        };
+
+        samples.push(CounterSample {
+            id: counter_id,
+            type_id,
+            type_name,
+            label,
+            value,
+            delta,
+            rate_per_sec,
+        });
+    });
+
+    samples
+}
+
+fn optional_to_string<T: ToString>(value: Option<T>, none: &str) -> String {
+    value.map_or_else(|| String::from(none), |value| value.to_string())
+}
+
+fn print_text(header: &str, samples: &[CounterSample]) {
+    println!("[{}] - {}", get_date(), header);
+    println!("===========================");
+
+    for sample in samples {
+        println!(
+            "{:>3}:{:>20} {:>14} {:>16}/s - {}",
+            sample.id,
+            sample.value,
+            optional_to_string(sample.delta.map(|delta| format!("{:+}", delta)), "-"),
+            optional_to_string(sample.rate_per_sec.map(|rate| format!("{:.1}", rate)), "-"),
+            sample.label
+        );
+    }
+    println!("===========================");
+}
+
+fn print_json_lines(timestamp_ms: u64, samples: &[CounterSample]) {
+    for sample in samples {
+        println!(
+            "{{\"timestamp\":{},\"id\":{},\"type_id\":{},\"type\":\"{}\",\"label\":\"{}\",\"value\":{},\"delta\":{},\"rate\":{}}}",
+            timestamp_ms,
+            sample.id,
+            sample.type_id,
+            sample.type_name,
+            json_escape(&sample.label),
+            sample.value,
+            optional_to_string(sample.delta, "null"),
+            optional_to_string(sample.rate_per_sec.map(|rate| format!("{:.3}", rate)), "null")
+        );
+    }
+}
+
+fn print_csv(timestamp_ms: u64, samples: &[CounterSample]) {
+    for sample in samples {
+        println!(
+            "{},{},{},{},{},{},{},{}",
+            timestamp_ms,
+            sample.id,
+            sample.type_id,
+            sample.type_name,
+            csv_escape(&sample.label),
+            sample.value,
+            optional_to_string(sample.delta, ""),
+            optional_to_string(sample.rate_per_sec.map(|rate| format!("{:.3}", rate)), "")
+        );
+    }
+}
+
+type MappedCounters = (MemoryMappedFile, String, AtomicBuffer, AtomicBuffer);
+
+fn map_cnc_counters(base_path: &str, text: bool) -> MappedCounters {
+    let filename = String::from(base_path) + "/" + cnc_file_descriptor::CNC_FILE;
+
+    if text {
+        println!("Using file: {}", filename);
+    }
 
     let cnc_file = MemoryMappedFile::map_existing(filename, false).expect("Can't map file");
     let cnc_version = cnc_file_descriptor::cnc_version_volatile(&cnc_file);
@@ -81,33 +287,90 @@ fn main() {
         );
     }
 
-    let client_liveness_timeout_ns = cnc_file_descriptor::client_liveness_timeout(&cnc_file);
-    let pid = cnc_file_descriptor::pid(&cnc_file);
-
+    let header = format!(
+        "Aeron Stat (CnC v{}), pid: {}, client liveness: {} ns",
+        semantic_version_to_string(cnc_version),
+        cnc_file_descriptor::pid(&cnc_file),
+        cnc_file_descriptor::client_liveness_timeout(&cnc_file)
+    );
     let metadata_buffer = cnc_file_descriptor::create_counter_metadata_buffer(&cnc_file);
     let values_buffer = cnc_file_descriptor::create_counter_values_buffer(&cnc_file);
 
+    (cnc_file, header, metadata_buffer, values_buffer)
+}
+
+/// Map a counters file published by an application with AppCounters.
+fn map_app_counters(filename: &str, text: bool) -> MappedCounters {
+    if text {
+        println!("Using file: {}", filename);
+    }
+
+    let file = app_counters::map_existing(filename).unwrap_or_else(|err| exit_with_error(&format!("{}", err)));
+
+    let header = format!(
+        "Aeron Stat (app counters v{}), pid: {}",
+        semantic_version_to_string(app_counters::version_volatile(&file)),
+        app_counters::pid(&file)
+    );
+    let metadata_buffer = app_counters::create_counter_metadata_buffer(&file);
+    let values_buffer = app_counters::create_counter_values_buffer(&file);
+
+    (file, header, metadata_buffer, values_buffer)
+}
+
+fn main() {
+    pretty_env_logger::init();
+    ctrlc::set_handler(move || {
+        sig_int_handler();
+    })
+    .expect("Error setting Ctrl-C handler");
+
+    let settings = parse_cmd_line();
+    let text = settings.format == OutputFormat::Text;
+
+    let (_file, header, metadata_buffer, values_buffer) = match &settings.counters_file {
+        Some(counters_file) => map_app_counters(counters_file, text),
+        None => map_cnc_counters(&settings.base_path, text),
+    };
+
     let counters = CountersReader::new(metadata_buffer, values_buffer);
 
+    if settings.format == OutputFormat::Csv {
+        println!("timestamp,id,type_id,type,label,value,delta,rate");
+    }
+
+    let mut previous = PreviousValues::new();
+    let mut previous_sample_time: Option<Instant> = None;
+
     while RUNNING.load(Ordering::SeqCst) {
-        let date = get_date();
-        println!(
-            "[{}] - Aeron Stat (CnC v{}), pid: {}, client liveness: {} ns",
-            date,
-            semantic_version_to_string(cnc_version),
-            pid,
-            client_liveness_timeout_ns
+        let now = Instant::now();
+        let samples = sample_counters(
+            &settings,
+            &counters,
+            &previous,
+            previous_sample_time.map(|time| now.duration_since(time)),
         );
-        println!("===========================");
 
-        counters.for_each(|counter_id, _, _buffer, l| {
-            let value = counters.counter_value(counter_id).expect("Get an error from counter");
+        match settings.format {
+            OutputFormat::Text => print_text(&header, &samples),
+            OutputFormat::JsonLines => print_json_lines(unix_time_ms(), &samples),
+            OutputFormat::Csv => print_csv(unix_time_ms(), &samples),
+        }
+
+        if settings.single_shot {
+            break;
+        }
+
+        previous = samples
+            .into_iter()
+            .map(|sample| (sample.id, (sample.label, sample.value)))
+            .collect();
+        previous_sample_time = Some(now);
 
-            println!("{:>3}:{:>20} - {}", counter_id, value, l.into_string().unwrap());
-        });
-        println!("===========================");
         thread::sleep(Duration::from_millis(settings.update_interval_ms));
     }
 
-    println!("Exiting...")
+    if settings.format == OutputFormat::Text {
+        println!("Exiting...")
+    }
 }
@@ -0,0 +1,201 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::time::Duration;
+use std::{process, thread};
+
+use aeron_rs::context::Context;
+use aeron_rs::utils::errors::AeronError;
+use aeron_rs::utils::misc::unix_time_ms;
+use aeron_rs::utils::types::Moment;
+
+/// Driver has stopped its heartbeat after the termination request.
+const EXIT_TERMINATED: i32 = 0;
+/// Driver is still alive when the timeout expires. Usually this means that the token was rejected.
+const EXIT_TOKEN_REJECTED_OR_TIMED_OUT: i32 = 1;
+/// No CnC file in the directory or the driver heartbeat is stale.
+const EXIT_NO_DRIVER: i32 = 2;
+/// Invalid command line or I/O error.
+const EXIT_ERROR: i32 = 3;
+
+const POLL_INTERVAL_MS: u64 = 100;
+
+struct CmdOpts {
+    base_path: String,
+    token: Vec<u8>,
+    timeout_ms: Moment,
+    driver_timeout_ms: Moment,
+    heartbeat_stall_ms: Moment,
+}
+
+impl Default for CmdOpts {
+    fn default() -> Self {
+        Self {
+            base_path: Context::default_aeron_path(),
+            token: Vec::new(),
+            timeout_ms: 10000,
+            driver_timeout_ms: 10000,
+            heartbeat_stall_ms: 3000,
+        }
+    }
+}
+
+fn usage() -> String {
+    String::from(
+        "Usage: aeron_terminate [options]\n\
+         \n\
+         Options:\n\
+         \x20 -d, --dir <path>               Aeron directory of the driver (default: /dev/shm/aeron-$USER)\n\
+         \x20 -t, --token <string>           termination validation token\n\
+         \x20 -f, --token-file <path>        read termination validation token from the file, a trailing newline is dropped\n\
+         \x20 -w, --timeout-ms <ms>          time to wait for the driver to stop (default: 10000)\n\
+         \x20     --driver-timeout-ms <ms>   heartbeat age after which the driver is considered dead (default: 10000)\n\
+         \x20     --stall-ms <ms>            time without heartbeat updates which means the driver has stopped (default: 3000)\n\
+         \x20 -h, --help                     print this help\n\
+         \n\
+         Exit codes: 0 - terminated, 1 - token rejected or timed out, 2 - no driver found, 3 - error",
+    )
+}
+
+fn exit_with_error(message: &str) -> ! {
+    eprintln!("{}\n\n{}", message, usage());
+    process::exit(EXIT_ERROR);
+}
+
+fn parse_ms(option: &str, value: Option<String>) -> Moment {
+    match value.map(|v| v.parse::<Moment>()) {
+        Some(Ok(ms)) => ms,
+        _ => exit_with_error(&format!("{} expects a number of milliseconds", option)),
+    }
+}
+
+fn parse_cmd_line() -> CmdOpts {
+    let mut opts = CmdOpts::default();
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-d" | "--dir" => opts.base_path = args.next().unwrap_or_else(|| exit_with_error("--dir expects a path")),
+            "-t" | "--token" => {
+                opts.token = args
+                    .next()
+                    .unwrap_or_else(|| exit_with_error("--token expects a value"))
+                    .into_bytes()
+            },
+            "-f" | "--token-file" => {
+                let path = args.next().unwrap_or_else(|| exit_with_error("--token-file expects a path"));
+                let mut token = std::fs::read(&path)
+                    .unwrap_or_else(|err| exit_with_error(&format!("Can't read token file {}: {}", path, err)));
+                // Files written with e.g. echo end with a newline which is not part of the token.
+                if token.ends_with(b"\n") {
+                    token.pop();
+                    if token.ends_with(b"\r") {
+                        token.pop();
+                    }
+                }
+                opts.token = token;
+            },
+            "-w" | "--timeout-ms" => opts.timeout_ms = parse_ms("--timeout-ms", args.next()),
+            "--driver-timeout-ms" => opts.driver_timeout_ms = parse_ms("--driver-timeout-ms", args.next()),
+            "--stall-ms" => opts.heartbeat_stall_ms = parse_ms("--stall-ms", args.next()),
+            "-h" | "--help" => {
+                println!("{}", usage());
+                process::exit(EXIT_TERMINATED);
+            },
+            _ => exit_with_error(&format!("Unknown option: {}", arg)),
+        }
+    }
+
+    opts
+}
+
+/**
+ * Waits until the driver heartbeat stops advancing for heartbeat_stall_ms or the CnC file goes away.
+ * Returns false if the driver is still alive after timeout_ms and an error if the CnC file can't be read.
+ */
+fn await_driver_termination(settings: &CmdOpts, mut last_heartbeat: Moment) -> Result<bool, AeronError> {
+    let start_ms = unix_time_ms();
+    let mut last_change_ms = start_ms;
+
+    loop {
+        let now_ms = unix_time_ms();
+
+        match Context::driver_heartbeat_time(&settings.base_path) {
+            Ok(Some(heartbeat)) => {
+                if heartbeat != last_heartbeat {
+                    last_heartbeat = heartbeat;
+                    last_change_ms = now_ms;
+                } else if now_ms.saturating_sub(last_change_ms) >= settings.heartbeat_stall_ms {
+                    return Ok(true);
+                }
+            },
+            // CnC file is deleted by the driver on shutdown
+            Ok(None) => return Ok(true),
+            Err(err) => return Err(err),
+        }
+
+        if now_ms.saturating_sub(start_ms) >= settings.timeout_ms {
+            return Ok(false);
+        }
+
+        thread::sleep(Duration::from_millis(POLL_INTERVAL_MS));
+    }
+}
+
+fn main() {
+    pretty_env_logger::init();
+    let settings = parse_cmd_line();
+
+    let last_heartbeat = match Context::driver_heartbeat_time(&settings.base_path) {
+        Ok(Some(heartbeat)) if heartbeat + settings.driver_timeout_ms > unix_time_ms() => heartbeat,
+        Ok(_) => {
+            eprintln!("No active driver found in {}", settings.base_path);
+            process::exit(EXIT_NO_DRIVER);
+        },
+        Err(err) => {
+            eprintln!("Can't read CnC file in {}: {}", settings.base_path, err);
+            process::exit(EXIT_ERROR);
+        },
+    };
+
+    println!("Requesting termination of driver in {}", settings.base_path);
+
+    if let Err(err) = Context::request_driver_termination_with_token(&settings.base_path, &settings.token) {
+        eprintln!("Can't send termination request: {}", err);
+        process::exit(EXIT_ERROR);
+    }
+
+    match await_driver_termination(&settings, last_heartbeat) {
+        Ok(true) => {
+            println!("Driver terminated");
+            process::exit(EXIT_TERMINATED);
+        },
+        Ok(false) => {
+            eprintln!(
+                "Driver is still running after {} ms, termination token may have been rejected",
+                settings.timeout_ms
+            );
+            process::exit(EXIT_TOKEN_REJECTED_OR_TIMED_OUT);
+        },
+        Err(err) => {
+            eprintln!(
+                "Can't read CnC file in {} while awaiting termination: {}",
+                settings.base_path, err
+            );
+            process::exit(EXIT_ERROR);
+        },
+    }
+}
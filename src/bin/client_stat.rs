@@ -0,0 +1,170 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use std::{process, thread};
+
+use aeron_rs::cnc_file_descriptor::{self, CNC_FILE, CNC_VERSION};
+use aeron_rs::concurrent::counters::CountersReader;
+use aeron_rs::connected_clients::{self, ClientCounter, ClientResource};
+use aeron_rs::context::Context;
+use aeron_rs::utils::memory_mapped_file::MemoryMappedFile;
+use aeron_rs::utils::misc::{semantic_version_major, semantic_version_to_string, unix_time_ms};
+use chrono::Local;
+use lazy_static::lazy_static;
+
+lazy_static! {
+    pub static ref RUNNING: AtomicBool = AtomicBool::from(true);
+}
+
+fn sig_int_handler() {
+    RUNNING.store(false, Ordering::SeqCst);
+}
+
+struct CmdOpts {
+    base_path: String,
+    update_interval_ms: u64,
+    single_shot: bool,
+}
+
+impl Default for CmdOpts {
+    fn default() -> Self {
+        Self {
+            base_path: Context::default_aeron_path(),
+            update_interval_ms: 1000,
+            single_shot: false,
+        }
+    }
+}
+
+fn usage() -> &'static str {
+    "Usage: client_stat [-d <aeron dir>] [-u <update interval ms>] [-1 (print once and exit)]"
+}
+
+fn exit_with_error(message: &str) -> ! {
+    eprintln!("{}\n\n{}", message, usage());
+    process::exit(1);
+}
+
+fn parse_number<T: std::str::FromStr>(option: &str, value: Option<String>) -> T {
+    value
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_else(|| exit_with_error(&format!("{} expects a number", option)))
+}
+
+fn parse_cmd_line() -> CmdOpts {
+    let mut opts = CmdOpts::default();
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-d" | "--dir" => opts.base_path = args.next().unwrap_or_else(|| exit_with_error("--dir expects a path")),
+            "-u" | "--interval-ms" => opts.update_interval_ms = parse_number("--interval-ms", args.next()),
+            "-1" | "--once" => opts.single_shot = true,
+            _ => {
+                println!("{}", usage());
+                process::exit(if arg == "-h" || arg == "--help" { 0 } else { 1 });
+            },
+        }
+    }
+
+    opts
+}
+
+fn print_resources(kind: &str, resources: &[ClientResource]) {
+    for resource in resources {
+        println!(
+            "    {} registration_id={} stream_id={} sessions={:?} counters={:?} {}",
+            kind, resource.registration_id, resource.stream_id, resource.session_ids, resource.counter_ids, resource.channel
+        );
+    }
+}
+
+fn print_counters(counters: &[ClientCounter]) {
+    for counter in counters {
+        println!(
+            "    counter id={} registration_id={} type_id={} {}",
+            counter.counter_id, counter.registration_id, counter.type_id, counter.label
+        );
+    }
+}
+
+fn main() {
+    pretty_env_logger::init();
+    ctrlc::set_handler(move || {
+        sig_int_handler();
+    })
+    .expect("Error setting Ctrl-C handler");
+
+    let settings = parse_cmd_line();
+    let filename = settings.base_path.clone() + "/" + CNC_FILE;
+
+    let cnc_file = MemoryMappedFile::map_existing(filename, false).expect("Can't map file");
+    let cnc_version = cnc_file_descriptor::cnc_version_volatile(&cnc_file);
+
+    if semantic_version_major(cnc_version) != semantic_version_major(CNC_VERSION) {
+        panic!(
+            "CNC version is not supported: file={} app={}",
+            semantic_version_to_string(cnc_version),
+            semantic_version_to_string(CNC_VERSION)
+        );
+    }
+
+    let client_liveness_timeout_ns = cnc_file_descriptor::client_liveness_timeout(&cnc_file);
+
+    let counters = CountersReader::new(
+        cnc_file_descriptor::create_counter_metadata_buffer(&cnc_file),
+        cnc_file_descriptor::create_counter_values_buffer(&cnc_file),
+    );
+
+    while RUNNING.load(Ordering::SeqCst) {
+        let clients = connected_clients::connected_clients(&counters, client_liveness_timeout_ns, unix_time_ms());
+
+        println!(
+            "[{}] - Aeron clients of driver pid {} (client liveness: {} ns)",
+            Local::now(),
+            cnc_file_descriptor::pid(&cnc_file),
+            client_liveness_timeout_ns
+        );
+        println!("===========================");
+
+        for client in &clients {
+            println!(
+                "client_id={} {} heartbeat_age={} ms heartbeat_counter={} publications={} subscriptions={} counters={}",
+                client.client_id,
+                if client.is_alive { "ALIVE" } else { "TIMED OUT" },
+                client.heartbeat_age_ms,
+                client.heartbeat_counter_id,
+                client.publications.len(),
+                client.subscriptions.len(),
+                client.counters.len()
+            );
+            print_resources("pub", &client.publications);
+            print_resources("sub", &client.subscriptions);
+            print_counters(&client.counters);
+        }
+
+        println!("{} clients", clients.len());
+        println!("===========================");
+
+        if settings.single_shot {
+            break;
+        }
+
+        thread::sleep(Duration::from_millis(settings.update_interval_ms));
+    }
+}
@@ -0,0 +1,164 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use std::{process, thread};
+
+use aeron_rs::cnc_file_descriptor::{self, CNC_FILE, CNC_VERSION};
+use aeron_rs::context::Context;
+use aeron_rs::control_sniffer::{ControlEvent, ControlFilter, ControlSniffer};
+use aeron_rs::utils::memory_mapped_file::MemoryMappedFile;
+use aeron_rs::utils::misc::{semantic_version_major, semantic_version_to_string};
+use chrono::{Local, TimeZone};
+use lazy_static::lazy_static;
+
+lazy_static! {
+    pub static ref RUNNING: AtomicBool = AtomicBool::from(true);
+}
+
+fn sig_int_handler() {
+    RUNNING.store(false, Ordering::SeqCst);
+}
+
+struct CmdOpts {
+    base_path: String,
+    client_id: Option<i64>,
+    correlation_id: Option<i64>,
+    idle_us: u64,
+}
+
+impl Default for CmdOpts {
+    fn default() -> Self {
+        Self {
+            base_path: Context::default_aeron_path(),
+            client_id: None,
+            correlation_id: None,
+            idle_us: 0,
+        }
+    }
+}
+
+fn usage() -> String {
+    String::from(
+        "Usage: control_sniffer [options]\n\
+         \n\
+         Prints the commands clients send to the driver and the responses of the driver as they pass through\n\
+         the CnC file. Commands are read without consuming them, those the driver consumes before they are read\n\
+         are counted as lost.\n\
+         \n\
+         Options:\n\
+         \x20 -d, --dir <path>            Aeron directory of the driver (default: /dev/shm/aeron-$USER)\n\
+         \x20 -c, --client <id>           only commands of the client and responses to them\n\
+         \x20 -r, --correlation <id>      only events referring to the correlation or registration id\n\
+         \x20 -i, --idle-us <us>          sleep when there was nothing to read, 0 yields (default: 0)\n\
+         \x20 -h, --help                  print this help",
+    )
+}
+
+fn exit_with_error(message: &str) -> ! {
+    eprintln!("{}\n\n{}", message, usage());
+    process::exit(1);
+}
+
+fn parse_number<T: std::str::FromStr>(option: &str, value: Option<String>) -> T {
+    value
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_else(|| exit_with_error(&format!("{} expects a number", option)))
+}
+
+fn parse_cmd_line() -> CmdOpts {
+    let mut opts = CmdOpts::default();
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-d" | "--dir" => opts.base_path = args.next().unwrap_or_else(|| exit_with_error("--dir expects a path")),
+            "-c" | "--client" => opts.client_id = Some(parse_number("--client", args.next())),
+            "-r" | "--correlation" => opts.correlation_id = Some(parse_number("--correlation", args.next())),
+            "-i" | "--idle-us" => opts.idle_us = parse_number("--idle-us", args.next()),
+            "-h" | "--help" => {
+                println!("{}", usage());
+                process::exit(0);
+            },
+            _ => exit_with_error(&format!("Unknown option: {}", arg)),
+        }
+    }
+
+    opts
+}
+
+fn format_timestamp(timestamp_ns: i64) -> String {
+    Local.timestamp_nanos(timestamp_ns).format("%Y-%m-%d %H:%M:%S%.6f").to_string()
+}
+
+fn print_event(event: &ControlEvent) {
+    println!("{} {}", format_timestamp(event.timestamp_ns), event);
+}
+
+fn main() {
+    pretty_env_logger::init();
+    ctrlc::set_handler(move || {
+        sig_int_handler();
+    })
+    .expect("Error setting Ctrl-C handler");
+
+    let settings = parse_cmd_line();
+    let cnc_file_name = settings.base_path.clone() + "/" + CNC_FILE;
+
+    let cnc_file = MemoryMappedFile::map_existing(&cnc_file_name, true)
+        .unwrap_or_else(|err| exit_with_error(&format!("Can't map {}: {}", cnc_file_name, err)));
+    let cnc_version = cnc_file_descriptor::cnc_version_volatile(&cnc_file);
+
+    if semantic_version_major(cnc_version) != semantic_version_major(CNC_VERSION) {
+        exit_with_error(&format!(
+            "CNC version is not supported:\n file={}\n app={}",
+            semantic_version_to_string(cnc_version),
+            semantic_version_to_string(CNC_VERSION)
+        ));
+    }
+
+    let mut sniffer = ControlSniffer::new(
+        cnc_file_descriptor::create_to_driver_buffer(&cnc_file),
+        cnc_file_descriptor::create_to_clients_buffer(&cnc_file),
+    )
+    .unwrap_or_else(|err| exit_with_error(&format!("Can't read control buffers of {}: {}", cnc_file_name, err)));
+    let mut filter = ControlFilter::new(settings.client_id, settings.correlation_id);
+
+    println!("Sniffing control protocol of {}", cnc_file_name);
+
+    while RUNNING.load(Ordering::SeqCst) {
+        let events = sniffer.poll(|event| {
+            if filter.matches(&event.message) {
+                print_event(&event);
+            }
+        });
+
+        if events == 0 {
+            if settings.idle_us == 0 {
+                thread::yield_now();
+            } else {
+                thread::sleep(Duration::from_micros(settings.idle_us));
+            }
+        }
+    }
+
+    println!(
+        "\nLOST TO DRIVER BYTES: {}, TO CLIENTS LAPPED: {}",
+        sniffer.lost_to_driver_bytes(),
+        sniffer.to_clients_lapped_count()
+    );
+}
@@ -14,27 +14,127 @@
  * limitations under the License.
  */
 
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use std::{process, thread};
+
 use aeron_rs::cnc_file_descriptor::{self, CNC_FILE, CNC_VERSION};
+use aeron_rs::concurrent::atomic_buffer::AtomicBuffer;
 use aeron_rs::context::Context;
-use aeron_rs::utils::errors::error_log_reader;
+use aeron_rs::utils::errors::error_log_reader::{self, ErrorLogEntry};
 use aeron_rs::utils::memory_mapped_file::MemoryMappedFile;
-use aeron_rs::utils::misc::{semantic_version_major, semantic_version_to_string};
-use chrono::{Local, TimeZone};
+use aeron_rs::utils::misc::{json_escape, semantic_version_major, semantic_version_to_string, unix_time_ms};
+use aeron_rs::utils::types::Index;
+use chrono::{DateTime, Local, TimeZone};
+use lazy_static::lazy_static;
+
+lazy_static! {
+    pub static ref RUNNING: AtomicBool = AtomicBool::from(true);
+}
+
+fn sig_int_handler() {
+    RUNNING.store(false, Ordering::SeqCst);
+}
 
 struct CmdOpts {
     base_path: String,
+    error_log_file: Option<String>,
+    since_timestamp: i64,
+    follow: bool,
+    update_interval_ms: u64,
+    json: bool,
 }
 
 impl Default for CmdOpts {
     fn default() -> Self {
         Self {
             base_path: Context::default_aeron_path(),
+            error_log_file: None,
+            since_timestamp: 0,
+            follow: false,
+            update_interval_ms: 1000,
+            json: false,
         }
     }
 }
 
+fn usage() -> String {
+    String::from(
+        "Usage: error_stat [options]\n\
+         \n\
+         Options:\n\
+         \x20 -d, --dir <path>           Aeron directory of the driver (default: /dev/shm/aeron-$USER)\n\
+         \x20 -e, --error-log <file>     read a client error log file written by DistinctErrorLog instead of the CnC file\n\
+         \x20 -s, --since <time>         show only errors last observed since the time: ms since epoch, RFC 3339\n\
+         \x20                            date (e.g. 2020-06-01T10:00:00Z) or age with s, m, h or d unit (e.g. 15m)\n\
+         \x20 -f, --follow               keep printing new and updated observations as the driver records them\n\
+         \x20 -u, --interval-ms <ms>     follow mode poll interval (default: 1000)\n\
+         \x20 -j, --json                 print observations as JSON lines\n\
+         \x20 -h, --help                 print this help",
+    )
+}
+
+fn exit_with_error(message: &str) -> ! {
+    eprintln!("{}\n\n{}", message, usage());
+    process::exit(1);
+}
+
+/// Parse ms since epoch, a RFC 3339 date or an age relative to now such as "30s", "15m", "2h" or "1d".
+fn parse_since(value: &str, now_ms: i64) -> Option<i64> {
+    if let Ok(timestamp) = value.parse::<i64>() {
+        return Some(timestamp);
+    }
+
+    if let Ok(date) = DateTime::parse_from_rfc3339(value) {
+        return Some(date.timestamp_millis());
+    }
+
+    let unit_ms = match value.chars().last()? {
+        's' => 1000,
+        'm' => 60 * 1000,
+        'h' => 60 * 60 * 1000,
+        'd' => 24 * 60 * 60 * 1000,
+        _ => return None,
+    };
+    let age: i64 = value[..value.len() - 1].parse().ok()?;
+
+    Some(now_ms - age * unit_ms)
+}
+
 fn parse_cmd_line() -> CmdOpts {
-    CmdOpts::default()
+    let mut opts = CmdOpts::default();
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-d" | "--dir" => opts.base_path = args.next().unwrap_or_else(|| exit_with_error("--dir expects a path")),
+            "-e" | "--error-log" => {
+                opts.error_log_file = Some(args.next().unwrap_or_else(|| exit_with_error("--error-log expects a file")))
+            },
+            "-s" | "--since" => {
+                opts.since_timestamp = args
+                    .next()
+                    .and_then(|value| parse_since(&value, unix_time_ms() as i64))
+                    .unwrap_or_else(|| exit_with_error("--since expects ms since epoch, a RFC 3339 date or an age like 15m"))
+            },
+            "-f" | "--follow" => opts.follow = true,
+            "-u" | "--interval-ms" => {
+                opts.update_interval_ms = args
+                    .next()
+                    .and_then(|value| value.parse().ok())
+                    .unwrap_or_else(|| exit_with_error("--interval-ms expects a number of milliseconds"))
+            },
+            "-j" | "--json" => opts.json = true,
+            "-h" | "--help" => {
+                println!("{}", usage());
+                process::exit(0);
+            },
+            _ => exit_with_error(&format!("Unknown option: {}", arg)),
+        }
+    }
+
+    opts
 }
 
 fn format_date(milliseconds_since_epoch: i64) -> String {
@@ -42,13 +142,34 @@ fn format_date(milliseconds_since_epoch: i64) -> String {
     Local.timestamp_millis_opt(milliseconds_since_epoch).unwrap().to_string()
 }
 
-fn main() {
-    pretty_env_logger::init();
-    let settings = parse_cmd_line();
+fn print_entry(entry: &ErrorLogEntry, json: bool) {
+    let message = entry.encoded_exception.to_string_lossy();
 
-    let cnc_file_name = settings.base_path + "/" + CNC_FILE;
+    if json {
+        println!(
+            "{{\"observation_count\":{},\"first_observation_timestamp\":{},\"last_observation_timestamp\":{},\"message\":\"{}\"}}",
+            entry.observation_count,
+            entry.first_observation_timestamp,
+            entry.last_observation_timestamp,
+            json_escape(&message)
+        );
+    } else {
+        println!(
+            "***\n{} observations from {} to {} for:\n{}\n",
+            entry.observation_count,
+            format_date(entry.first_observation_timestamp),
+            format_date(entry.last_observation_timestamp),
+            message
+        );
+    }
+}
+
+fn map_cnc_error_log(base_path: &str, json: bool) -> (MemoryMappedFile, AtomicBuffer) {
+    let cnc_file_name = String::from(base_path) + "/" + CNC_FILE;
 
-    println!("Opening CnC file: {}", cnc_file_name);
+    if !json {
+        println!("Opening CnC file: {}", cnc_file_name);
+    }
 
     let cnc_file = MemoryMappedFile::map_existing(&cnc_file_name, false).expect("Cannot map file");
     let cnc_version = cnc_file_descriptor::cnc_version_volatile(&cnc_file);
@@ -63,19 +184,56 @@ fn main() {
 
     let error_buffer = cnc_file_descriptor::create_error_log_buffer(&cnc_file);
 
-    let distinct_error_count = error_log_reader::read(
-        error_buffer,
-        |observation_count, first_observation_timestamp, last_observation_timestamp, encoded_exception| {
-            println!(
-                "***\n{} observations from {} to {} for:\n{}\n",
-                observation_count,
-                format_date(first_observation_timestamp),
-                format_date(last_observation_timestamp),
-                encoded_exception.into_string().expect("Cannot convert exception")
-            );
-        },
-        0,
-    );
-
-    println!("\n{} distinct errors observed.\n", distinct_error_count);
+    (cnc_file, error_buffer)
+}
+
+/// Map an error log written by a client with DistinctErrorLog, the whole file is the log.
+fn map_error_log_file(file_name: &str, json: bool) -> (MemoryMappedFile, AtomicBuffer) {
+    if !json {
+        println!("Opening error log file: {}", file_name);
+    }
+
+    let file = MemoryMappedFile::map_existing(file_name, false).expect("Cannot map file");
+    let error_buffer = file.atomic_buffer(0, file.memory_size());
+
+    (file, error_buffer)
+}
+
+fn main() {
+    pretty_env_logger::init();
+    ctrlc::set_handler(move || {
+        sig_int_handler();
+    })
+    .expect("Error setting Ctrl-C handler");
+
+    let settings = parse_cmd_line();
+
+    let (_file, error_buffer) = match &settings.error_log_file {
+        Some(error_log_file) => map_error_log_file(error_log_file, settings.json),
+        None => map_cnc_error_log(&settings.base_path, settings.json),
+    };
+
+    // Observation count and last timestamp of each distinct error keyed by its offset in the log
+    let mut printed: HashMap<Index, (i32, i64)> = HashMap::new();
+
+    loop {
+        for entry in error_log_reader::entries(error_buffer, settings.since_timestamp) {
+            let observed = (entry.observation_count, entry.last_observation_timestamp);
+
+            if printed.get(&entry.offset) != Some(&observed) {
+                print_entry(&entry, settings.json);
+                printed.insert(entry.offset, observed);
+            }
+        }
+
+        if !settings.follow || !RUNNING.load(Ordering::SeqCst) {
+            break;
+        }
+
+        thread::sleep(Duration::from_millis(settings.update_interval_ms));
+    }
+
+    if !settings.json {
+        println!("\n{} distinct errors observed.\n", printed.len());
+    }
 }
@@ -0,0 +1,194 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::process;
+
+use aeron_rs::concurrent::logbuffer::log_buffer_descriptor::PARTITION_COUNT;
+use aeron_rs::log_inspector::{frame_payload, LogInspector, LogMetaData, TermEntry};
+use aeron_rs::utils::misc::{hex_dump, printable_ascii};
+use aeron_rs::utils::types::Index;
+
+#[derive(Clone, Copy, PartialEq)]
+enum PayloadDump {
+    None,
+    Hex,
+    Ascii,
+}
+
+struct CmdOpts {
+    log_file: Option<String>,
+    payload_dump: PayloadDump,
+    max_bytes: usize,
+    whole_term: bool,
+    partition_index: Option<Index>,
+}
+
+impl Default for CmdOpts {
+    fn default() -> Self {
+        Self {
+            log_file: None,
+            payload_dump: PayloadDump::None,
+            max_bytes: 64,
+            whole_term: false,
+            partition_index: None,
+        }
+    }
+}
+
+fn usage() -> String {
+    String::from(
+        "Usage: log_inspector [options] <log buffer file>\n\
+         \n\
+         Prints the meta data and the default frame header of the log buffer of a publication or image and walks\n\
+         the frames of its terms. Frames which were claimed but not committed or whose header doesn't match their\n\
+         position are flagged as torn, runs of frames with a zero length as zeroed.\n\
+         \n\
+         Options:\n\
+         \x20 -d, --dump <hex|ascii>      dump the payload of frames\n\
+         \x20 -m, --max-bytes <bytes>     bytes of the payload to dump (default: 64)\n\
+         \x20 -a, --all                   walk whole terms instead of up to their tail, e.g. for images\n\
+         \x20 -p, --partition <index>     only walk the term of the partition\n\
+         \x20 -h, --help                  print this help",
+    )
+}
+
+fn exit_with_error(message: &str) -> ! {
+    eprintln!("{}\n\n{}", message, usage());
+    process::exit(1);
+}
+
+fn parse_number<T: std::str::FromStr>(option: &str, value: Option<String>) -> T {
+    value
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_else(|| exit_with_error(&format!("{} expects a number", option)))
+}
+
+fn parse_cmd_line() -> CmdOpts {
+    let mut opts = CmdOpts::default();
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-d" | "--dump" => {
+                opts.payload_dump = match args.next().as_deref() {
+                    Some("hex") => PayloadDump::Hex,
+                    Some("ascii") => PayloadDump::Ascii,
+                    _ => exit_with_error("--dump expects hex or ascii"),
+                }
+            },
+            "-m" | "--max-bytes" => opts.max_bytes = parse_number("--max-bytes", args.next()),
+            "-a" | "--all" => opts.whole_term = true,
+            "-p" | "--partition" => {
+                let partition_index = parse_number("--partition", args.next());
+                if !(0..PARTITION_COUNT).contains(&partition_index) {
+                    exit_with_error(&format!("--partition expects an index below {}", PARTITION_COUNT));
+                }
+                opts.partition_index = Some(partition_index);
+            },
+            "-h" | "--help" => {
+                println!("{}", usage());
+                process::exit(0);
+            },
+            _ if arg.starts_with('-') => exit_with_error(&format!("Unknown option: {}", arg)),
+            _ if opts.log_file.is_none() => opts.log_file = Some(arg),
+            _ => exit_with_error(&format!("Unexpected argument: {}", arg)),
+        }
+    }
+
+    opts
+}
+
+fn print_meta_data(meta_data: &LogMetaData) {
+    println!("Initial term id:        {}", meta_data.initial_term_id);
+    println!("Active term count:      {}", meta_data.active_term_count);
+    println!("Active partition index: {}", meta_data.active_partition_index());
+    for partition_index in 0..PARTITION_COUNT {
+        println!(
+            "Raw tail {}:             {:#018x} term_id={} term_offset={}",
+            partition_index,
+            meta_data.raw_tails[partition_index as usize],
+            meta_data.tail_term_id(partition_index),
+            meta_data.tail_term_offset(partition_index)
+        );
+    }
+    println!("MTU length:             {}", meta_data.mtu_length);
+    println!("Term length:            {}", meta_data.term_length);
+    println!("Page size:              {}", meta_data.page_size);
+    println!("Connected:              {}", meta_data.is_connected);
+    println!("Active transport count: {}", meta_data.active_transport_count);
+    println!("End of stream position: {}", meta_data.end_of_stream_position);
+    println!("Correlation id:         {}", meta_data.correlation_id);
+}
+
+fn print_term(inspector: &LogInspector, partition_index: Index, settings: &CmdOpts) {
+    let term = inspector.term_buffer(partition_index);
+    let entries = inspector.scan_partition(partition_index, !settings.whole_term);
+    let mut frames = 0;
+    let mut torn_frames = 0;
+
+    println!("\nPartition {}:", partition_index);
+
+    for entry in &entries {
+        match entry {
+            TermEntry::Frame { offset, header, issue } => {
+                frames += 1;
+                match issue {
+                    Some(issue) => {
+                        torn_frames += 1;
+                        println!("{:>10}: {} [{}]", offset, header, issue);
+                    },
+                    None => println!("{:>10}: {}", offset, header),
+                }
+
+                let payload = frame_payload(&term, *offset, header, settings.max_bytes);
+                match settings.payload_dump {
+                    PayloadDump::Hex if !payload.is_empty() => println!("{}", hex_dump(payload)),
+                    PayloadDump::Ascii if !payload.is_empty() => println!("            {}", printable_ascii(payload)),
+                    _ => {},
+                }
+            },
+            TermEntry::Zeroed { offset, length } => println!("{:>10}: zeroed length={}", offset, length),
+        }
+    }
+
+    println!("Frames: {}, torn: {}", frames, torn_frames);
+}
+
+fn main() {
+    pretty_env_logger::init();
+
+    let settings = parse_cmd_line();
+    let log_file = settings
+        .log_file
+        .clone()
+        .unwrap_or_else(|| exit_with_error("Missing log buffer file"));
+
+    let inspector =
+        LogInspector::open(log_file.clone()).unwrap_or_else(|err| exit_with_error(&format!("Can't map {}: {}", log_file, err)));
+
+    println!("Log buffer {}", log_file);
+    print_meta_data(&inspector.meta_data());
+    println!("Default frame header:   {}", inspector.default_frame_header());
+
+    match settings.partition_index {
+        Some(partition_index) => print_term(&inspector, partition_index, &settings),
+        None => {
+            for partition_index in 0..PARTITION_COUNT {
+                print_term(&inspector, partition_index, &settings);
+            }
+        },
+    }
+}
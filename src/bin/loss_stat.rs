@@ -14,25 +14,123 @@
  * limitations under the License.
  */
 
-use aeron_rs::concurrent::reports::{self, loss_report_descriptor};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use std::{process, thread};
+
+use aeron_rs::concurrent::atomic_buffer::AtomicBuffer;
+use aeron_rs::concurrent::reports::{self, loss_report_descriptor, LossReportEntry};
 use aeron_rs::context::Context;
 use aeron_rs::utils::memory_mapped_file::MemoryMappedFile;
+use aeron_rs::utils::misc::csv_escape;
+use aeron_rs::utils::types::Index;
 use chrono::{Local, TimeZone};
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    pub static ref RUNNING: AtomicBool = AtomicBool::from(true);
+}
+
+fn sig_int_handler() {
+    RUNNING.store(false, Ordering::SeqCst);
+}
 
 struct CmdOpts {
     base_path: String,
+    stream_id: Option<i32>,
+    session_id: Option<i32>,
+    channel_filter: Option<Regex>,
+    sort_by_bytes_lost: bool,
+    by_source: bool,
+    watch: bool,
+    update_interval_ms: u64,
 }
 
 impl Default for CmdOpts {
     fn default() -> Self {
         Self {
             base_path: Context::default_aeron_path(),
+            stream_id: None,
+            session_id: None,
+            channel_filter: None,
+            sort_by_bytes_lost: false,
+            by_source: false,
+            watch: false,
+            update_interval_ms: 1000,
         }
     }
 }
 
+fn usage() -> String {
+    String::from(
+        "Usage: loss_stat [options]\n\
+         \n\
+         Options:\n\
+         \x20 -d, --dir <path>           Aeron directory of the driver (default: /dev/shm/aeron-$USER)\n\
+         \x20 -s, --stream <id>          show only entries of the stream id\n\
+         \x20 -e, --session <id>         show only entries of the session id\n\
+         \x20 -c, --channel <regex>      show only entries with matching channel\n\
+         \x20 -b, --sort-bytes           sort entries by total bytes lost, largest first\n\
+         \x20 -S, --by-source            print totals per source address\n\
+         \x20 -w, --watch                print loss growth between snapshots until interrupted\n\
+         \x20 -u, --interval-ms <ms>     watch mode snapshot interval (default: 1000)\n\
+         \x20 -h, --help                 print this help",
+    )
+}
+
+fn exit_with_error(message: &str) -> ! {
+    eprintln!("{}\n\n{}", message, usage());
+    process::exit(1);
+}
+
+fn parse_number<T: std::str::FromStr>(option: &str, value: Option<String>) -> T {
+    value
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_else(|| exit_with_error(&format!("{} expects a number", option)))
+}
+
 fn parse_cmd_line() -> CmdOpts {
-    CmdOpts::default()
+    let mut opts = CmdOpts::default();
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-d" | "--dir" => opts.base_path = args.next().unwrap_or_else(|| exit_with_error("--dir expects a path")),
+            "-s" | "--stream" => opts.stream_id = Some(parse_number("--stream", args.next())),
+            "-e" | "--session" => opts.session_id = Some(parse_number("--session", args.next())),
+            "-c" | "--channel" => {
+                let value = args.next().unwrap_or_else(|| exit_with_error("--channel expects a regex"));
+                opts.channel_filter = Some(
+                    Regex::new(&value).unwrap_or_else(|err| exit_with_error(&format!("Invalid regex for --channel: {}", err))),
+                );
+            },
+            "-b" | "--sort-bytes" => opts.sort_by_bytes_lost = true,
+            "-S" | "--by-source" => opts.by_source = true,
+            "-w" | "--watch" => opts.watch = true,
+            "-u" | "--interval-ms" => opts.update_interval_ms = parse_number("--interval-ms", args.next()),
+            "-h" | "--help" => {
+                println!("{}", usage());
+                process::exit(0);
+            },
+            _ => exit_with_error(&format!("Unknown option: {}", arg)),
+        }
+    }
+
+    opts
+}
+
+impl CmdOpts {
+    fn is_selected(&self, entry: &LossReportEntry) -> bool {
+        self.stream_id.is_none_or(|stream_id| stream_id == entry.stream_id)
+            && self.session_id.is_none_or(|session_id| session_id == entry.session_id)
+            && self
+                .channel_filter
+                .as_ref()
+                .is_none_or(|regex| regex.is_match(&entry.channel))
+    }
 }
 
 fn format_date(milliseconds_since_epoch: i64) -> String {
@@ -40,8 +138,79 @@ fn format_date(milliseconds_since_epoch: i64) -> String {
     Local.timestamp_millis_opt(milliseconds_since_epoch).unwrap().to_string()
 }
 
+fn read_entries(settings: &CmdOpts, buffer: &AtomicBuffer) -> Vec<LossReportEntry> {
+    let mut entries: Vec<LossReportEntry> = reports::entries(buffer).filter(|entry| settings.is_selected(entry)).collect();
+
+    if settings.sort_by_bytes_lost {
+        entries.sort_by_key(|entry| Reverse(entry.total_bytes_lost));
+    }
+
+    entries
+}
+
+fn print_entries(entries: &[LossReportEntry]) {
+    println!("OBSERVATION_COUNT, TOTAL_BYTES_LOST, FIRST_OBSERVATION, LAST_OBSERVATION, SESSION_ID, STREAM_ID, CHANNEL, SOURCE");
+
+    for entry in entries {
+        println!(
+            "{},{},{},{},{},{},{},{}",
+            entry.observation_count,
+            entry.total_bytes_lost,
+            format_date(entry.first_observation_timestamp),
+            format_date(entry.last_observation_timestamp),
+            entry.session_id,
+            entry.stream_id,
+            csv_escape(&entry.channel),
+            csv_escape(&entry.source)
+        );
+    }
+}
+
+fn print_source_totals(entries: &[LossReportEntry]) {
+    // source -> (entries, observations, bytes lost)
+    let mut totals: BTreeMap<&str, (usize, i64, i64)> = BTreeMap::new();
+
+    for entry in entries {
+        let total = totals.entry(entry.source.as_str()).or_default();
+        total.0 += 1;
+        total.1 += entry.observation_count;
+        total.2 += entry.total_bytes_lost;
+    }
+
+    println!("SOURCE, ENTRIES, OBSERVATION_COUNT, TOTAL_BYTES_LOST");
+
+    for (source, (entry_count, observation_count, bytes_lost)) in totals {
+        println!("{},{},{},{}", csv_escape(source), entry_count, observation_count, bytes_lost);
+    }
+}
+
+/// Print entries which recorded new loss since the previous snapshot, keyed by their offset in the report.
+fn print_growth(entries: &[LossReportEntry], previous: &HashMap<Index, (i64, i64)>) {
+    for entry in entries {
+        let (previous_observations, previous_bytes_lost) = previous.get(&entry.offset).copied().unwrap_or_default();
+
+        if entry.observation_count != previous_observations {
+            println!(
+                "{},+{},+{},{},{},{},{},{}",
+                format_date(entry.last_observation_timestamp),
+                entry.observation_count - previous_observations,
+                entry.total_bytes_lost - previous_bytes_lost,
+                entry.total_bytes_lost,
+                entry.session_id,
+                entry.stream_id,
+                csv_escape(&entry.channel),
+                csv_escape(&entry.source)
+            );
+        }
+    }
+}
+
 fn main() {
     pretty_env_logger::init();
+    ctrlc::set_handler(move || {
+        sig_int_handler();
+    })
+    .expect("Error setting Ctrl-C handler");
 
     let settings = parse_cmd_line();
     let filename = loss_report_descriptor::file(&settings.base_path);
@@ -55,21 +224,34 @@ fn main() {
     let loss_report_file = MemoryMappedFile::map_existing(filename, false).expect("Cannot map file");
     let buffer = loss_report_file.atomic_buffer(0, loss_report_file.memory_size());
 
-    println!("OBSERVATION_COUNT, TOTAL_BYTES_LOST, FIRST_OBSERVATION, LAST_OBSERVATION, SESSION_ID, STREAM_ID, CHANNEL, SOURCE");
+    let entries = read_entries(&settings, &buffer);
 
-    let entries_read = reports::read(&buffer, |observation_count, loss_report_entry, channel, source| {
-        println!(
-            "{},{},{},{},{},{},{:#?},{:#?}",
-            observation_count,
-            { loss_report_entry.total_bytes_lost },
-            format_date(loss_report_entry.first_observation_timestamp),
-            format_date(loss_report_entry.last_observation_timestamp),
-            loss_report_entry.session_id,
-            loss_report_entry.stream_id,
-            channel,
-            source
-        );
-    });
+    print_entries(&entries);
+    if settings.by_source {
+        print_source_totals(&entries);
+    }
+    println!("{} entries read", entries.len());
 
-    println!("{} entries read", entries_read);
+    if !settings.watch {
+        return;
+    }
+
+    println!("LAST_OBSERVATION, OBSERVATIONS_DELTA, BYTES_LOST_DELTA, TOTAL_BYTES_LOST, SESSION_ID, STREAM_ID, CHANNEL, SOURCE");
+
+    let mut previous: HashMap<Index, (i64, i64)> = entries
+        .iter()
+        .map(|entry| (entry.offset, (entry.observation_count, entry.total_bytes_lost)))
+        .collect();
+
+    while RUNNING.load(Ordering::SeqCst) {
+        thread::sleep(Duration::from_millis(settings.update_interval_ms));
+
+        let entries = read_entries(&settings, &buffer);
+        print_growth(&entries, &previous);
+
+        previous = entries
+            .iter()
+            .map(|entry| (entry.offset, (entry.observation_count, entry.total_bytes_lost)))
+            .collect();
+    }
 }
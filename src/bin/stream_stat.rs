@@ -0,0 +1,247 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use std::{process, thread};
+
+use aeron_rs::cnc_file_descriptor::{self, CNC_FILE, CNC_VERSION};
+use aeron_rs::concurrent::counters::CountersReader;
+use aeron_rs::context::Context;
+use aeron_rs::stream_stat::{self, StreamKey, StreamPositions};
+use aeron_rs::utils::memory_mapped_file::MemoryMappedFile;
+use aeron_rs::utils::misc::{json_escape, semantic_version_major, semantic_version_to_string, unix_time_ms};
+use chrono::Local;
+use lazy_static::lazy_static;
+
+lazy_static! {
+    pub static ref RUNNING: AtomicBool = AtomicBool::from(true);
+}
+
+fn sig_int_handler() {
+    RUNNING.store(false, Ordering::SeqCst);
+}
+
+struct CmdOpts {
+    base_path: String,
+    update_interval_ms: u64,
+    slow_threshold_bytes: i64,
+    single_shot: bool,
+    json: bool,
+}
+
+impl Default for CmdOpts {
+    fn default() -> Self {
+        Self {
+            base_path: Context::default_aeron_path(),
+            update_interval_ms: 1000,
+            slow_threshold_bytes: 64 * 1024,
+            single_shot: false,
+            json: false,
+        }
+    }
+}
+
+fn usage() -> String {
+    String::from(
+        "Usage: stream_stat [options]\n\
+         \n\
+         Options:\n\
+         \x20 -d, --dir <path>           Aeron directory of the driver (default: /dev/shm/aeron-$USER)\n\
+         \x20 -u, --interval-ms <ms>     update interval (default: 1000)\n\
+         \x20 -s, --slow-bytes <bytes>   subscriber backlog above which it is reported as slow (default: 65536)\n\
+         \x20 -1, --once                 print streams once and exit\n\
+         \x20 -j, --json                 print a single JSON snapshot and exit\n\
+         \x20 -h, --help                 print this help",
+    )
+}
+
+fn exit_with_error(message: &str) -> ! {
+    eprintln!("{}\n\n{}", message, usage());
+    process::exit(1);
+}
+
+fn parse_cmd_line() -> CmdOpts {
+    let mut opts = CmdOpts::default();
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-d" | "--dir" => opts.base_path = args.next().unwrap_or_else(|| exit_with_error("--dir expects a path")),
+            "-u" | "--interval-ms" => {
+                opts.update_interval_ms = args
+                    .next()
+                    .and_then(|value| value.parse().ok())
+                    .unwrap_or_else(|| exit_with_error("--interval-ms expects a number of milliseconds"))
+            },
+            "-s" | "--slow-bytes" => {
+                opts.slow_threshold_bytes = args
+                    .next()
+                    .and_then(|value| value.parse().ok())
+                    .unwrap_or_else(|| exit_with_error("--slow-bytes expects a number of bytes"))
+            },
+            "-1" | "--once" => opts.single_shot = true,
+            "-j" | "--json" => {
+                opts.json = true;
+                opts.single_shot = true;
+            },
+            "-h" | "--help" => {
+                println!("{}", usage());
+                process::exit(0);
+            },
+            _ => exit_with_error(&format!("Unknown option: {}", arg)),
+        }
+    }
+
+    opts
+}
+
+fn optional_to_string(value: Option<i64>, none: &str) -> String {
+    value.map_or_else(|| String::from(none), |value| value.to_string())
+}
+
+fn print_text(streams: &BTreeMap<StreamKey, StreamPositions>, slow_threshold_bytes: i64) {
+    println!("[{}] - Aeron streams ({} streams)", Local::now(), streams.len());
+    println!("===========================");
+
+    for (key, stream) in streams {
+        println!(
+            "sessionId={} streamId={} channel={}",
+            key.session_id, key.stream_id, key.channel
+        );
+        println!(
+            "    pub-pos={} pub-lmt={} snd-pos={} snd-lmt={} rcv-hwm={} rcv-pos={}",
+            optional_to_string(stream.publisher_position, "-"),
+            optional_to_string(stream.publisher_limit, "-"),
+            optional_to_string(stream.sender_position, "-"),
+            optional_to_string(stream.sender_limit, "-"),
+            optional_to_string(stream.receiver_hwm, "-"),
+            optional_to_string(stream.receiver_position, "-")
+        );
+        println!(
+            "    backlog pub->snd={} snd->rcv={}",
+            optional_to_string(stream.publisher_backlog(), "-"),
+            optional_to_string(stream.sender_backlog(), "-")
+        );
+
+        for subscriber in &stream.subscribers {
+            let backlog = stream.subscriber_backlog(subscriber);
+            println!(
+                "    sub registrationId={} position={} backlog={}{}",
+                subscriber.registration_id,
+                subscriber.position,
+                optional_to_string(backlog, "-"),
+                if backlog.is_some_and(|backlog| backlog > slow_threshold_bytes) {
+                    " <== SLOW"
+                } else {
+                    ""
+                }
+            );
+        }
+    }
+    println!("===========================");
+}
+
+fn print_json(streams: &BTreeMap<StreamKey, StreamPositions>, slow_threshold_bytes: i64) {
+    let streams_json: Vec<String> = streams
+        .iter()
+        .map(|(key, stream)| {
+            let subscribers: Vec<String> = stream
+                .subscribers
+                .iter()
+                .map(|subscriber| {
+                    let backlog = stream.subscriber_backlog(subscriber);
+                    format!(
+                        "{{\"registration_id\":{},\"counter_id\":{},\"position\":{},\"backlog\":{},\"slow\":{}}}",
+                        subscriber.registration_id,
+                        subscriber.counter_id,
+                        subscriber.position,
+                        optional_to_string(backlog, "null"),
+                        backlog.is_some_and(|backlog| backlog > slow_threshold_bytes)
+                    )
+                })
+                .collect();
+
+            format!(
+                "{{\"session_id\":{},\"stream_id\":{},\"channel\":\"{}\",\"publisher_position\":{},\"publisher_limit\":{},\
+                 \"sender_position\":{},\"sender_limit\":{},\"receiver_hwm\":{},\"receiver_position\":{},\
+                 \"publisher_backlog\":{},\"sender_backlog\":{},\"subscribers\":[{}]}}",
+                key.session_id,
+                key.stream_id,
+                json_escape(&key.channel),
+                optional_to_string(stream.publisher_position, "null"),
+                optional_to_string(stream.publisher_limit, "null"),
+                optional_to_string(stream.sender_position, "null"),
+                optional_to_string(stream.sender_limit, "null"),
+                optional_to_string(stream.receiver_hwm, "null"),
+                optional_to_string(stream.receiver_position, "null"),
+                optional_to_string(stream.publisher_backlog(), "null"),
+                optional_to_string(stream.sender_backlog(), "null"),
+                subscribers.join(",")
+            )
+        })
+        .collect();
+
+    println!(
+        "{{\"timestamp\":{},\"streams\":[{}]}}",
+        unix_time_ms(),
+        streams_json.join(",")
+    );
+}
+
+fn main() {
+    pretty_env_logger::init();
+    ctrlc::set_handler(move || {
+        sig_int_handler();
+    })
+    .expect("Error setting Ctrl-C handler");
+
+    let settings = parse_cmd_line();
+    let filename = settings.base_path.clone() + "/" + CNC_FILE;
+
+    let cnc_file = MemoryMappedFile::map_existing(filename, false).expect("Can't map file");
+    let cnc_version = cnc_file_descriptor::cnc_version_volatile(&cnc_file);
+
+    if semantic_version_major(cnc_version) != semantic_version_major(CNC_VERSION) {
+        panic!(
+            "CNC version is not supported: file={} app={}",
+            semantic_version_to_string(cnc_version),
+            semantic_version_to_string(CNC_VERSION)
+        );
+    }
+
+    let counters = CountersReader::new(
+        cnc_file_descriptor::create_counter_metadata_buffer(&cnc_file),
+        cnc_file_descriptor::create_counter_values_buffer(&cnc_file),
+    );
+
+    while RUNNING.load(Ordering::SeqCst) {
+        let streams = stream_stat::stream_positions(&counters);
+
+        if settings.json {
+            print_json(&streams, settings.slow_threshold_bytes);
+        } else {
+            print_text(&streams, settings.slow_threshold_bytes);
+        }
+
+        if settings.single_shot {
+            break;
+        }
+
+        thread::sleep(Duration::from_millis(settings.update_interval_ms));
+    }
+}
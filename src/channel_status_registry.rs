@@ -0,0 +1,253 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::collections::HashMap;
+use std::ffi::CString;
+
+use crate::concurrent::counters::CountersReader;
+use crate::concurrent::status::status_indicator_reader::{
+    CHANNEL_ENDPOINT_ERRORED, CHANNEL_ENDPOINT_INITIALIZING, NO_ID_ALLOCATED,
+};
+use crate::utils::types::Moment;
+
+/// Time to wait for the ChannelEndpointException of the driver after the channel status counter went ERRORED
+/// before the channel is reported without the error text.
+pub const ERROR_MESSAGE_WAIT_MS: Moment = 1000;
+
+/// Channel endpoint of a publication or subscription which has gone into ERRORED state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErroredChannel {
+    pub registration_id: i64,
+    pub stream_id: i32,
+    pub channel: CString,
+    pub channel_status_id: i32,
+    /// Text of the ChannelEndpointException sent by the driver, if it was received.
+    pub error_message: Option<String>,
+}
+
+struct ChannelStatusEntry {
+    stream_id: i32,
+    channel: CString,
+    channel_status_id: i32,
+    last_status: i64,
+    error_message: Option<String>,
+    errored_at_ms: Option<Moment>,
+    is_reported: bool,
+}
+
+/**
+ * Keeps track of the channel status counters of live publications and subscriptions and detects
+ * transitions of the channel endpoints to ERRORED state.
+ *
+ * Status is checked by polling the counters (see poll()) and, additionally, on channel endpoint errors
+ * received from the driver. Each registration is reported at most once. As the driver sets the counter
+ * before it sends the error, a registration found ERRORED by polling is held back for ERROR_MESSAGE_WAIT_MS
+ * so it can be reported with the error text of the driver.
+ */
+#[derive(Default)]
+pub struct ChannelStatusRegistry {
+    entries: HashMap<i64, ChannelStatusEntry>,
+}
+
+impl ChannelStatusRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts watching channel status counter of the registration. Registrations without channel status
+    /// counter (e.g. IPC) are ignored.
+    pub fn add(&mut self, registration_id: i64, stream_id: i32, channel: CString, channel_status_id: i32) {
+        if channel_status_id == NO_ID_ALLOCATED {
+            return;
+        }
+
+        self.entries.insert(registration_id, ChannelStatusEntry {
+            stream_id,
+            channel,
+            channel_status_id,
+            last_status: CHANNEL_ENDPOINT_INITIALIZING,
+            error_message: None,
+            errored_at_ms: None,
+            is_reported: false,
+        });
+    }
+
+    pub fn remove(&mut self, registration_id: i64) {
+        self.entries.remove(&registration_id);
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn contains(&self, registration_id: i64) -> bool {
+        self.entries.contains_key(&registration_id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Records the error text of a channel endpoint error sent by the driver for `channel_status_id`
+    /// and returns all not yet reported registrations using this channel endpoint.
+    pub fn on_channel_endpoint_error(&mut self, channel_status_id: i32, error_message: &str) -> Vec<ErroredChannel> {
+        let mut errored = Vec::new();
+
+        for (registration_id, entry) in self.entries.iter_mut() {
+            if entry.channel_status_id == channel_status_id {
+                entry.error_message = Some(String::from(error_message));
+                entry.last_status = CHANNEL_ENDPOINT_ERRORED;
+
+                if !entry.is_reported {
+                    entry.is_reported = true;
+                    errored.push(Self::errored_channel(*registration_id, entry));
+                }
+            }
+        }
+
+        errored
+    }
+
+    /// Reads channel status counters and returns registrations which went ERRORED at least
+    /// ERROR_MESSAGE_WAIT_MS ago without the driver sending the error text.
+    pub fn poll(&mut self, counters_reader: &CountersReader, now_ms: Moment) -> Vec<ErroredChannel> {
+        let mut errored = Vec::new();
+
+        for (registration_id, entry) in self.entries.iter_mut() {
+            if let Ok(value) = counters_reader.counter_value(entry.channel_status_id) {
+                let status = value as i64;
+
+                if status == CHANNEL_ENDPOINT_ERRORED && entry.last_status != CHANNEL_ENDPOINT_ERRORED {
+                    entry.errored_at_ms = Some(now_ms);
+                }
+
+                entry.last_status = status;
+            }
+
+            if let Some(errored_at_ms) = entry.errored_at_ms {
+                if !entry.is_reported && now_ms >= errored_at_ms + ERROR_MESSAGE_WAIT_MS {
+                    entry.is_reported = true;
+                    errored.push(Self::errored_channel(*registration_id, entry));
+                }
+            }
+        }
+
+        errored
+    }
+
+    fn errored_channel(registration_id: i64, entry: &ChannelStatusEntry) -> ErroredChannel {
+        ErroredChannel {
+            registration_id,
+            stream_id: entry.stream_id,
+            channel: entry.channel.clone(),
+            channel_status_id: entry.channel_status_id,
+            error_message: entry.error_message.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::concurrent::counters::CountersFixture;
+    use crate::concurrent::status::status_indicator_reader::CHANNEL_ENDPOINT_ACTIVE;
+
+    const NUM_COUNTERS: i32 = 4;
+    const CHANNEL: &str = "aeron:udp?endpoint=localhost:40123";
+
+    #[test]
+    fn should_report_transition_to_errored_once() {
+        let mut fixture = CountersFixture::new(NUM_COUNTERS);
+        let status_id = fixture.manager.allocate("channel status").unwrap();
+        let mut registry = ChannelStatusRegistry::new();
+
+        registry.add(10, 1001, CString::new(CHANNEL).unwrap(), status_id);
+
+        fixture.manager.set_counter_value(status_id, CHANNEL_ENDPOINT_ACTIVE as u64);
+        assert!(registry.poll(&fixture.reader(), 0).is_empty());
+
+        fixture.manager.set_counter_value(status_id, CHANNEL_ENDPOINT_ERRORED as u64);
+        assert!(registry.poll(&fixture.reader(), 100).is_empty());
+        assert!(registry.poll(&fixture.reader(), 100 + ERROR_MESSAGE_WAIT_MS - 1).is_empty());
+
+        let errored = registry.poll(&fixture.reader(), 100 + ERROR_MESSAGE_WAIT_MS);
+        assert_eq!(errored.len(), 1);
+        assert_eq!(errored[0].registration_id, 10);
+        assert_eq!(errored[0].stream_id, 1001);
+        assert_eq!(errored[0].channel, CString::new(CHANNEL).unwrap());
+        assert_eq!(errored[0].error_message, None);
+
+        assert!(registry.poll(&fixture.reader(), 200 + ERROR_MESSAGE_WAIT_MS).is_empty());
+    }
+
+    #[test]
+    fn should_report_driver_error_text_arriving_after_counter_errored() {
+        let mut fixture = CountersFixture::new(NUM_COUNTERS);
+        let status_id = fixture.manager.allocate("channel status").unwrap();
+        let mut registry = ChannelStatusRegistry::new();
+
+        registry.add(10, 1001, CString::new(CHANNEL).unwrap(), status_id);
+
+        fixture.manager.set_counter_value(status_id, CHANNEL_ENDPOINT_ERRORED as u64);
+        assert!(registry.poll(&fixture.reader(), 100).is_empty());
+
+        let errored = registry.on_channel_endpoint_error(status_id, "Address already in use");
+        assert_eq!(errored.len(), 1);
+        assert_eq!(errored[0].registration_id, 10);
+        assert_eq!(errored[0].error_message.as_deref(), Some("Address already in use"));
+
+        assert!(registry.poll(&fixture.reader(), 100 + ERROR_MESSAGE_WAIT_MS).is_empty());
+    }
+
+    #[test]
+    fn should_report_driver_error_text_for_all_users_of_endpoint() {
+        let fixture = CountersFixture::new(NUM_COUNTERS);
+        let mut registry = ChannelStatusRegistry::new();
+
+        registry.add(10, 1001, CString::new(CHANNEL).unwrap(), 2);
+        registry.add(11, 1002, CString::new(CHANNEL).unwrap(), 2);
+        registry.add(12, 1003, CString::new(CHANNEL).unwrap(), 3);
+
+        let mut errored = registry.on_channel_endpoint_error(2, "Address already in use");
+        errored.sort_by_key(|e| e.registration_id);
+
+        assert_eq!(errored.len(), 2);
+        assert_eq!(errored[0].registration_id, 10);
+        assert_eq!(errored[1].registration_id, 11);
+        assert_eq!(errored[1].error_message.as_deref(), Some("Address already in use"));
+
+        // Already reported registrations are not reported again by polling
+        assert!(registry.poll(&fixture.reader(), ERROR_MESSAGE_WAIT_MS).is_empty());
+    }
+
+    #[test]
+    fn should_ignore_registrations_without_channel_status() {
+        let mut registry = ChannelStatusRegistry::new();
+
+        registry.add(10, 1001, CString::new("aeron:ipc").unwrap(), NO_ID_ALLOCATED);
+        assert!(registry.is_empty());
+
+        registry.add(11, 1001, CString::new(CHANNEL).unwrap(), 1);
+        assert!(registry.contains(11));
+
+        registry.remove(11);
+        assert!(registry.is_empty());
+    }
+}
@@ -14,10 +14,13 @@
  * limitations under the License.
  */
 
-use std::collections::HashMap;
 use std::fmt::{self, Display, Formatter};
-use std::sync::{Arc, Mutex};
+use std::str::FromStr;
+use std::time::Duration;
 
+use crate::concurrent::logbuffer::frame_descriptor::FRAME_ALIGNMENT;
+use crate::concurrent::logbuffer::log_buffer_descriptor::{self, TERM_MAX_LENGTH};
+use crate::flow_control::{CongestionControl, FlowControl};
 use crate::utils::errors::{AeronError, IllegalArgumentError, IllegalStateError};
 
 pub const SPY_QUALIFIER: &str = "aeron-spy";
@@ -40,6 +43,7 @@ pub const MDC_CONTROL_PARAM_NAME: &str = "control";
 pub const MDC_CONTROL_MODE_PARAM_NAME: &str = "control-mode";
 pub const MDC_CONTROL_MODE_MANUAL: &str = "manual";
 pub const MDC_CONTROL_MODE_DYNAMIC: &str = "dynamic";
+pub const MDC_CONTROL_MODE_RESPONSE: &str = "response";
 pub const SESSION_ID_PARAM_NAME: &str = "session-id";
 pub const LINGER_PARAM_NAME: &str = "linger";
 pub const RELIABLE_STREAM_PARAM_NAME: &str = "reliable";
@@ -51,25 +55,83 @@ pub const EOS_PARAM_NAME: &str = "eos";
 pub const TETHER_PARAM_NAME: &str = "tether";
 pub const GROUP_PARAM_NAME: &str = "group";
 pub const REJOIN_PARAM_NAME: &str = "rejoin";
+pub const SOCKET_SNDBUF_PARAM_NAME: &str = "so-sndbuf";
+pub const SOCKET_RCVBUF_PARAM_NAME: &str = "so-rcvbuf";
+pub const RECEIVER_WINDOW_LENGTH_PARAM_NAME: &str = "rcv-wnd";
+pub const NAK_DELAY_PARAM_NAME: &str = "nak-delay";
+pub const UNTETHERED_WINDOW_LIMIT_TIMEOUT_PARAM_NAME: &str = "untethered-window-limit-timeout";
+pub const UNTETHERED_RESTING_TIMEOUT_PARAM_NAME: &str = "untethered-resting-timeout";
+pub const MAX_RESEND_PARAM_NAME: &str = "max-resend";
+pub const SPIES_SIMULATE_CONNECTION_PARAM_NAME: &str = "ssc";
+pub const GROUP_TAG_PARAM_NAME: &str = "gtag";
+pub const FLOW_CONTROL_PARAM_NAME: &str = "fc";
+pub const MEDIA_RCV_TIMESTAMP_OFFSET_PARAM_NAME: &str = "media-rcv-ts-offset";
+pub const CHANNEL_RCV_TIMESTAMP_OFFSET_PARAM_NAME: &str = "channel-rcv-ts-offset";
+pub const CHANNEL_SND_TIMESTAMP_OFFSET_PARAM_NAME: &str = "channel-snd-ts-offset";
+pub const RESERVED_OFFSET: &str = "reserved";
+pub const STREAM_ID_PARAM_NAME: &str = "stream-id";
+pub const PUBLICATION_WINDOW_LENGTH_PARAM_NAME: &str = "pub-wnd";
+pub const RESPONSE_ENDPOINT_PARAM_NAME: &str = "response-endpoint";
+pub const RESPONSE_CORRELATION_ID_PARAM_NAME: &str = "response-correlation-id";
+pub const PROTOTYPE_CORRELATION_ID: &str = "prototype";
 
 pub const CONGESTION_CONTROL_PARAM_NAME: &str = "cc";
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum State {
     Media,
     ParamsKey,
     ParamsValue,
 }
 
-#[derive(Debug)]
+/// Host and port of an endpoint or control parameter, e.g. "224.10.9.8:40123" or "[::1]:40123".
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ChannelEndpoint {
+    pub host: String,
+    pub port: u16,
+}
+
+impl FromStr for ChannelEndpoint {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (host, port) = value.rsplit_once(':').ok_or_else(|| String::from("expected <host>:<port>"))?;
+
+        if host.is_empty() || (host.contains(':') && !(host.starts_with('[') && host.ends_with(']'))) {
+            return Err(String::from("expected host name, IPv4 address or IPv6 address in brackets"));
+        }
+
+        let port = port.parse::<u16>().map_err(|_| format!("invalid port '{}'", port))?;
+
+        Ok(Self {
+            host: String::from(host),
+            port,
+        })
+    }
+}
+
+impl Display for ChannelEndpoint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.host, self.port)
+    }
+}
+
+/**
+ * Parser and value type of Aeron channel URIs, e.g. "aeron:udp?endpoint=224.10.9.8:40123|mtu=8k".
+ *
+ * Parameters keep the order they were given in so a parsed URI is displayed exactly as the input.
+ * Unknown parameters are preserved, typed accessors validate known ones when they are read.
+ * Equality doesn't depend on the order of parameters.
+ */
+#[derive(Debug, Clone, Default)]
 pub struct ChannelUri {
     prefix: String,
     media: String,
-    params: HashMap<String, String>,
+    params: Vec<(String, String)>,
 }
 
 impl ChannelUri {
-    pub fn new(prefix: String, media: String, params: HashMap<String, String>) -> Self {
+    pub fn new(prefix: String, media: String, params: Vec<(String, String)>) -> Self {
         Self { prefix, media, params }
     }
 
@@ -98,33 +160,44 @@ impl ChannelUri {
         AERON_SCHEME
     }
 
+    #[inline]
+    pub fn is_udp(&self) -> bool {
+        self.media == UDP_MEDIA
+    }
+
+    #[inline]
+    pub fn is_ipc(&self) -> bool {
+        self.media == IPC_MEDIA
+    }
+
     #[inline]
     pub fn get(&self, key: &str) -> &str {
-        if let Some(value) = self.params.get(key) {
-            value
-        } else {
-            ""
-        }
+        self.get_or_default(key, "")
     }
 
     #[inline]
     pub fn get_or_default<'a>(&'a self, key: &str, default_value: &'a str) -> &'a str {
-        if let Some(value) = self.params.get(key) {
+        if let Some((_key, value)) = self.params.iter().find(|(param_key, _value)| param_key == key) {
             value
         } else {
             default_value
         }
     }
 
+    /// Set the parameter, a parameter which is already present keeps its position.
     #[inline]
     pub fn put(&mut self, key: &str, value: String) {
-        self.params.insert(String::from(key), value);
+        if let Some((_key, param_value)) = self.params.iter_mut().find(|(param_key, _value)| param_key == key) {
+            *param_value = value;
+        } else {
+            self.params.push((String::from(key), value));
+        }
     }
 
     #[inline]
     pub fn remove(&mut self, key: &str) -> String {
-        if let Some(result) = self.params.remove(key) {
-            result
+        if let Some(index) = self.params.iter().position(|(param_key, _value)| param_key == key) {
+            self.params.remove(index).1
         } else {
             String::default()
         }
@@ -132,10 +205,15 @@ impl ChannelUri {
 
     #[inline]
     pub fn contains_key(&self, key: &str) -> bool {
-        self.params.get(key).is_some()
+        self.params.iter().any(|(param_key, _value)| param_key == key)
+    }
+
+    /// Parameters in the order they were given.
+    pub fn params(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.params.iter().map(|(key, value)| (key.as_str(), value.as_str()))
     }
 
-    pub fn parse(uri: &str) -> Result<Arc<Mutex<Self>>, AeronError> {
+    pub fn parse(uri: &str) -> Result<Self, AeronError> {
         let mut position = 0;
         let prefix;
 
@@ -153,13 +231,13 @@ impl ChannelUri {
         }
 
         let mut builder = String::new();
-        let mut params: HashMap<String, String> = HashMap::new();
+        let mut params: Vec<(String, String)> = Vec::new();
         let mut media = String::new();
         let mut key = String::new();
         let mut state = State::Media;
 
-        for i in position..uri.len() {
-            let c: char = uri.chars().nth(i).unwrap();
+        // Indices in errors are byte offsets in the uri
+        for (i, c) in uri[position..].char_indices().map(|(i, c)| (i + position, c)) {
             match state {
                 State::Media => match c {
                     '?' => {
@@ -202,7 +280,7 @@ impl ChannelUri {
                 },
                 State::ParamsValue => {
                     if c == '|' {
-                        params.insert(key.clone(), builder.clone());
+                        Self::push_param(&mut params, &key, &builder)?;
                         builder.clear();
                         state = State::ParamsKey;
                     } else {
@@ -220,26 +298,234 @@ impl ChannelUri {
                 }
             },
             State::ParamsValue => {
-                params.insert(key, builder);
+                Self::push_param(&mut params, &key, &builder)?;
             },
             _ => {
                 return Err(IllegalArgumentError::NoMoreInputFound { state }.into());
             },
         }
 
-        Ok(Arc::new(Mutex::new(ChannelUri::new(String::from(prefix), media, params))))
+        Ok(ChannelUri::new(String::from(prefix), media, params))
+    }
+
+    /// Duplicated parameters are rejected as only one of them would be used by the driver.
+    fn push_param(params: &mut Vec<(String, String)>, key: &str, value: &str) -> Result<(), AeronError> {
+        if params.iter().any(|(param_key, _value)| param_key == key) {
+            return Err(Self::invalid_param(key, value, "parameter is duplicated"));
+        }
+
+        params.push((String::from(key), String::from(value)));
+        Ok(())
+    }
+
+    pub(crate) fn invalid_param(name: &str, value: &str, reason: &str) -> AeronError {
+        IllegalArgumentError::InvalidChannelParameter {
+            name: String::from(name),
+            value: String::from(value),
+            reason: String::from(reason),
+        }
+        .into()
+    }
+
+    /// Parse the parameter if it is present, errors of the parser are reported for the parameter.
+    fn typed_param<T>(&self, key: &str, parser: impl FnOnce(&str) -> Result<T, String>) -> Result<Option<T>, AeronError> {
+        match self.params.iter().find(|(param_key, _value)| param_key == key) {
+            Some((_key, value)) => parser(value)
+                .map(Some)
+                .map_err(|reason| Self::invalid_param(key, value, &reason)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn endpoint(&self) -> Result<Option<ChannelEndpoint>, AeronError> {
+        self.typed_param(ENDPOINT_PARAM_NAME, ChannelEndpoint::from_str)
+    }
+
+    pub fn control_endpoint(&self) -> Result<Option<ChannelEndpoint>, AeronError> {
+        self.typed_param(MDC_CONTROL_PARAM_NAME, ChannelEndpoint::from_str)
+    }
+
+    /// MTU in bytes, the value may have a k suffix, e.g. 8k.
+    pub fn mtu(&self) -> Result<Option<u32>, AeronError> {
+        self.typed_param(MTU_LENGTH_PARAM_NAME, |value| {
+            let mtu = parse_size(value)?;
+
+            if !(MIN_MTU_LENGTH..=MAX_MTU_LENGTH).contains(&mtu) {
+                return Err(format!("must be in range {}-{}", MIN_MTU_LENGTH, MAX_MTU_LENGTH));
+            }
+
+            if mtu & (FRAME_ALIGNMENT as u64 - 1) != 0 {
+                return Err(format!("must be a multiple of FRAME_ALIGNMENT={}", FRAME_ALIGNMENT));
+            }
+
+            Ok(mtu as u32)
+        })
+    }
+
+    /// Term length in bytes, the value may have a k, m or g suffix, e.g. 64k.
+    pub fn term_length(&self) -> Result<Option<i32>, AeronError> {
+        self.typed_param(TERM_LENGTH_PARAM_NAME, |value| {
+            let term_length = parse_size(value)?;
+
+            if term_length > TERM_MAX_LENGTH as u64 {
+                return Err(format!("must not be greater than {}", TERM_MAX_LENGTH));
+            }
+
+            log_buffer_descriptor::check_term_length(term_length as i32).map_err(|err| err.to_string())?;
+            Ok(term_length as i32)
+        })
+    }
+
+    pub fn ttl(&self) -> Result<Option<u8>, AeronError> {
+        self.typed_param(TTL_PARAM_NAME, |value| {
+            value
+                .parse::<u8>()
+                .map_err(|_| String::from("must be a number in range 0-255"))
+        })
+    }
+
+    /// Linger timeout, the value is in ns unless it has a ns, us, ms or s suffix, e.g. 5s.
+    pub fn linger(&self) -> Result<Option<Duration>, AeronError> {
+        self.typed_param(LINGER_PARAM_NAME, parse_duration)
+    }
+
+    pub fn session_id(&self) -> Result<Option<i32>, AeronError> {
+        self.typed_param(SESSION_ID_PARAM_NAME, |value| {
+            value.parse::<i32>().map_err(|_| String::from("must be a 32 bit number"))
+        })
+    }
+
+    /// Boolean parameter given as "true" or "false".
+    pub fn bool_param(&self, key: &str) -> Result<Option<bool>, AeronError> {
+        self.typed_param(key, |value| match value {
+            "true" => Ok(true),
+            "false" => Ok(false),
+            _ => Err(String::from("must be true or false")),
+        })
+    }
+
+    pub fn reliable(&self) -> Result<Option<bool>, AeronError> {
+        self.bool_param(RELIABLE_STREAM_PARAM_NAME)
+    }
+
+    pub fn sparse(&self) -> Result<Option<bool>, AeronError> {
+        self.bool_param(SPARSE_PARAM_NAME)
+    }
+
+    pub fn eos(&self) -> Result<Option<bool>, AeronError> {
+        self.bool_param(EOS_PARAM_NAME)
+    }
+
+    pub fn tether(&self) -> Result<Option<bool>, AeronError> {
+        self.bool_param(TETHER_PARAM_NAME)
+    }
+
+    pub fn group(&self) -> Result<Option<bool>, AeronError> {
+        self.bool_param(GROUP_PARAM_NAME)
+    }
+
+    pub fn rejoin(&self) -> Result<Option<bool>, AeronError> {
+        self.bool_param(REJOIN_PARAM_NAME)
+    }
+
+    pub fn flow_control(&self) -> Result<Option<FlowControl>, AeronError> {
+        self.typed_param(FLOW_CONTROL_PARAM_NAME, FlowControl::from_str)
+    }
+
+    pub fn congestion_control(&self) -> Result<Option<CongestionControl>, AeronError> {
+        self.typed_param(CONGESTION_CONTROL_PARAM_NAME, CongestionControl::from_str)
     }
 
     #[inline]
     pub fn add_session_id(channel: &str, session_id: i32) -> Result<String, AeronError> {
-        let channel_uri = Self::parse(channel)?;
-        let mut channel_guard = channel_uri.lock().unwrap();
-        channel_guard.put(SESSION_ID_PARAM_NAME, session_id.to_string());
+        let mut channel_uri = Self::parse(channel)?;
+        channel_uri.put(SESSION_ID_PARAM_NAME, session_id.to_string());
+
+        Ok(channel_uri.to_string())
+    }
+
+    /**
+     * Derive the channel to spy on the local publications of a UDP channel.
+     *
+     * @param channel of the publications, it may already be a spy channel.
+     * @return the channel with the aeron-spy prefix.
+     */
+    pub fn spy_channel(channel: &str) -> Result<String, AeronError> {
+        let mut channel_uri = Self::parse(channel)?;
+        if !channel_uri.is_udp() {
+            return Err(IllegalArgumentError::InvalidMedia(channel_uri.media()).into());
+        }
+
+        channel_uri.set_prefix(String::new());
+
+        Ok(format!("{}{}", SPY_PREFIX, channel_uri))
+    }
+}
+
+const MIN_MTU_LENGTH: u64 = 32;
+const MAX_MTU_LENGTH: u64 = 65504;
+
+/// Parse size with an optional k, m or g suffix (case insensitive) as used by the driver.
+pub(crate) fn parse_size(value: &str) -> Result<u64, String> {
+    let (number, multiplier) = match value.chars().last() {
+        Some('k') | Some('K') => (&value[..value.len() - 1], 1024),
+        Some('m') | Some('M') => (&value[..value.len() - 1], 1024 * 1024),
+        Some('g') | Some('G') => (&value[..value.len() - 1], 1024 * 1024 * 1024),
+        _ => (value, 1),
+    };
+
+    number
+        .parse::<u64>()
+        .ok()
+        .and_then(|number| number.checked_mul(multiplier))
+        .ok_or_else(|| String::from("must be a size in bytes with an optional k, m or g suffix"))
+}
+
+/// Parse duration with an optional ns, us, ms or s suffix, without a suffix the value is in ns.
+pub(crate) fn parse_duration(value: &str) -> Result<Duration, String> {
+    let (number, nanos_per_unit) = if let Some(number) = value.strip_suffix("ns") {
+        (number, 1)
+    } else if let Some(number) = value.strip_suffix("us") {
+        (number, 1_000)
+    } else if let Some(number) = value.strip_suffix("ms") {
+        (number, 1_000_000)
+    } else if let Some(number) = value.strip_suffix('s') {
+        (number, 1_000_000_000)
+    } else {
+        (value, 1)
+    };
+
+    number
+        .parse::<u64>()
+        .ok()
+        .and_then(|number| number.checked_mul(nanos_per_unit))
+        .map(Duration::from_nanos)
+        .ok_or_else(|| String::from("must be a duration with an optional ns, us, ms or s suffix"))
+}
+
+impl FromStr for ChannelUri {
+    type Err = AeronError;
+
+    fn from_str(uri: &str) -> Result<Self, Self::Err> {
+        Self::parse(uri)
+    }
+}
 
-        Ok(channel_guard.to_string())
+impl PartialEq for ChannelUri {
+    fn eq(&self, other: &Self) -> bool {
+        // Parameters are unique so equal length and containment in the other make them equal
+        self.prefix == other.prefix
+            && self.media == other.media
+            && self.params.len() == other.params.len()
+            && self
+                .params
+                .iter()
+                .all(|(key, value)| other.contains_key(key) && other.get(key) == value)
     }
 }
 
+impl Eq for ChannelUri {}
+
 impl Display for ChannelUri {
     #[inline]
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
@@ -273,26 +559,45 @@ mod tests {
     use galvanic_assert::matchers::any_value;
     use galvanic_assert::{assert_that, has_structure, structure};
 
-    use crate::channel_uri::{ChannelUri, SPY_QUALIFIER, UDP_MEDIA};
+    use std::time::Duration;
+
+    use crate::channel_uri::{ChannelEndpoint, ChannelUri, SPY_QUALIFIER, UDP_MEDIA};
     use crate::channel_uri_string_builder::ChannelUriStringBuilder;
-    use crate::utils::errors::AeronError;
+    use crate::utils::errors::{AeronError, IllegalArgumentError};
+
+    #[test]
+    fn should_derive_spy_channel() {
+        assert_eq!(
+            ChannelUri::spy_channel("aeron:udp?endpoint=localhost:40123|session-id=5").unwrap(),
+            "aeron-spy:aeron:udp?endpoint=localhost:40123|session-id=5"
+        );
+        assert_eq!(
+            ChannelUri::spy_channel("aeron:udp?endpoint=localhost:40123|x-custom=1").unwrap(),
+            "aeron-spy:aeron:udp?endpoint=localhost:40123|x-custom=1"
+        );
+        assert_eq!(
+            ChannelUri::spy_channel("aeron-spy:aeron:udp?endpoint=224.0.1.1:40456").unwrap(),
+            "aeron-spy:aeron:udp?endpoint=224.0.1.1:40456"
+        );
+        assert!(matches!(
+            ChannelUri::spy_channel("aeron:ipc"),
+            Err(AeronError::IllegalArgument(IllegalArgumentError::InvalidMedia(_)))
+        ));
+    }
 
     #[test]
     fn should_parse_simple_default_uris() {
         let channel_uri = ChannelUri::parse("aeron:udp").expect("Can't parse uri");
-        let channel_guard = channel_uri.lock().unwrap();
-        assert_eq!(channel_guard.prefix(), "");
-        assert_eq!(channel_guard.media(), "udp");
+        assert_eq!(channel_uri.prefix(), "");
+        assert_eq!(channel_uri.media(), "udp");
 
         let channel_uri = ChannelUri::parse("aeron:ipc").expect("Can't parse uri");
-        let channel_guard = channel_uri.lock().unwrap();
-        assert_eq!(channel_guard.prefix(), "");
-        assert_eq!(channel_guard.media(), "ipc");
+        assert_eq!(channel_uri.prefix(), "");
+        assert_eq!(channel_uri.media(), "ipc");
 
         let channel_uri = ChannelUri::parse("aeron-spy:aeron:udp").expect("Can't parse uri");
-        let channel_guard = channel_uri.lock().unwrap();
-        assert_eq!(channel_guard.prefix(), "aeron-spy");
-        assert_eq!(channel_guard.media(), "udp");
+        assert_eq!(channel_uri.prefix(), "aeron-spy");
+        assert_eq!(channel_uri.media(), "udp");
     }
 
     #[test]
@@ -337,45 +642,39 @@ mod tests {
     #[test]
     fn should_parse_with_single_parameter() {
         let channel_uri = ChannelUri::parse("aeron:udp?endpoint=224.10.9.8").expect("Can't parse uri");
-        let channel_guard = channel_uri.lock().unwrap();
-        assert_eq!(channel_guard.get("endpoint"), "224.10.9.8");
+        assert_eq!(channel_uri.get("endpoint"), "224.10.9.8");
 
         let channel_uri = ChannelUri::parse("aeron:udp?address=224.10.9.8").expect("Can't parse uri");
-        let channel_guard = channel_uri.lock().unwrap();
-        assert_eq!(channel_guard.get("address"), "224.10.9.8");
+        assert_eq!(channel_uri.get("address"), "224.10.9.8");
 
         let channel_uri = ChannelUri::parse("aeron:udp?endpoint=224.10.9.8").expect("Can't parse uri");
-        let channel_guard = channel_uri.lock().unwrap();
-        assert_eq!(channel_guard.get("endpoint"), "224.10.9.8");
+        assert_eq!(channel_uri.get("endpoint"), "224.10.9.8");
     }
 
     #[test]
     fn should_parse_with_multiple_arguments() {
         let channel_uri =
             ChannelUri::parse("aeron:udp?endpoint=224.10.9.8|port=4567|interface=192.168.0.3|ttl=16").expect("Can't parse uri");
-        let channel_guard = channel_uri.lock().unwrap();
-        assert_eq!(channel_guard.get("endpoint"), "224.10.9.8");
-        assert_eq!(channel_guard.get("port"), "4567");
-        assert_eq!(channel_guard.get("interface"), "192.168.0.3");
-        assert_eq!(channel_guard.get("ttl"), "16");
+        assert_eq!(channel_uri.get("endpoint"), "224.10.9.8");
+        assert_eq!(channel_uri.get("port"), "4567");
+        assert_eq!(channel_uri.get("interface"), "192.168.0.3");
+        assert_eq!(channel_uri.get("ttl"), "16");
     }
 
     #[test]
     fn should_allow_return_default_if_param_not_specified() {
         let channel_uri = ChannelUri::parse("aeron:udp?endpoint=224.10.9.8").expect("Can't parse uri");
-        let channel_guard = channel_uri.lock().unwrap();
-        assert_eq!(channel_guard.get("endpoint"), "224.10.9.8");
-        assert_eq!(channel_guard.get("interface"), "");
-        assert_eq!(channel_guard.get_or_default("interface", "192.168.0.0"), "192.168.0.0");
+        assert_eq!(channel_uri.get("endpoint"), "224.10.9.8");
+        assert_eq!(channel_uri.get("interface"), "");
+        assert_eq!(channel_uri.get_or_default("interface", "192.168.0.0"), "192.168.0.0");
     }
 
     #[test]
     fn should_round_trip_to_string() {
         let uri_string = String::from("aeron:udp?endpoint=224.10.9.8:777");
         let channel_uri = ChannelUri::parse(&uri_string).expect("Can't parse uri");
-        let channel_guard = channel_uri.lock().unwrap();
 
-        assert_eq!(channel_guard.to_string(), uri_string);
+        assert_eq!(channel_uri.to_string(), uri_string);
     }
 
     #[test]
@@ -386,8 +685,7 @@ mod tests {
         let uri_string = builder.build();
 
         let channel_uri = ChannelUri::parse(&uri_string).expect("Can't parse uri");
-        let channel_guard = channel_uri.lock().unwrap();
-        assert_eq!(channel_guard.to_string(), uri_string);
+        assert_eq!(channel_uri.to_string(), uri_string);
     }
 
     #[test]
@@ -403,7 +701,91 @@ mod tests {
         let uri_string = builder.build();
 
         let channel_uri = ChannelUri::parse(&uri_string).expect("Can't parse uri");
-        let channel_guard = channel_uri.lock().unwrap();
-        assert_eq!(channel_guard.to_string(), uri_string);
+        assert_eq!(channel_uri.to_string(), uri_string);
+    }
+
+    #[test]
+    fn should_round_trip_unknown_params_and_non_ascii_values_in_order() {
+        let uri_string = "aeron:udp?mtu=8k|endpoint=[::1]:40123|x-custom=żółw|alias=ünïcode";
+        let channel_uri: ChannelUri = uri_string.parse().expect("Can't parse uri");
+
+        assert_eq!(channel_uri.get("x-custom"), "żółw");
+        assert_eq!(channel_uri.to_string(), uri_string);
+        assert_eq!(channel_uri.clone(), channel_uri);
+    }
+
+    #[test]
+    fn should_compare_params_regardless_of_order() {
+        let a = ChannelUri::parse("aeron:udp?endpoint=localhost:40123|ttl=4").unwrap();
+        let b = ChannelUri::parse("aeron:udp?ttl=4|endpoint=localhost:40123").unwrap();
+        let c = ChannelUri::parse("aeron:udp?ttl=5|endpoint=localhost:40123").unwrap();
+
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn should_read_typed_params() {
+        let channel_uri = ChannelUri::parse(
+            "aeron:udp?endpoint=224.10.9.8:40123|control=[::1]:40124|mtu=8k|term-length=64k|ttl=16|linger=5ms|\
+             reliable=false|sparse=true",
+        )
+        .unwrap();
+
+        assert_eq!(
+            channel_uri.endpoint().unwrap(),
+            Some(ChannelEndpoint {
+                host: String::from("224.10.9.8"),
+                port: 40123
+            })
+        );
+        assert_eq!(channel_uri.control_endpoint().unwrap().unwrap().host, "[::1]");
+        assert_eq!(channel_uri.mtu().unwrap(), Some(8192));
+        assert_eq!(channel_uri.term_length().unwrap(), Some(65536));
+        assert_eq!(channel_uri.ttl().unwrap(), Some(16));
+        assert_eq!(channel_uri.linger().unwrap(), Some(Duration::from_millis(5)));
+        assert_eq!(channel_uri.reliable().unwrap(), Some(false));
+        assert_eq!(channel_uri.sparse().unwrap(), Some(true));
+        assert_eq!(channel_uri.eos().unwrap(), None);
+        assert_eq!(channel_uri.session_id().unwrap(), None);
+    }
+
+    #[test]
+    fn should_report_invalid_param() {
+        let channel_uri = ChannelUri::parse("aeron:udp?endpoint=localhost|term-length=100000|mtu=33|eos=yes").unwrap();
+
+        for (result, expected_name) in [
+            (channel_uri.endpoint().map(|_| ()), "endpoint"),
+            (channel_uri.term_length().map(|_| ()), "term-length"),
+            (channel_uri.mtu().map(|_| ()), "mtu"),
+            (channel_uri.eos().map(|_| ()), "eos"),
+        ] {
+            match result {
+                Err(AeronError::IllegalArgument(IllegalArgumentError::InvalidChannelParameter { name, .. })) => {
+                    assert_eq!(name, expected_name)
+                },
+                other => panic!("Unexpected result for {}: {:?}", expected_name, other),
+            }
+        }
+    }
+
+    #[test]
+    fn should_reject_duplicated_param() {
+        let result = ChannelUri::parse("aeron:udp?ttl=1|ttl=2");
+        assert_that!(&result.unwrap_err(), has_structure!(AeronError::IllegalArgument[any_value()]));
+    }
+
+    #[test]
+    fn should_keep_position_of_replaced_param() {
+        let mut channel_uri = ChannelUri::parse("aeron:udp?endpoint=localhost:40123|ttl=4").unwrap();
+        channel_uri.put("endpoint", String::from("localhost:40124"));
+        channel_uri.put("session-id", String::from("7"));
+
+        assert_eq!(
+            channel_uri.to_string(),
+            "aeron:udp?endpoint=localhost:40124|ttl=4|session-id=7"
+        );
+        assert_eq!(channel_uri.remove("ttl"), "4");
+        assert_eq!(channel_uri.session_id().unwrap(), Some(7));
     }
 }
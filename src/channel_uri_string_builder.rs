@@ -14,10 +14,19 @@
  * limitations under the License.
  */
 
-use crate::channel_uri;
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+use crate::channel_uri::{self, ChannelUri};
 use crate::concurrent::logbuffer;
+use crate::concurrent::logbuffer::frame_descriptor::FRAME_ALIGNMENT;
+use crate::concurrent::logbuffer::log_buffer_descriptor;
+use crate::flow_control::{CongestionControl, FlowControl};
 use crate::utils::errors::{AeronError, IllegalArgumentError};
 
+/// Upper bound of max-resend accepted by the driver.
+const MAX_RESEND_MAX: u32 = 16;
+
 #[derive(Debug)]
 struct Value {
     value: i64,
@@ -62,6 +71,24 @@ pub struct ChannelUriStringBuilder {
     tether: Option<Value>,
     group: Option<Value>,
     rejoin: Option<Value>,
+    socket_sndbuf_length: Option<Value>,
+    socket_rcvbuf_length: Option<Value>,
+    receiver_window_length: Option<Value>,
+    nak_delay: Option<Value>,
+    untethered_window_limit_timeout: Option<Value>,
+    untethered_resting_timeout: Option<Value>,
+    max_resend: Option<Value>,
+    spies_simulate_connection: Option<Value>,
+    group_tag: Option<Value>,
+    flow_control: Option<String>,
+    media_receive_timestamp_offset: Option<String>,
+    channel_receive_timestamp_offset: Option<String>,
+    channel_send_timestamp_offset: Option<String>,
+    stream_id: Option<Value>,
+    publication_window_length: Option<Value>,
+    response_endpoint: Option<String>,
+    response_correlation_id: Option<String>,
+    extra_params: Vec<(String, String)>,
 
     is_session_id_tagged: bool,
 }
@@ -92,6 +119,24 @@ impl ChannelUriStringBuilder {
         self.tether = None;
         self.group = None;
         self.rejoin = None;
+        self.socket_sndbuf_length = None;
+        self.socket_rcvbuf_length = None;
+        self.receiver_window_length = None;
+        self.nak_delay = None;
+        self.untethered_window_limit_timeout = None;
+        self.untethered_resting_timeout = None;
+        self.max_resend = None;
+        self.spies_simulate_connection = None;
+        self.group_tag = None;
+        self.flow_control = None;
+        self.media_receive_timestamp_offset = None;
+        self.channel_receive_timestamp_offset = None;
+        self.channel_send_timestamp_offset = None;
+        self.stream_id = None;
+        self.publication_window_length = None;
+        self.response_endpoint = None;
+        self.response_correlation_id = None;
+        self.extra_params.clear();
         self.is_session_id_tagged = false;
     }
 
@@ -143,11 +188,14 @@ impl ChannelUriStringBuilder {
 
     #[inline]
     pub fn control_mode(&mut self, control_mode: &str) -> Result<&mut Self, AeronError> {
-        if !control_mode.eq(channel_uri::MDC_CONTROL_MODE_MANUAL) && !control_mode.eq(channel_uri::MDC_CONTROL_MODE_DYNAMIC) {
+        if !control_mode.eq(channel_uri::MDC_CONTROL_MODE_MANUAL)
+            && !control_mode.eq(channel_uri::MDC_CONTROL_MODE_DYNAMIC)
+            && !control_mode.eq(channel_uri::MDC_CONTROL_MODE_RESPONSE)
+        {
             return Err(IllegalArgumentError::InvalidControlMode(control_mode.to_string()).into());
         }
 
-        self.prefix = Some(String::from(control_mode));
+        self.control_mode = Some(String::from(control_mode));
         Ok(self)
     }
 
@@ -163,12 +211,19 @@ impl ChannelUriStringBuilder {
         self
     }
 
+    /// Prefer congestion_control_strategy which can't be given an unknown strategy.
     #[inline]
     pub fn congestion_control(&mut self, congestrion_control: &str) -> &mut Self {
         self.cc = Some(String::from(congestrion_control));
         self
     }
 
+    #[inline]
+    pub fn congestion_control_strategy(&mut self, congestion_control: CongestionControl) -> &mut Self {
+        self.cc = Some(congestion_control.to_string());
+        self
+    }
+
     #[inline]
     pub fn reliable(&mut self, reliable: bool) -> &mut Self {
         let value = if reliable { 1 } else { 0 };
@@ -214,6 +269,9 @@ impl ChannelUriStringBuilder {
     #[inline]
     pub fn term_length(&mut self, term_length: i32) -> Result<&mut Self, AeronError> {
         logbuffer::log_buffer_descriptor::check_term_length(term_length)?;
+        if let Some(window_length) = &self.publication_window_length {
+            Self::check_publication_window_length(window_length.value, term_length as i64)?;
+        }
         self.term_length = Some(Value::new(term_length as i64));
         Ok(self)
     }
@@ -248,9 +306,55 @@ impl ChannelUriStringBuilder {
         Ok(self)
     }
 
+    /**
+     * Set initial-term-id, term-id, term-offset and term-length so an ExclusivePublication starts at the position,
+     * e.g. to continue a stream at the position returned by ExclusivePublication::resume_position.
+     *
+     * @param position        the publication should start at, must be frame aligned.
+     * @param initial_term_id of the stream.
+     * @param term_length     of the stream.
+     * @return this for a fluent API.
+     */
+    pub fn initial_position(&mut self, position: i64, initial_term_id: i32, term_length: i32) -> Result<&mut Self, AeronError> {
+        let position_bits_to_shift = log_buffer_descriptor::position_bits_to_shift(term_length)?;
+        // Term count must fit into i32 so the position can be represented by term id and offset
+        let max_position = ((i32::MAX as i64 + 1) << position_bits_to_shift) - 1;
+
+        if position < 0 || position > max_position {
+            return Err(IllegalArgumentError::PositionOutOfRange {
+                position,
+                left_bound: 0,
+                right_bound: max_position,
+            }
+            .into());
+        }
+
+        if position & (FRAME_ALIGNMENT - 1) as i64 != 0 {
+            return Err(IllegalArgumentError::NewPositionNotAlignedToFrameAlignment {
+                new_position: position,
+                frame_alignment: FRAME_ALIGNMENT,
+            }
+            .into());
+        }
+
+        self.initial_term_id = Some(Value::new(initial_term_id as i64));
+        self.term_id = Some(Value::new(log_buffer_descriptor::compute_term_id_from_position(
+            position,
+            position_bits_to_shift,
+            initial_term_id,
+        ) as i64));
+        self.term_offset = Some(Value::new(log_buffer_descriptor::compute_term_offset_from_position(
+            position,
+            position_bits_to_shift,
+        ) as i64));
+        self.term_length = Some(Value::new(term_length as i64));
+
+        Ok(self)
+    }
+
     #[inline]
     pub fn session_id(&mut self, session_id: i32) -> &mut Self {
-        self.term_id = Some(Value::new(session_id as i64));
+        self.session_id = Some(Value::new(session_id as i64));
         self
     }
 
@@ -281,14 +385,14 @@ impl ChannelUriStringBuilder {
     #[inline]
     pub fn tether(&mut self, tether: bool) -> &mut Self {
         let value = if tether { 1 } else { 0 };
-        self.term_id = Some(Value::new(value));
+        self.tether = Some(Value::new(value));
         self
     }
 
     #[inline]
     pub fn group(&mut self, group: bool) -> &mut Self {
         let value = if group { 1 } else { 0 };
-        self.term_id = Some(Value::new(value));
+        self.group = Some(Value::new(value));
         self
     }
 
@@ -305,6 +409,169 @@ impl ChannelUriStringBuilder {
         self
     }
 
+    /// Length of SO_SNDBUF of the socket in bytes.
+    #[inline]
+    pub fn socket_sndbuf_length(&mut self, length: u32) -> Result<&mut Self, AeronError> {
+        self.socket_sndbuf_length = Some(Self::positive_length(channel_uri::SOCKET_SNDBUF_PARAM_NAME, length)?);
+        Ok(self)
+    }
+
+    /// Length of SO_RCVBUF of the socket in bytes.
+    #[inline]
+    pub fn socket_rcvbuf_length(&mut self, length: u32) -> Result<&mut Self, AeronError> {
+        self.socket_rcvbuf_length = Some(Self::positive_length(channel_uri::SOCKET_RCVBUF_PARAM_NAME, length)?);
+        Ok(self)
+    }
+
+    /// Initial receiver window length in bytes.
+    #[inline]
+    pub fn receiver_window_length(&mut self, length: u32) -> Result<&mut Self, AeronError> {
+        self.receiver_window_length = Some(Self::positive_length(channel_uri::RECEIVER_WINDOW_LENGTH_PARAM_NAME, length)?);
+        Ok(self)
+    }
+
+    #[inline]
+    pub fn nak_delay(&mut self, nak_delay_ns: i64) -> Result<&mut Self, AeronError> {
+        self.nak_delay = Some(Self::non_negative_ns(channel_uri::NAK_DELAY_PARAM_NAME, nak_delay_ns)?);
+        Ok(self)
+    }
+
+    #[inline]
+    pub fn untethered_window_limit_timeout(&mut self, timeout_ns: i64) -> Result<&mut Self, AeronError> {
+        self.untethered_window_limit_timeout = Some(Self::non_negative_ns(
+            channel_uri::UNTETHERED_WINDOW_LIMIT_TIMEOUT_PARAM_NAME,
+            timeout_ns,
+        )?);
+        Ok(self)
+    }
+
+    #[inline]
+    pub fn untethered_resting_timeout(&mut self, timeout_ns: i64) -> Result<&mut Self, AeronError> {
+        self.untethered_resting_timeout = Some(Self::non_negative_ns(
+            channel_uri::UNTETHERED_RESTING_TIMEOUT_PARAM_NAME,
+            timeout_ns,
+        )?);
+        Ok(self)
+    }
+
+    /// Max number of NAKed ranges to be resent at once.
+    #[inline]
+    pub fn max_resend(&mut self, max_resend: u32) -> Result<&mut Self, AeronError> {
+        if !(1..=MAX_RESEND_MAX).contains(&max_resend) {
+            return Err(ChannelUri::invalid_param(
+                channel_uri::MAX_RESEND_PARAM_NAME,
+                &max_resend.to_string(),
+                &format!("must be in range 1-{}", MAX_RESEND_MAX),
+            ));
+        }
+
+        self.max_resend = Some(Value::new(max_resend as i64));
+        Ok(self)
+    }
+
+    #[inline]
+    pub fn spies_simulate_connection(&mut self, spies_simulate_connection: bool) -> &mut Self {
+        let value = if spies_simulate_connection { 1 } else { 0 };
+        self.spies_simulate_connection = Some(Value::new(value));
+        self
+    }
+
+    #[inline]
+    pub fn group_tag(&mut self, group_tag: i64) -> &mut Self {
+        self.group_tag = Some(Value::new(group_tag));
+        self
+    }
+
+    /// Flow control strategy with its options, rendered e.g. as "min,t:5s" or "tagged,g:123/5".
+    #[inline]
+    pub fn flow_control(&mut self, flow_control: &FlowControl) -> &mut Self {
+        self.flow_control = Some(flow_control.to_string());
+        self
+    }
+
+    #[inline]
+    pub fn media_receive_timestamp_offset(&mut self, offset: &str) -> Result<&mut Self, AeronError> {
+        self.media_receive_timestamp_offset = Some(Self::timestamp_offset(
+            channel_uri::MEDIA_RCV_TIMESTAMP_OFFSET_PARAM_NAME,
+            offset,
+        )?);
+        Ok(self)
+    }
+
+    #[inline]
+    pub fn channel_receive_timestamp_offset(&mut self, offset: &str) -> Result<&mut Self, AeronError> {
+        self.channel_receive_timestamp_offset = Some(Self::timestamp_offset(
+            channel_uri::CHANNEL_RCV_TIMESTAMP_OFFSET_PARAM_NAME,
+            offset,
+        )?);
+        Ok(self)
+    }
+
+    #[inline]
+    pub fn channel_send_timestamp_offset(&mut self, offset: &str) -> Result<&mut Self, AeronError> {
+        self.channel_send_timestamp_offset = Some(Self::timestamp_offset(
+            channel_uri::CHANNEL_SND_TIMESTAMP_OFFSET_PARAM_NAME,
+            offset,
+        )?);
+        Ok(self)
+    }
+
+    #[inline]
+    pub fn stream_id(&mut self, stream_id: i32) -> &mut Self {
+        self.stream_id = Some(Value::new(stream_id as i64));
+        self
+    }
+
+    /// Length of the publication window in bytes, at most half of the term length.
+    #[inline]
+    pub fn publication_window_length(&mut self, length: u32) -> Result<&mut Self, AeronError> {
+        let window_length = Self::positive_length(channel_uri::PUBLICATION_WINDOW_LENGTH_PARAM_NAME, length)?;
+        if let Some(term_length) = &self.term_length {
+            Self::check_publication_window_length(window_length.value, term_length.value)?;
+        }
+        self.publication_window_length = Some(window_length);
+        Ok(self)
+    }
+
+    fn check_publication_window_length(window_length: i64, term_length: i64) -> Result<(), AeronError> {
+        if window_length > term_length / 2 {
+            return Err(ChannelUri::invalid_param(
+                channel_uri::PUBLICATION_WINDOW_LENGTH_PARAM_NAME,
+                &window_length.to_string(),
+                &format!("must not exceed half of term-length={}", term_length),
+            ));
+        }
+        Ok(())
+    }
+
+    #[inline]
+    pub fn response_endpoint(&mut self, response_endpoint: &str) -> &mut Self {
+        self.response_endpoint = Some(String::from(response_endpoint));
+        self
+    }
+
+    /// Correlation id of the request publication a response publication responds to.
+    #[inline]
+    pub fn response_correlation_id(&mut self, correlation_id: i64) -> Result<&mut Self, AeronError> {
+        if correlation_id < 0 {
+            return Err(ChannelUri::invalid_param(
+                channel_uri::RESPONSE_CORRELATION_ID_PARAM_NAME,
+                &correlation_id.to_string(),
+                "must not be negative",
+            ));
+        }
+
+        self.response_correlation_id = Some(correlation_id.to_string());
+        Ok(self)
+    }
+
+    /// Mark the response publication as prototype to be used for responses of many requests.
+    #[inline]
+    pub fn response_correlation_id_prototype(&mut self) -> &mut Self {
+        self.response_correlation_id = Some(String::from(channel_uri::PROTOTYPE_CORRELATION_ID));
+        self
+    }
+
     #[inline]
     pub fn is_session_tagged(&mut self, is_session_tagged: bool) -> &mut Self {
         self.is_session_id_tagged = is_session_tagged;
@@ -369,7 +636,7 @@ impl ChannelUriStringBuilder {
         if let Some(session_id) = &self.session_id {
             sb += &format!(
                 "{}={}|",
-                channel_uri::TERM_ID_PARAM_NAME,
+                channel_uri::SESSION_ID_PARAM_NAME,
                 Self::prefix_tag(self.is_session_id_tagged, session_id)
             );
         }
@@ -418,6 +685,86 @@ impl ChannelUriStringBuilder {
             sb += &format!("{}={}|", channel_uri::REJOIN_PARAM_NAME, Value::bool_to_string(rejoin));
         }
 
+        if let Some(length) = &self.socket_sndbuf_length {
+            sb += &format!("{}={}|", channel_uri::SOCKET_SNDBUF_PARAM_NAME, length.value);
+        }
+
+        if let Some(length) = &self.socket_rcvbuf_length {
+            sb += &format!("{}={}|", channel_uri::SOCKET_RCVBUF_PARAM_NAME, length.value);
+        }
+
+        if let Some(length) = &self.receiver_window_length {
+            sb += &format!("{}={}|", channel_uri::RECEIVER_WINDOW_LENGTH_PARAM_NAME, length.value);
+        }
+
+        if let Some(nak_delay) = &self.nak_delay {
+            sb += &format!("{}={}|", channel_uri::NAK_DELAY_PARAM_NAME, nak_delay.value);
+        }
+
+        if let Some(timeout) = &self.untethered_window_limit_timeout {
+            sb += &format!(
+                "{}={}|",
+                channel_uri::UNTETHERED_WINDOW_LIMIT_TIMEOUT_PARAM_NAME,
+                timeout.value
+            );
+        }
+
+        if let Some(timeout) = &self.untethered_resting_timeout {
+            sb += &format!("{}={}|", channel_uri::UNTETHERED_RESTING_TIMEOUT_PARAM_NAME, timeout.value);
+        }
+
+        if let Some(max_resend) = &self.max_resend {
+            sb += &format!("{}={}|", channel_uri::MAX_RESEND_PARAM_NAME, max_resend.value);
+        }
+
+        if let Some(ssc) = &self.spies_simulate_connection {
+            sb += &format!(
+                "{}={}|",
+                channel_uri::SPIES_SIMULATE_CONNECTION_PARAM_NAME,
+                Value::bool_to_string(ssc)
+            );
+        }
+
+        if let Some(group_tag) = &self.group_tag {
+            sb += &format!("{}={}|", channel_uri::GROUP_TAG_PARAM_NAME, group_tag.value);
+        }
+
+        if let Some(fc) = &self.flow_control {
+            sb += &format!("{}={}|", channel_uri::FLOW_CONTROL_PARAM_NAME, fc);
+        }
+
+        if let Some(offset) = &self.media_receive_timestamp_offset {
+            sb += &format!("{}={}|", channel_uri::MEDIA_RCV_TIMESTAMP_OFFSET_PARAM_NAME, offset);
+        }
+
+        if let Some(offset) = &self.channel_receive_timestamp_offset {
+            sb += &format!("{}={}|", channel_uri::CHANNEL_RCV_TIMESTAMP_OFFSET_PARAM_NAME, offset);
+        }
+
+        if let Some(offset) = &self.channel_send_timestamp_offset {
+            sb += &format!("{}={}|", channel_uri::CHANNEL_SND_TIMESTAMP_OFFSET_PARAM_NAME, offset);
+        }
+
+        if let Some(stream_id) = &self.stream_id {
+            sb += &format!("{}={}|", channel_uri::STREAM_ID_PARAM_NAME, stream_id.value);
+        }
+
+        if let Some(length) = &self.publication_window_length {
+            sb += &format!("{}={}|", channel_uri::PUBLICATION_WINDOW_LENGTH_PARAM_NAME, length.value);
+        }
+
+        if let Some(response_endpoint) = &self.response_endpoint {
+            sb += &format!("{}={}|", channel_uri::RESPONSE_ENDPOINT_PARAM_NAME, response_endpoint);
+        }
+
+        if let Some(correlation_id) = &self.response_correlation_id {
+            sb += &format!("{}={}|", channel_uri::RESPONSE_CORRELATION_ID_PARAM_NAME, correlation_id);
+        }
+
+        for (key, value) in &self.extra_params {
+            sb += &format!("{}={}|", key, value);
+        }
+
         let last_char = sb.chars().last().unwrap();
 
         if last_char == '|' || last_char == '?' {
@@ -429,6 +776,156 @@ impl ChannelUriStringBuilder {
 }
 
 impl ChannelUriStringBuilder {
+    /**
+     * Create a builder seeded with the prefix, media and parameters of the channel, e.g. to take a base channel
+     * from configuration and override some of its parameters.
+     *
+     * Parameters are validated the same way as by the setters. Parameters not known to the builder are carried
+     * through as is and appended by build() after the known ones, in the order they appear in the channel.
+     *
+     * @param channel_uri to take the parameters from.
+     * @return builder with the parameters set.
+     */
+    pub fn from_channel_uri(channel_uri: &ChannelUri) -> Result<Self, AeronError> {
+        let mut builder = Self::default();
+
+        if !channel_uri.prefix().is_empty() {
+            builder.prefix(&channel_uri.prefix())?;
+        }
+        builder.media(&channel_uri.media())?;
+
+        for (key, value) in channel_uri.params() {
+            match key {
+                channel_uri::TAGS_PARAM_NAME => builder.tags(value),
+                channel_uri::ENDPOINT_PARAM_NAME => builder.endpoint(value),
+                channel_uri::INTERFACE_PARAM_NAME => builder.network_interface(value),
+                channel_uri::MDC_CONTROL_PARAM_NAME => builder.control_endpoint(value),
+                channel_uri::MDC_CONTROL_MODE_PARAM_NAME => builder.control_mode(value)?,
+                channel_uri::MTU_LENGTH_PARAM_NAME => builder.mtu(channel_uri.mtu()?.unwrap_or_default())?,
+                channel_uri::TERM_LENGTH_PARAM_NAME => builder.term_length(channel_uri.term_length()?.unwrap_or_default())?,
+                channel_uri::INITIAL_TERM_ID_PARAM_NAME => builder.initial_term_id(Self::parse_param(key, value)?),
+                channel_uri::TERM_ID_PARAM_NAME => builder.term_id(Self::parse_param(key, value)?),
+                channel_uri::TERM_OFFSET_PARAM_NAME => builder.term_offset(Self::parse_param(key, value)?)?,
+                channel_uri::SESSION_ID_PARAM_NAME => match value.strip_prefix(channel_uri::TAG_PREFIX) {
+                    Some(tag) => builder.session_id(Self::parse_param(key, tag)?).is_session_tagged(true),
+                    None => builder.session_id(Self::parse_param(key, value)?),
+                },
+                channel_uri::TTL_PARAM_NAME => builder.ttl(channel_uri.ttl()?.unwrap_or_default()),
+                channel_uri::RELIABLE_STREAM_PARAM_NAME => builder.reliable(Self::parse_bool(channel_uri, key)?),
+                channel_uri::LINGER_PARAM_NAME => builder.linger(Self::parse_duration_ns(key, value)?)?,
+                channel_uri::ALIAS_PARAM_NAME => builder.alias(value),
+                channel_uri::CONGESTION_CONTROL_PARAM_NAME => {
+                    builder.congestion_control_strategy(channel_uri.congestion_control()?.unwrap_or(CongestionControl::Static))
+                },
+                channel_uri::SPARSE_PARAM_NAME => builder.sparse(Self::parse_bool(channel_uri, key)?),
+                channel_uri::EOS_PARAM_NAME => builder.eos(Self::parse_bool(channel_uri, key)?),
+                channel_uri::TETHER_PARAM_NAME => builder.tether(Self::parse_bool(channel_uri, key)?),
+                channel_uri::GROUP_PARAM_NAME => builder.group(Self::parse_bool(channel_uri, key)?),
+                channel_uri::REJOIN_PARAM_NAME => builder.rejoin(Self::parse_bool(channel_uri, key)?),
+                channel_uri::SOCKET_SNDBUF_PARAM_NAME => builder.socket_sndbuf_length(Self::parse_size(key, value)?)?,
+                channel_uri::SOCKET_RCVBUF_PARAM_NAME => builder.socket_rcvbuf_length(Self::parse_size(key, value)?)?,
+                channel_uri::RECEIVER_WINDOW_LENGTH_PARAM_NAME => {
+                    builder.receiver_window_length(Self::parse_size(key, value)?)?
+                },
+                channel_uri::NAK_DELAY_PARAM_NAME => builder.nak_delay(Self::parse_duration_ns(key, value)?)?,
+                channel_uri::UNTETHERED_WINDOW_LIMIT_TIMEOUT_PARAM_NAME => {
+                    builder.untethered_window_limit_timeout(Self::parse_duration_ns(key, value)?)?
+                },
+                channel_uri::UNTETHERED_RESTING_TIMEOUT_PARAM_NAME => {
+                    builder.untethered_resting_timeout(Self::parse_duration_ns(key, value)?)?
+                },
+                channel_uri::MAX_RESEND_PARAM_NAME => builder.max_resend(Self::parse_param(key, value)?)?,
+                channel_uri::SPIES_SIMULATE_CONNECTION_PARAM_NAME => {
+                    builder.spies_simulate_connection(Self::parse_bool(channel_uri, key)?)
+                },
+                channel_uri::GROUP_TAG_PARAM_NAME => builder.group_tag(Self::parse_param(key, value)?),
+                channel_uri::FLOW_CONTROL_PARAM_NAME => {
+                    builder.flow_control(&channel_uri.flow_control()?.unwrap_or_else(FlowControl::max))
+                },
+                channel_uri::MEDIA_RCV_TIMESTAMP_OFFSET_PARAM_NAME => builder.media_receive_timestamp_offset(value)?,
+                channel_uri::CHANNEL_RCV_TIMESTAMP_OFFSET_PARAM_NAME => builder.channel_receive_timestamp_offset(value)?,
+                channel_uri::CHANNEL_SND_TIMESTAMP_OFFSET_PARAM_NAME => builder.channel_send_timestamp_offset(value)?,
+                channel_uri::STREAM_ID_PARAM_NAME => builder.stream_id(Self::parse_param(key, value)?),
+                channel_uri::PUBLICATION_WINDOW_LENGTH_PARAM_NAME => {
+                    builder.publication_window_length(Self::parse_size(key, value)?)?
+                },
+                channel_uri::RESPONSE_ENDPOINT_PARAM_NAME => builder.response_endpoint(value),
+                channel_uri::RESPONSE_CORRELATION_ID_PARAM_NAME if value == channel_uri::PROTOTYPE_CORRELATION_ID => {
+                    builder.response_correlation_id_prototype()
+                },
+                channel_uri::RESPONSE_CORRELATION_ID_PARAM_NAME => {
+                    builder.response_correlation_id(Self::parse_param(key, value)?)?
+                },
+                _ => {
+                    builder.extra_params.push((key.to_string(), value.to_string()));
+                    &mut builder
+                },
+            };
+        }
+
+        Ok(builder)
+    }
+
+    fn parse_param<T: FromStr>(key: &str, value: &str) -> Result<T, AeronError> {
+        value
+            .parse::<T>()
+            .map_err(|_| ChannelUri::invalid_param(key, value, "must be a number"))
+    }
+
+    fn parse_bool(channel_uri: &ChannelUri, key: &str) -> Result<bool, AeronError> {
+        Ok(channel_uri.bool_param(key)?.unwrap_or_default())
+    }
+
+    fn parse_size(key: &str, value: &str) -> Result<u32, AeronError> {
+        channel_uri::parse_size(value)
+            .and_then(|size| u32::try_from(size).map_err(|_| String::from("is too big")))
+            .map_err(|reason| ChannelUri::invalid_param(key, value, &reason))
+    }
+
+    fn parse_duration_ns(key: &str, value: &str) -> Result<i64, AeronError> {
+        channel_uri::parse_duration(value)
+            .and_then(|duration| i64::try_from(duration.as_nanos()).map_err(|_| String::from("is too long")))
+            .map_err(|reason| ChannelUri::invalid_param(key, value, &reason))
+    }
+
+    fn positive_length(key: &str, length: u32) -> Result<Value, AeronError> {
+        if length == 0 || length > i32::MAX as u32 {
+            return Err(ChannelUri::invalid_param(
+                key,
+                &length.to_string(),
+                &format!("must be in range 1-{}", i32::MAX),
+            ));
+        }
+
+        Ok(Value::new(length as i64))
+    }
+
+    fn non_negative_ns(key: &str, value_ns: i64) -> Result<Value, AeronError> {
+        if value_ns < 0 {
+            return Err(ChannelUri::invalid_param(key, &value_ns.to_string(), "must not be negative"));
+        }
+
+        Ok(Value::new(value_ns))
+    }
+
+    /// Offset of the timestamp is either "reserved" (reserved value of the data header) or offset in the message.
+    fn timestamp_offset(key: &str, offset: &str) -> Result<String, AeronError> {
+        if offset != channel_uri::RESERVED_OFFSET {
+            match offset.parse::<i32>() {
+                Ok(value) if value >= 0 => {},
+                _ => {
+                    return Err(ChannelUri::invalid_param(
+                        key,
+                        offset,
+                        "must be 'reserved' or a non negative offset",
+                    ))
+                },
+            }
+        }
+
+        Ok(String::from(offset))
+    }
+
     #[inline]
     fn prefix_tag(is_tagged: bool, value: &Value) -> String {
         if is_tagged {
@@ -441,8 +938,9 @@ impl ChannelUriStringBuilder {
 
 #[cfg(test)]
 mod tests {
-    use crate::channel_uri;
+    use crate::channel_uri::{self, ChannelUri};
     use crate::channel_uri_string_builder::ChannelUriStringBuilder;
+    use crate::flow_control::{CongestionControl, FlowControl};
 
     #[test]
     fn should_generate_basic_ipc_channel() {
@@ -511,4 +1009,224 @@ mod tests {
             "aeron:udp?endpoint=localhost:9999|term-length=131072|init-term-id=777|term-id=999|term-offset=64"
         );
     }
+
+    #[test]
+    fn should_generate_extended_udp_channel() {
+        let mut builder = ChannelUriStringBuilder::default();
+
+        builder
+            .media(channel_uri::UDP_MEDIA)
+            .unwrap()
+            .endpoint("localhost:9999")
+            .session_id(7)
+            .is_session_tagged(true)
+            .socket_sndbuf_length(128 * 1024)
+            .unwrap()
+            .receiver_window_length(64 * 1024)
+            .unwrap()
+            .nak_delay(100_000)
+            .unwrap()
+            .max_resend(4)
+            .unwrap()
+            .spies_simulate_connection(true)
+            .group_tag(42)
+            .flow_control(&FlowControl::min())
+            .media_receive_timestamp_offset("reserved")
+            .unwrap()
+            .stream_id(1001)
+            .response_correlation_id_prototype();
+
+        assert_eq!(
+            builder.build(),
+            "aeron:udp?endpoint=localhost:9999|session-id=tag:7|so-sndbuf=131072|rcv-wnd=65536|nak-delay=100000|\
+             max-resend=4|ssc=true|gtag=42|fc=min|media-rcv-ts-offset=reserved|stream-id=1001|\
+             response-correlation-id=prototype"
+        );
+    }
+
+    #[test]
+    fn should_reject_invalid_values() {
+        let mut builder = ChannelUriStringBuilder::default();
+
+        assert!(builder.socket_rcvbuf_length(0).is_err());
+        assert!(builder.publication_window_length(u32::MAX).is_err());
+        assert!(builder.untethered_resting_timeout(-1).is_err());
+        assert!(builder.max_resend(0).is_err());
+        assert!(builder.max_resend(17).is_err());
+        assert!(builder.channel_send_timestamp_offset("-8").is_err());
+        assert!(builder.channel_send_timestamp_offset("later").is_err());
+        assert!(builder.response_correlation_id(-1).is_err());
+    }
+
+    #[test]
+    fn should_generate_control_mode_without_prefix() {
+        let mut builder = ChannelUriStringBuilder::default();
+
+        builder
+            .media(channel_uri::UDP_MEDIA)
+            .unwrap()
+            .control_endpoint("localhost:9999")
+            .control_mode(channel_uri::MDC_CONTROL_MODE_MANUAL)
+            .unwrap();
+
+        assert_eq!(builder.build(), "aeron:udp?control=localhost:9999|control-mode=manual");
+    }
+
+    #[test]
+    fn should_generate_session_id_tether_and_group_params() {
+        let mut builder = ChannelUriStringBuilder::default();
+
+        builder
+            .media(channel_uri::UDP_MEDIA)
+            .unwrap()
+            .endpoint("localhost:9999")
+            .term_id(3)
+            .session_id(5)
+            .tether(false)
+            .group(true);
+
+        assert_eq!(
+            builder.build(),
+            "aeron:udp?endpoint=localhost:9999|term-id=3|session-id=5|tether=false|group=true"
+        );
+    }
+
+    #[test]
+    fn should_reject_publication_window_longer_than_half_term_length() {
+        let mut builder = ChannelUriStringBuilder::default();
+
+        builder
+            .media(channel_uri::UDP_MEDIA)
+            .unwrap()
+            .endpoint("localhost:9999")
+            .term_length(64 * 1024)
+            .unwrap();
+
+        assert!(builder.publication_window_length(32 * 1024 + 32).is_err());
+
+        builder.publication_window_length(32 * 1024).unwrap();
+
+        assert!(builder.term_length(32 * 1024).is_err());
+
+        assert_eq!(
+            builder.build(),
+            "aeron:udp?endpoint=localhost:9999|term-length=65536|pub-wnd=32768"
+        );
+    }
+
+    #[test]
+    fn should_seed_builder_from_channel_uri_and_override() {
+        let channel_uri = ChannelUri::parse(
+            "aeron:udp?endpoint=localhost:9999|control-mode=response|mtu=8k|term-length=64k|session-id=tag:5|\
+             linger=1ms|sparse=true|untethered-window-limit-timeout=1s|so-rcvbuf=256k|pub-wnd=32k|\
+             channel-snd-ts-offset=8|response-correlation-id=12",
+        )
+        .unwrap();
+
+        let mut builder = ChannelUriStringBuilder::from_channel_uri(&channel_uri).unwrap();
+        builder.endpoint("localhost:8888").ttl(3);
+
+        let channel = ChannelUri::parse(&builder.build()).unwrap();
+
+        assert_eq!(channel.get(channel_uri::ENDPOINT_PARAM_NAME), "localhost:8888");
+        assert_eq!(channel.get(channel_uri::MDC_CONTROL_MODE_PARAM_NAME), "response");
+        assert_eq!(channel.get(channel_uri::TTL_PARAM_NAME), "3");
+        assert_eq!(channel.mtu().unwrap(), Some(8192));
+        assert_eq!(channel.term_length().unwrap(), Some(65536));
+        assert_eq!(channel.get(channel_uri::SESSION_ID_PARAM_NAME), "tag:5");
+        assert_eq!(channel.get(channel_uri::LINGER_PARAM_NAME), "1000000");
+        assert_eq!(channel.sparse().unwrap(), Some(true));
+        assert_eq!(
+            channel.get(channel_uri::UNTETHERED_WINDOW_LIMIT_TIMEOUT_PARAM_NAME),
+            "1000000000"
+        );
+        assert_eq!(channel.get(channel_uri::SOCKET_RCVBUF_PARAM_NAME), "262144");
+        assert_eq!(channel.get(channel_uri::PUBLICATION_WINDOW_LENGTH_PARAM_NAME), "32768");
+        assert_eq!(channel.get(channel_uri::CHANNEL_SND_TIMESTAMP_OFFSET_PARAM_NAME), "8");
+        assert_eq!(channel.get(channel_uri::RESPONSE_CORRELATION_ID_PARAM_NAME), "12");
+    }
+
+    #[test]
+    fn should_carry_unknown_params_when_seeding() {
+        let channel_uri = ChannelUri::parse("aeron:udp?x-custom=1|endpoint=localhost:9999|x-other=abc").unwrap();
+
+        let mut builder = ChannelUriStringBuilder::from_channel_uri(&channel_uri).unwrap();
+        builder.ttl(3);
+
+        assert_eq!(
+            builder.build(),
+            "aeron:udp?endpoint=localhost:9999|ttl=3|x-custom=1|x-other=abc"
+        );
+
+        builder.clear();
+        builder.media(channel_uri::IPC_MEDIA).unwrap();
+
+        assert_eq!(builder.build(), "aeron:ipc");
+    }
+
+    #[test]
+    fn should_set_typed_flow_and_congestion_control() {
+        let mut flow_control = FlowControl::tagged();
+        flow_control
+            .with_group_tag(101)
+            .unwrap()
+            .with_group_min_size(3)
+            .unwrap()
+            .with_timeout(std::time::Duration::from_secs(1))
+            .unwrap();
+
+        let mut builder = ChannelUriStringBuilder::default();
+        builder
+            .media(channel_uri::UDP_MEDIA)
+            .unwrap()
+            .endpoint("224.10.9.8:40123")
+            .congestion_control_strategy(CongestionControl::Cubic)
+            .flow_control(&flow_control);
+
+        let channel = builder.build();
+        assert_eq!(channel, "aeron:udp?endpoint=224.10.9.8:40123|cc=cubic|fc=tagged,g:101/3,t:1s");
+
+        let channel_uri = ChannelUri::parse(&channel).unwrap();
+        assert_eq!(channel_uri.flow_control().unwrap(), Some(flow_control));
+        assert_eq!(channel_uri.congestion_control().unwrap(), Some(CongestionControl::Cubic));
+    }
+
+    #[test]
+    fn should_reject_seeding_with_invalid_flow_control() {
+        let channel_uri = ChannelUri::parse("aeron:udp?endpoint=224.10.9.8:40123|fc=min,g:5").unwrap();
+
+        assert!(ChannelUriStringBuilder::from_channel_uri(&channel_uri).is_err());
+    }
+
+    #[test]
+    fn should_set_initial_position() {
+        let term_length = 64 * 1024;
+        let position = 3 * term_length as i64 + 4096;
+        let mut builder = ChannelUriStringBuilder::default();
+        builder
+            .media(channel_uri::IPC_MEDIA)
+            .unwrap()
+            .initial_position(position, 7, term_length)
+            .unwrap();
+
+        let channel_uri = ChannelUri::parse(&builder.build()).unwrap();
+        assert_eq!(channel_uri.get(channel_uri::INITIAL_TERM_ID_PARAM_NAME), "7");
+        assert_eq!(channel_uri.get(channel_uri::TERM_ID_PARAM_NAME), "10");
+        assert_eq!(channel_uri.get(channel_uri::TERM_OFFSET_PARAM_NAME), "4096");
+        assert_eq!(channel_uri.get(channel_uri::TERM_LENGTH_PARAM_NAME), "65536");
+    }
+
+    #[test]
+    fn should_reject_invalid_initial_position() {
+        let term_length = 64 * 1024;
+        let mut builder = ChannelUriStringBuilder::default();
+
+        assert!(builder.initial_position(-32, 0, term_length).is_err());
+        assert!(builder.initial_position(33, 0, term_length).is_err());
+        assert!(builder.initial_position((i32::MAX as i64 + 1) << 16, 0, term_length).is_err());
+        assert!(builder.initial_position(0, 0, 1000).is_err());
+        assert!(builder
+            .initial_position(((i32::MAX as i64 + 1) << 16) - 32, 0, term_length)
+            .is_ok());
+    }
 }
@@ -19,6 +19,7 @@ use std::ffi::{CStr, CString};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex, Weak};
 
+use crate::channel_status_registry::{ChannelStatusRegistry, ErroredChannel};
 use crate::concurrent::agent_runner::Agent;
 use crate::concurrent::atomic_buffer::AtomicBuffer;
 use crate::concurrent::atomic_counter::AtomicCounter;
@@ -28,8 +29,8 @@ use crate::concurrent::logbuffer::term_reader::ErrorHandler;
 use crate::concurrent::position::UnsafeBufferPosition;
 use crate::concurrent::status::status_indicator_reader;
 use crate::context::{
-    OnAvailableCounter, OnAvailableImage, OnCloseClient, OnNewPublication, OnNewSubscription, OnUnavailableCounter,
-    OnUnavailableImage,
+    OnAvailableCounter, OnAvailableImage, OnChannelErrored, OnCloseClient, OnNewPublication, OnNewSubscription,
+    OnUnavailableCounter, OnUnavailableImage,
 };
 use crate::counter::Counter;
 use crate::driver_listener_adapter::{DriverListener, DriverListenerAdapter};
@@ -39,7 +40,7 @@ use crate::image::Image;
 use crate::publication::Publication;
 use crate::subscription::Subscription;
 use crate::utils::errors::AeronError::{self, ChannelEndpointException};
-use crate::utils::errors::{DriverInteractionError, GenericError, IllegalArgumentError};
+use crate::utils::errors::{AeronErrorCode, DriverInteractionError, GenericError, IllegalArgumentError};
 use crate::utils::log_buffers::LogBuffers;
 use crate::utils::misc::CallbackGuard;
 use crate::utils::types::{Moment, MAX_MOMENT};
@@ -228,6 +229,7 @@ impl LogBuffersDefn {
 #[allow(dead_code)]
 struct DestinationStateDefn {
     error_message: CString,
+    channel: CString,
     correlation_id: i64,
     registration_id: i64,
     time_of_registration_ms: Moment,
@@ -236,9 +238,10 @@ struct DestinationStateDefn {
 }
 
 impl DestinationStateDefn {
-    pub fn new(correlation_id: i64, registration_id: i64, now_ms: Moment) -> Self {
+    pub fn new(channel: CString, correlation_id: i64, registration_id: i64, now_ms: Moment) -> Self {
         Self {
             error_message: CString::new("").unwrap(),
+            channel,
             registration_id,
             correlation_id,
             time_of_registration_ms: now_ms,
@@ -248,6 +251,16 @@ impl DestinationStateDefn {
     }
 }
 
+/// Numbers of resources closed by ClientConductor::close_all_resources().
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ClosedResources {
+    pub publications: usize,
+    pub exclusive_publications: usize,
+    pub subscriptions: usize,
+    pub images: usize,
+    pub counters: usize,
+}
+
 #[allow(dead_code)]
 pub struct ClientConductor {
     publication_by_registration_id: HashMap<i64, PublicationStateDefn>,
@@ -258,6 +271,7 @@ pub struct ClientConductor {
 
     log_buffers_by_registration_id: HashMap<i64, LogBuffersDefn>,
     lingering_image_lists: Vec<ImageListLingerDefn>,
+    channel_status_registry: ChannelStatusRegistry,
 
     driver_proxy: Arc<DriverProxy>,
     driver_listener_adapter: Option<DriverListenerAdapter<ClientConductor>>,
@@ -273,6 +287,7 @@ pub struct ClientConductor {
     on_available_counter_handlers: Vec<Box<dyn OnAvailableCounter>>,
     on_unavailable_counter_handlers: Vec<Box<dyn OnUnavailableCounter>>,
     on_close_client_handlers: Vec<Box<dyn OnCloseClient>>,
+    on_channel_errored_handlers: Vec<Box<dyn OnChannelErrored>>,
 
     epoch_clock: Box<dyn Fn() -> Moment>,
     driver_timeout_ms: Moment,
@@ -282,6 +297,7 @@ pub struct ClientConductor {
     is_in_callback: bool,
     driver_active: AtomicBool,
     is_closed: AtomicBool,
+    is_client_close_sent: bool,
     //admin_lock: Mutex<()>,
     heartbeat_timestamp: Option<Box<AtomicCounter>>,
 
@@ -309,6 +325,7 @@ impl ClientConductor {
         on_available_counter_handler: Box<dyn OnAvailableCounter>,
         on_unavailable_counter_handler: Box<dyn OnUnavailableCounter>,
         on_close_client_handler: Box<dyn OnCloseClient>,
+        on_channel_errored_handler: Box<dyn OnChannelErrored>,
         driver_timeout_ms: Moment,
         resource_linger_timeout_ms: Moment,
         inter_service_timeout_ns: Moment,
@@ -322,6 +339,7 @@ impl ClientConductor {
             destination_state_by_correlation_id: Default::default(),
             log_buffers_by_registration_id: Default::default(),
             lingering_image_lists: vec![],
+            channel_status_registry: ChannelStatusRegistry::new(),
             driver_proxy,
             driver_listener_adapter: None,
             counters_reader: Arc::new(CountersReader::new(counter_metadata_buffer, counter_values_buffer)),
@@ -333,6 +351,7 @@ impl ClientConductor {
             on_available_counter_handlers: vec![],
             on_unavailable_counter_handlers: vec![],
             on_close_client_handlers: vec![],
+            on_channel_errored_handlers: vec![],
             epoch_clock: Box::new(epoch_clock),
             driver_timeout_ms,
             resource_linger_timeout_ms,
@@ -341,6 +360,7 @@ impl ClientConductor {
             is_in_callback: false,
             driver_active: AtomicBool::from(true),
             is_closed: AtomicBool::from(false),
+            is_client_close_sent: false,
             //admin_lock: Mutex::new(()),
             heartbeat_timestamp: None,
             time_of_last_do_work_ms: epoch_clock(),
@@ -353,6 +373,7 @@ impl ClientConductor {
         selfy.on_available_counter_handlers.push(on_available_counter_handler);
         selfy.on_unavailable_counter_handlers.push(on_unavailable_counter_handler);
         selfy.on_close_client_handlers.push(on_close_client_handler);
+        selfy.on_channel_errored_handlers.push(on_channel_errored_handler);
 
         let arc_selfy = Arc::new(Mutex::new(selfy));
         let another_selfy = arc_selfy.clone();
@@ -672,6 +693,9 @@ impl ClientConductor {
                         Err(ClientConductor::return_registration_error(
                             state.error_code,
                             &state.error_message,
+                            &state.channel,
+                            state.stream_id,
+                            registration_id,
                         ))
                     },
                 }
@@ -688,8 +712,37 @@ impl ClientConductor {
         result
     }
 
-    pub fn return_registration_error(err_code: i32, err_message: &CStr) -> AeronError {
-        AeronError::RegistrationException(err_code, String::from(err_message.to_str().expect("CStr conversion error")))
+    pub fn return_registration_error(
+        err_code: i32,
+        err_message: &CStr,
+        channel: &CStr,
+        stream_id: i32,
+        correlation_id: i64,
+    ) -> AeronError {
+        AeronError::RegistrationException {
+            code: AeronErrorCode::from_code(err_code),
+            message: String::from(err_message.to_str().expect("CStr conversion error")),
+            channel: channel.to_string_lossy().into_owned(),
+            stream_id,
+            correlation_id,
+        }
+    }
+
+    fn return_channel_endpoint_error(
+        correlation_id: i64,
+        err_message: &CStr,
+        channel: &CStr,
+        stream_id: i32,
+        registration_id: i64,
+    ) -> AeronError {
+        ChannelEndpointException {
+            code: AeronErrorCode::ChannelEndpointError,
+            message: String::from(err_message.to_str().expect("CStr conversion error")),
+            channel: channel.to_string_lossy().into_owned(),
+            stream_id,
+            registration_id,
+            correlation_id,
+        }
     }
 
     pub fn release_publication(&mut self, registration_id: i64) -> Result<(), AeronError> {
@@ -700,6 +753,7 @@ impl ClientConductor {
         if let Some(_publication) = self.publication_by_registration_id.get(&registration_id) {
             self.driver_proxy.remove_publication(registration_id)?;
             self.publication_by_registration_id.remove(&registration_id);
+            self.channel_status_registry.remove(registration_id);
             log!(
                 trace,
                 "release_publication: publication with registration_id {} RELEASED",
@@ -817,6 +871,9 @@ impl ClientConductor {
                         Err(ClientConductor::return_registration_error(
                             state.error_code,
                             &state.error_message,
+                            &state.channel,
+                            state.stream_id,
+                            registration_id,
                         ))
                     },
                 }
@@ -845,6 +902,7 @@ impl ClientConductor {
         if let Some(_publication) = self.exclusive_publication_by_registration_id.get(&registration_id) {
             let _result = self.driver_proxy.remove_publication(registration_id);
             self.exclusive_publication_by_registration_id.remove(&registration_id);
+            self.channel_status_registry.remove(registration_id);
             log!(
                 trace,
                 "release_exclusive_publication: exclusive publication with registration_id {} RELEASED",
@@ -942,6 +1000,9 @@ impl ClientConductor {
                     Err(ClientConductor::return_registration_error(
                         state.error_code,
                         &state.error_message,
+                        &state.channel,
+                        state.stream_id,
+                        registration_id,
                     ))
                 } else {
                     Err(GenericError::SubscriptionWasNotCreatedBefore { status: state.status }.into())
@@ -984,6 +1045,7 @@ impl ClientConductor {
 
         self.linger_all_resources((self.epoch_clock)(), images);
         self.subscription_by_registration_id.remove(&registration_id);
+        self.channel_status_registry.remove(registration_id);
         log!(
             trace,
             "release_subscription: subscription with registration_id {} RELEASED together with its images",
@@ -1070,9 +1132,13 @@ impl ClientConductor {
                     }
                 } else if RegistrationStatus::Errored == state.status {
                     counter_to_remove = Some(registration_id);
+                    // Counters are not bound to a channel or stream
                     Err(ClientConductor::return_registration_error(
                         state.error_code,
                         &state.error_message,
+                        &CString::default(),
+                        -1,
+                        registration_id,
                     ))
                 } else {
                     Err(GenericError::CounterWasNotCreatedBefore { status: state.status }.into())
@@ -1126,11 +1192,16 @@ impl ClientConductor {
 
         let correlation_id = self
             .driver_proxy
-            .add_destination(publication_registration_id, endpoint_channel)?;
+            .add_destination(publication_registration_id, endpoint_channel.clone())?;
 
         self.destination_state_by_correlation_id.insert(
             correlation_id,
-            DestinationStateDefn::new(correlation_id, publication_registration_id, (self.epoch_clock)()),
+            DestinationStateDefn::new(
+                endpoint_channel,
+                correlation_id,
+                publication_registration_id,
+                (self.epoch_clock)(),
+            ),
         );
 
         log!(
@@ -1156,13 +1227,18 @@ impl ClientConductor {
 
         let correlation_id = self
             .driver_proxy
-            .remove_destination(publication_registration_id, endpoint_channel)?;
+            .remove_destination(publication_registration_id, endpoint_channel.clone())?;
 
         // FIXME: the code is ported from C++ as is. But it seems there is a bug. We need to remove destination from
         // destination_state_by_correlation_id instead of inserting.
         self.destination_state_by_correlation_id.insert(
             correlation_id,
-            DestinationStateDefn::new(correlation_id, publication_registration_id, (self.epoch_clock)()),
+            DestinationStateDefn::new(
+                endpoint_channel,
+                correlation_id,
+                publication_registration_id,
+                (self.epoch_clock)(),
+            ),
         );
 
         log!(
@@ -1192,11 +1268,16 @@ impl ClientConductor {
 
         let correlation_id = self
             .driver_proxy
-            .add_rcv_destination(subscription_registration_id, endpoint_channel)?;
+            .add_rcv_destination(subscription_registration_id, endpoint_channel.clone())?;
 
         self.destination_state_by_correlation_id.insert(
             correlation_id,
-            DestinationStateDefn::new(correlation_id, subscription_registration_id, (self.epoch_clock)()),
+            DestinationStateDefn::new(
+                endpoint_channel,
+                correlation_id,
+                subscription_registration_id,
+                (self.epoch_clock)(),
+            ),
         );
 
         log!(
@@ -1226,11 +1307,16 @@ impl ClientConductor {
 
         let correlation_id = self
             .driver_proxy
-            .remove_rcv_destination(subscription_registration_id, endpoint_channel)?;
+            .remove_rcv_destination(subscription_registration_id, endpoint_channel.clone())?;
 
         self.destination_state_by_correlation_id.insert(
             correlation_id,
-            DestinationStateDefn::new(correlation_id, subscription_registration_id, (self.epoch_clock)()),
+            DestinationStateDefn::new(
+                endpoint_channel,
+                correlation_id,
+                subscription_registration_id,
+                (self.epoch_clock)(),
+            ),
         );
 
         log!(
@@ -1261,6 +1347,9 @@ impl ClientConductor {
                 RegistrationStatus::Errored => Err(ClientConductor::return_registration_error(
                     state.error_code,
                     &state.error_message,
+                    &state.channel,
+                    -1,
+                    correlation_id,
                 )),
             }
         } else {
@@ -1323,15 +1412,57 @@ impl ClientConductor {
         Ok(())
     }
 
-    pub fn close_all_resources(&mut self, now_ms: Moment) {
+    pub fn add_channel_errored_handler(&mut self, handler: Box<dyn OnChannelErrored>) -> Result<(), AeronError> {
+        self.ensure_not_reentrant();
+        self.ensure_open()?;
+
+        self.on_channel_errored_handlers.push(handler);
+        Ok(())
+    }
+
+    /// Checks channel status counters of all live publications and subscriptions and notifies
+    /// channel errored handlers about endpoints which went into ERRORED state.
+    pub fn on_check_channel_status(&mut self) -> usize {
+        let now_ms = (self.epoch_clock)();
+        let errored = self.channel_status_registry.poll(&self.counters_reader, now_ms);
+        let work_count = errored.len();
+
+        self.notify_channel_errored(errored);
+
+        work_count
+    }
+
+    fn notify_channel_errored(&mut self, errored: Vec<ErroredChannel>) {
+        for errored_channel in &errored {
+            log!(
+                trace,
+                "notify_channel_errored: registration_id {}, channel {}, error {:?}",
+                errored_channel.registration_id,
+                errored_channel.channel.to_string_lossy(),
+                errored_channel.error_message
+            );
+
+            for handler in &self.on_channel_errored_handlers {
+                let _callback_guard = CallbackGuard::new(&mut self.is_in_callback);
+                handler.call(errored_channel);
+            }
+        }
+    }
+
+    pub fn close_all_resources(&mut self, now_ms: Moment) -> ClosedResources {
         log!(trace, "close_all_resources: closing all resources");
 
+        let mut closed = ClosedResources::default();
+
         self.is_closed.store(true, Ordering::Release);
 
+        self.channel_status_registry.clear();
+
         for pub_defn in self.publication_by_registration_id.values() {
             if let Some(maybe_publication) = &pub_defn.publication {
                 if let Some(publication) = maybe_publication.upgrade() {
                     publication.lock().expect("Mutex on pub poisoned").close();
+                    closed.publications += 1;
                 }
             }
         }
@@ -1341,6 +1472,7 @@ impl ClientConductor {
             if let Some(maybe_publication) = &pub_defn.publication {
                 if let Some(publication) = maybe_publication.upgrade() {
                     publication.lock().expect("Mutex on ExPub poisoned").close();
+                    closed.exclusive_publications += 1;
                 }
             }
         }
@@ -1353,6 +1485,8 @@ impl ClientConductor {
         for sub_defn in self.subscription_by_registration_id.values_mut() {
             if let Some(maybe_subscription) = &sub_defn.subscription {
                 if let Some(subscription) = maybe_subscription.upgrade() {
+                    closed.subscriptions += 1;
+
                     if let Some(mut images) = subscription.lock().expect("Mutex poisoned").close_and_remove_images() {
                         for image in images.iter_mut() {
                             image.close();
@@ -1360,6 +1494,7 @@ impl ClientConductor {
                             let _callback_guard = CallbackGuard::new(&mut self.is_in_callback);
                             sub_defn.on_unavailable_image_handler.call(image);
                         }
+                        closed.images += images.len();
                         images_to_linger.push(images);
                     }
 
@@ -1383,6 +1518,7 @@ impl ClientConductor {
             if let Some(maybe_counter) = &cnt_defn.counter {
                 if let Some(counter) = maybe_counter.upgrade() {
                     counter.close();
+                    closed.counters += 1;
                     let registration_id = counter.registration_id();
                     let counter_id = counter.id();
 
@@ -1404,6 +1540,20 @@ impl ClientConductor {
             let _callback_guard = CallbackGuard::new(&mut self.is_in_callback);
             handler.call();
         }
+
+        closed
+    }
+
+    /**
+     * Notify the driver that this client is closing. The notification is sent only once.
+     */
+    pub fn close_client(&mut self) -> Result<(), AeronError> {
+        if !self.is_client_close_sent {
+            self.is_client_close_sent = true;
+            self.driver_proxy.client_close()?;
+        }
+
+        Ok(())
     }
 
     pub fn on_check_managed_resources(&mut self, now_ms: Moment) {
@@ -1432,6 +1582,31 @@ impl ClientConductor {
             .retain(|img| now_ms - resource_linger_timeout_ms <= img.time_of_last_state_change_ms);
     }
 
+    /**
+     * Release lingering images and the log buffers which are no longer used by the application without waiting
+     * for resource linger timeout. Used on close of the client.
+     *
+     * @return numbers of the released images and log buffers.
+     */
+    pub fn release_lingering_resources(&mut self) -> (usize, usize) {
+        let images_released = self.lingering_image_lists.iter().map(|defn| defn.image_array.len()).sum();
+        self.lingering_image_lists.clear();
+
+        let log_buffers_before = self.log_buffers_by_registration_id.len();
+        self.log_buffers_by_registration_id
+            .retain(|_id, entry| Arc::strong_count(&entry.log_buffers) > 1);
+
+        (
+            images_released,
+            log_buffers_before - self.log_buffers_by_registration_id.len(),
+        )
+    }
+
+    /// Number of log buffers which are still referenced by the application (e.g. via publications or images).
+    pub fn log_buffers_in_use(&self) -> usize {
+        self.log_buffers_by_registration_id.len()
+    }
+
     pub fn linger_resource(&mut self, now_ms: Moment, images: Vec<Image>) {
         self.lingering_image_lists.push(ImageListLingerDefn::new(now_ms, images));
     }
@@ -1454,6 +1629,7 @@ impl Agent for ClientConductor {
         work_count += dla.receive_messages(self)?;
         self.driver_listener_adapter.replace(dla);
         work_count += self.on_heartbeat_check_timeouts() as usize;
+        work_count += self.on_check_channel_status();
         Ok(work_count as i32)
     }
 
@@ -1516,6 +1692,9 @@ impl DriverListener for ClientConductor {
             state.buffers = log_buffers;
             state.original_registration_id = original_registration_id;
 
+            self.channel_status_registry
+                .add(registration_id, stream_id, state.channel.clone(), channel_status_indicator_id);
+
             let _callback_guard = CallbackGuard::new(&mut self.is_in_callback);
             self.on_new_publication_handler
                 .call(state.channel.clone(), stream_id, session_id, registration_id);
@@ -1571,6 +1750,9 @@ impl DriverListener for ClientConductor {
             state.channel_status_id = channel_status_indicator_id;
             state.buffers = log_buffers;
 
+            self.channel_status_registry
+                .add(registration_id, stream_id, state.channel.clone(), channel_status_indicator_id);
+
             let _callback_guard = CallbackGuard::new(&mut self.is_in_callback);
             self.on_new_exclusive_publication_handler
                 .call(state.channel.clone(), stream_id, session_id, registration_id);
@@ -1588,6 +1770,9 @@ impl DriverListener for ClientConductor {
 
             state.status = RegistrationStatus::Registered;
 
+            self.channel_status_registry
+                .add(registration_id, state.stream_id, state.channel.clone(), channel_status_id);
+
             let subscr = Arc::new(Mutex::new(Subscription::new(
                 self.arced_self.as_ref().unwrap().clone(),
                 state.registration_id,
@@ -1615,6 +1800,11 @@ impl DriverListener for ClientConductor {
     }
 
     fn on_channel_endpoint_error_response(&mut self, offending_command_correlation_id: i64, error_message: CString) {
+        let errored = self
+            .channel_status_registry
+            .on_channel_endpoint_error(offending_command_correlation_id as i32, &error_message.to_string_lossy());
+        self.notify_channel_errored(errored);
+
         let mut subscription_to_remove: Vec<i64> = Vec::new();
         let mut linger_images: Vec<Vec<Image>> = Vec::new();
 
@@ -1625,9 +1815,12 @@ impl DriverListener for ClientConductor {
                     if subscription.channel_status_id() == offending_command_correlation_id as i32 {
                         log!(trace, "on_channel_endpoint_error_response: for subscription, offending_command_correlation_id {}, error_message {}", offending_command_correlation_id, error_message.to_str().unwrap());
 
-                        self.error_handler.call(ChannelEndpointException(
+                        self.error_handler.call(ClientConductor::return_channel_endpoint_error(
                             offending_command_correlation_id,
-                            String::from(error_message.to_str().expect("CString conversion error")),
+                            &error_message,
+                            &subscr_defn.channel,
+                            subscr_defn.stream_id,
+                            *reg_id,
                         ));
 
                         if let Some(mut images) = subscription.close_and_remove_images() {
@@ -1651,7 +1844,10 @@ impl DriverListener for ClientConductor {
 
         let _removed_subs: Vec<Option<SubscriptionStateDefn>> = subscription_to_remove
             .into_iter()
-            .map(|id| self.subscription_by_registration_id.remove(&id))
+            .map(|id| {
+                self.channel_status_registry.remove(id);
+                self.subscription_by_registration_id.remove(&id)
+            })
             .collect();
 
         let mut publication_to_remove: Vec<i64> = Vec::new();
@@ -1663,9 +1859,12 @@ impl DriverListener for ClientConductor {
                     {
                         log!(trace, "on_channel_endpoint_error_response: for publication, offending_command_correlation_id {}, error_message {}", offending_command_correlation_id, error_message.to_str().unwrap());
 
-                        self.error_handler.call(ChannelEndpointException(
+                        self.error_handler.call(ClientConductor::return_channel_endpoint_error(
                             offending_command_correlation_id,
-                            String::from(error_message.to_str().expect("CString conversion error")),
+                            &error_message,
+                            &publication_defn.channel,
+                            publication_defn.stream_id,
+                            *reg_id,
                         ));
                         publication.lock().expect("Mutex on pub poisoned").close();
                         publication_to_remove.push(*reg_id);
@@ -1675,7 +1874,10 @@ impl DriverListener for ClientConductor {
         }
         let _removed_pubs: Vec<Option<PublicationStateDefn>> = publication_to_remove
             .into_iter()
-            .map(|id| self.publication_by_registration_id.remove(&id))
+            .map(|id| {
+                self.channel_status_registry.remove(id);
+                self.publication_by_registration_id.remove(&id)
+            })
             .collect();
 
         let mut epublication_to_remove: Vec<i64> = Vec::new();
@@ -1685,9 +1887,12 @@ impl DriverListener for ClientConductor {
                     if publication.lock().expect("Mutex on pub poisoned").channel_status_id()
                         == offending_command_correlation_id as i32
                     {
-                        self.error_handler.call(ChannelEndpointException(
+                        self.error_handler.call(ClientConductor::return_channel_endpoint_error(
                             offending_command_correlation_id,
-                            String::from(error_message.to_str().expect("CString conversion error")),
+                            &error_message,
+                            &publication_defn.channel,
+                            publication_defn.stream_id,
+                            *reg_id,
                         ));
                         publication.lock().expect("Mutex on pub poisoned").close();
                         epublication_to_remove.push(*reg_id);
@@ -1697,7 +1902,10 @@ impl DriverListener for ClientConductor {
         }
         let _removed_epubs: Vec<Option<ExclusivePublicationStateDefn>> = epublication_to_remove
             .into_iter()
-            .map(|id| self.exclusive_publication_by_registration_id.remove(&id))
+            .map(|id| {
+                self.channel_status_registry.remove(id);
+                self.exclusive_publication_by_registration_id.remove(&id)
+            })
             .collect();
     }
 
@@ -1941,7 +2149,7 @@ impl Drop for ClientConductor {
         for _img in &self.lingering_image_lists {
             // img.image_array.drop(); FIXME: check whether drop for Images is needed
         }
-        let _res = self.driver_proxy.client_close();
+        let _res = self.close_client();
     }
 }
 
@@ -1952,12 +2160,13 @@ unsafe impl Sync for ClientConductor {}
 mod tests {
     use std::sync::atomic::{AtomicBool, Ordering};
 
-    use galvanic_assert::matchers::any_value;
+    use galvanic_assert::matchers::{any_value, eq};
     use galvanic_assert::{assert_that, has_structure, structure};
     use lazy_static::lazy_static;
     use nix::unistd;
 
     use super::*;
+    use crate::channel_status_registry::ERROR_MESSAGE_WAIT_MS;
     use crate::command::control_protocol_events::AeronCommand;
     use crate::command::counter_message_flyweight::CounterMessageFlyweight;
     use crate::command::error_response_flyweight::{ERROR_CODE_GENERIC_ERROR, ERROR_CODE_INVALID_CHANNEL};
@@ -2080,6 +2289,7 @@ mod tests {
                 Box::new(on_available_counter_handler),
                 Box::new(on_unavailable_counter_handler),
                 Box::new(on_close_client_handler),
+                Box::new(on_channel_errored_handler),
                 DRIVER_TIMEOUT_MS,
                 RESOURCE_LINGER_TIMEOUT_MS,
                 INTER_SERVICE_TIMEOUT_NS,
@@ -2088,6 +2298,8 @@ mod tests {
 
             fn on_close_client_handler() {}
 
+            fn on_channel_errored_handler(_errored_channel: &ErroredChannel) {}
+
             #[allow(dead_code)]
             fn on_media_driver_timeout() {}
 
@@ -2410,7 +2622,12 @@ mod tests {
         let publication = test.conductor.lock().unwrap().find_publication(id);
         assert_that!(
             &publication.err().unwrap(),
-            has_structure!(AeronError::RegistrationException[any_value(), any_value()])
+            has_structure!(AeronError::RegistrationException {
+                code: eq(AeronErrorCode::InvalidChannel),
+                channel: eq(String::from(CHANNEL)),
+                stream_id: eq(STREAM_ID),
+                correlation_id: eq(id)
+            })
         );
     }
 
@@ -2658,7 +2875,12 @@ mod tests {
         let publication = test.conductor.lock().unwrap().find_exclusive_publication(id);
         assert_that!(
             &publication.err().unwrap(),
-            has_structure!(AeronError::RegistrationException[any_value(), any_value()])
+            has_structure!(AeronError::RegistrationException {
+                code: eq(AeronErrorCode::InvalidChannel),
+                channel: eq(String::from(CHANNEL)),
+                stream_id: eq(STREAM_ID),
+                correlation_id: eq(id)
+            })
         );
     }
 
@@ -2986,7 +3208,12 @@ mod tests {
 
         assert_that!(
             &subscription.err().unwrap(),
-            has_structure!(AeronError::RegistrationException[any_value(), any_value()])
+            has_structure!(AeronError::RegistrationException {
+                code: eq(AeronErrorCode::InvalidChannel),
+                channel: eq(String::from(CHANNEL)),
+                stream_id: eq(STREAM_ID),
+                correlation_id: eq(id)
+            })
         );
     }
 
@@ -4176,7 +4403,10 @@ mod tests {
 
         assert_that!(
             &counter.err().unwrap(),
-            has_structure!(AeronError::RegistrationException[any_value(), any_value()])
+            has_structure!(AeronError::RegistrationException {
+                code: eq(AeronErrorCode::GenericError),
+                correlation_id: eq(id)
+            })
         );
     }
 
@@ -4205,6 +4435,242 @@ mod tests {
         let val = ON_UNAV_COUNTER_CALLED1.load(Ordering::SeqCst);
         assert_eq!(val, 1);
     }
+
+    fn error_handler_channel_endpoint(error: AeronError) {
+        ERR_HANDLER_CHANNEL_ENDPOINT_CALLED.store(true, Ordering::SeqCst);
+        assert_eq!(error.error_code(), Some(AeronErrorCode::ChannelEndpointError));
+        assert_that!(
+            &error,
+            has_structure!(AeronError::ChannelEndpointException {
+                channel: eq(String::from(CHANNEL)),
+                stream_id: eq(STREAM_ID),
+                correlation_id: eq(CHANNEL_STATUS_INDICATOR_ID as i64)
+            })
+        );
+    }
+
+    lazy_static! {
+        pub static ref ERR_HANDLER_CHANNEL_ENDPOINT_CALLED: AtomicBool = AtomicBool::from(false);
+    }
+
+    #[test]
+    fn should_report_closed_resources_on_close_all_resources() {
+        let test = ClientConductorTest::new();
+
+        let pub_id = test
+            .conductor
+            .lock()
+            .unwrap()
+            .add_publication(str_to_c(CHANNEL), STREAM_ID)
+            .expect("failed to add publication");
+
+        test.conductor.lock().unwrap().on_new_publication(
+            pub_id,
+            pub_id,
+            STREAM_ID,
+            SESSION_ID,
+            PUBLICATION_LIMIT_COUNTER_ID,
+            CHANNEL_STATUS_INDICATOR_ID,
+            str_to_c(&test.log_file_name),
+        );
+
+        let _publication = test.conductor.lock().unwrap().find_publication(pub_id).unwrap();
+
+        let closed = test
+            .conductor
+            .lock()
+            .unwrap()
+            .close_all_resources(*test.current_time.lock().unwrap());
+
+        assert_eq!(closed, ClosedResources {
+            publications: 1,
+            ..ClosedResources::default()
+        });
+    }
+
+    #[test]
+    fn should_release_log_buffers_not_in_use_and_send_client_close_once() {
+        let test = ClientConductorTest::new();
+
+        let pub_id = test
+            .conductor
+            .lock()
+            .unwrap()
+            .add_publication(str_to_c(CHANNEL), STREAM_ID)
+            .expect("failed to add publication");
+
+        test.conductor.lock().unwrap().on_new_publication(
+            pub_id,
+            pub_id,
+            STREAM_ID,
+            SESSION_ID,
+            PUBLICATION_LIMIT_COUNTER_ID,
+            CHANNEL_STATUS_INDICATOR_ID,
+            str_to_c(&test.log_file_name),
+        );
+
+        let publication = test.conductor.lock().unwrap().find_publication(pub_id).unwrap();
+
+        assert_eq!(test.conductor.lock().unwrap().release_lingering_resources(), (0, 0));
+        assert_eq!(test.conductor.lock().unwrap().log_buffers_in_use(), 1);
+
+        drop(publication);
+
+        assert_eq!(test.conductor.lock().unwrap().release_lingering_resources(), (0, 1));
+        assert_eq!(test.conductor.lock().unwrap().log_buffers_in_use(), 0);
+
+        // Skip commands sent so far
+        test.many_to_one_ring_buffer.read(|_msg_type_id, _buffer| {}, 1000);
+
+        test.conductor.lock().unwrap().close_client().unwrap();
+        test.conductor.lock().unwrap().close_client().unwrap();
+
+        let count = test.many_to_one_ring_buffer.read(
+            |msg_type_id, _buffer| {
+                assert_eq!(msg_type_id, AeronCommand::ClientClose);
+            },
+            1000,
+        );
+
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn should_report_channel_endpoint_error_with_channel_and_stream() {
+        let test = ClientConductorTest::new();
+
+        let id = test
+            .conductor
+            .lock()
+            .unwrap()
+            .add_subscription(
+                str_to_c(CHANNEL),
+                STREAM_ID,
+                Box::new(on_available_image_handler),
+                Box::new(on_unavailable_image_handler),
+            )
+            .unwrap();
+
+        test.conductor
+            .lock()
+            .unwrap()
+            .on_subscription_ready(id, CHANNEL_STATUS_INDICATOR_ID);
+
+        let _subscription = test.conductor.lock().unwrap().find_subscription(id).unwrap();
+
+        test.conductor
+            .lock()
+            .unwrap()
+            .set_error_handler(Box::new(error_handler_channel_endpoint));
+        test.conductor
+            .lock()
+            .unwrap()
+            .on_channel_endpoint_error_response(CHANNEL_STATUS_INDICATOR_ID as i64, str_to_c("bind failed"));
+
+        assert!(ERR_HANDLER_CHANNEL_ENDPOINT_CALLED.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn should_notify_channel_errored_handler_when_channel_status_goes_errored() {
+        let test = ClientConductorTest::new();
+        let errored_channels: Arc<Mutex<Vec<ErroredChannel>>> = Arc::new(Mutex::new(Vec::new()));
+        let errored_channels_for_handler = errored_channels.clone();
+
+        test.conductor
+            .lock()
+            .unwrap()
+            .add_channel_errored_handler(Box::new(move |errored_channel: &ErroredChannel| {
+                errored_channels_for_handler.lock().unwrap().push(errored_channel.clone())
+            }))
+            .unwrap();
+
+        let id = test
+            .conductor
+            .lock()
+            .unwrap()
+            .add_publication(str_to_c(CHANNEL), STREAM_ID)
+            .expect("failed to add publication");
+
+        test.conductor.lock().unwrap().on_new_publication(
+            id,
+            id,
+            STREAM_ID,
+            SESSION_ID,
+            PUBLICATION_LIMIT_COUNTER_ID,
+            CHANNEL_STATUS_INDICATOR_ID,
+            str_to_c(&test.log_file_name),
+        );
+
+        let counter_values_buffer = AtomicBuffer::from_aligned(&test.counter_values);
+        counter_values_buffer.put_ordered::<i64>(
+            CountersReader::counter_offset(CHANNEL_STATUS_INDICATOR_ID),
+            status_indicator_reader::CHANNEL_ENDPOINT_ACTIVE,
+        );
+        assert_eq!(test.conductor.lock().unwrap().on_check_channel_status(), 0);
+
+        counter_values_buffer.put_ordered::<i64>(
+            CountersReader::counter_offset(CHANNEL_STATUS_INDICATOR_ID),
+            status_indicator_reader::CHANNEL_ENDPOINT_ERRORED,
+        );
+        // Held back until the driver error arrives or the wait is over
+        assert_eq!(test.conductor.lock().unwrap().on_check_channel_status(), 0);
+
+        *test.current_time.lock().unwrap() += ERROR_MESSAGE_WAIT_MS;
+        assert_eq!(test.conductor.lock().unwrap().on_check_channel_status(), 1);
+        assert_eq!(test.conductor.lock().unwrap().on_check_channel_status(), 0);
+
+        let errored_channels = errored_channels.lock().unwrap();
+        assert_eq!(errored_channels.len(), 1);
+        assert_eq!(errored_channels[0].registration_id, id);
+        assert_eq!(errored_channels[0].stream_id, STREAM_ID);
+        assert_eq!(errored_channels[0].channel, str_to_c(CHANNEL));
+        assert_eq!(errored_channels[0].error_message, None);
+    }
+
+    #[test]
+    fn should_notify_channel_errored_handler_with_driver_error_text() {
+        let test = ClientConductorTest::new();
+        let errored_channels: Arc<Mutex<Vec<ErroredChannel>>> = Arc::new(Mutex::new(Vec::new()));
+        let errored_channels_for_handler = errored_channels.clone();
+
+        test.conductor
+            .lock()
+            .unwrap()
+            .add_channel_errored_handler(Box::new(move |errored_channel: &ErroredChannel| {
+                errored_channels_for_handler.lock().unwrap().push(errored_channel.clone())
+            }))
+            .unwrap();
+
+        let id = test
+            .conductor
+            .lock()
+            .unwrap()
+            .add_subscription(
+                str_to_c(CHANNEL),
+                STREAM_ID,
+                Box::new(on_available_image_handler),
+                Box::new(on_unavailable_image_handler),
+            )
+            .unwrap();
+
+        test.conductor
+            .lock()
+            .unwrap()
+            .on_subscription_ready(id, CHANNEL_STATUS_INDICATOR_ID);
+
+        let _subscription = test.conductor.lock().unwrap().find_subscription(id).unwrap();
+
+        test.conductor
+            .lock()
+            .unwrap()
+            .on_channel_endpoint_error_response(CHANNEL_STATUS_INDICATOR_ID as i64, str_to_c("Address already in use"));
+
+        let errored_channels = errored_channels.lock().unwrap();
+        assert_eq!(errored_channels.len(), 1);
+        assert_eq!(errored_channels[0].registration_id, id);
+        assert_eq!(errored_channels[0].channel_status_id, CHANNEL_STATUS_INDICATOR_ID);
+        assert_eq!(errored_channels[0].error_message.as_deref(), Some("Address already in use"));
+    }
     /*
     fn on_available_counter3(_counters_reader: &CountersReader, _registration_id: i64, _counter_id: i32) {
         let mut val = ON_AV_COUNTER_CALLED3.load(Ordering::SeqCst);
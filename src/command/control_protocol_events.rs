@@ -47,7 +47,7 @@ pub enum AeronCommand {
     ResponseOnExclusivePublicationReady = 0xF06,
     ResponseOnSubscriptionReady = 0xF07,
     ResponseOnCounterReady = 0xF08,
-    ResponseOnUnavailableCounter = 0xF9,
+    ResponseOnUnavailableCounter = 0xF09,
     ResponseOnClientTimeout = 0xF0A,
 
     #[cfg(test)]
@@ -64,7 +64,13 @@ impl fmt::LowerHex for AeronCommand {
 
 impl AeronCommand {
     pub fn from_command_id(command_id: i32) -> Self {
-        match command_id {
+        Self::try_from_command_id(command_id)
+            .unwrap_or_else(|| unreachable!("Unexpected control protocol event: {:x}", command_id))
+    }
+
+    /// Command of the id or None when the id isn't known, e.g. sent by a newer driver.
+    pub fn try_from_command_id(command_id: i32) -> Option<Self> {
+        let command = match command_id {
             -0x01 => Self::Padding,
 
             0x01 => Self::AddPublication,
@@ -95,7 +101,9 @@ impl AeronCommand {
 
             #[cfg(test)]
             0x65 => Self::UnitTestMessageTypeID,
-            _ => unreachable!("Unexpected control protocol event: {:x}", command_id),
-        }
+            _ => return None,
+        };
+
+        Some(command)
     }
 }
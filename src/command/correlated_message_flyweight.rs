@@ -52,7 +52,12 @@ impl CorrelatedMessageFlyweight {
 
     // Getters
 
-    #[cfg(test)]
+    #[inline]
+    pub fn client_id(&self) -> i64 {
+        unsafe { (*self.flyweight.m_struct).client_id }
+    }
+
+    #[inline]
     pub fn correlation_id(&self) -> i64 {
         unsafe { (*self.flyweight.m_struct).correlation_id }
     }
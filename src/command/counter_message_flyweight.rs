@@ -14,7 +14,6 @@
  * limitations under the License.
  */
 
-#[cfg(test)]
 use std::ffi::CString;
 
 use crate::command::correlated_message_flyweight::{CorrelatedMessageDefn, CorrelatedMessageFlyweight};
@@ -69,7 +68,7 @@ impl CounterMessageFlyweight {
         }
     }
 
-    #[cfg(test)]
+    #[inline]
     pub fn type_id(&self) -> i32 {
         unsafe { (*self.m_struct).type_id }
     }
@@ -115,7 +114,7 @@ impl CounterMessageFlyweight {
             .string_get_length(self.label_length_offset())
     }
 
-    #[cfg(test)]
+    #[inline]
     pub fn label(&self) -> CString {
         self.correlated_message_flyweight
             .flyweight
@@ -136,7 +135,12 @@ impl CounterMessageFlyweight {
 
     // Parent Getters
 
-    #[cfg(test)]
+    #[inline]
+    pub fn client_id(&self) -> i64 {
+        self.correlated_message_flyweight.client_id()
+    }
+
+    #[inline]
     pub fn correlation_id(&self) -> i64 {
         self.correlated_message_flyweight.correlation_id()
     }
@@ -13,6 +13,8 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
+use std::ffi::CString;
+
 use crate::command::correlated_message_flyweight::{CorrelatedMessageDefn, CorrelatedMessageFlyweight};
 use crate::concurrent::atomic_buffer::AtomicBuffer;
 use crate::utils::types::Index;
@@ -43,6 +45,11 @@ impl DestinationMessageFlyweight {
         }
     }
 
+    #[inline]
+    pub fn registration_id(&self) -> i64 {
+        unsafe { (*self.m_struct).registration_id }
+    }
+
     #[inline]
     pub fn set_registration_id(&mut self, value: i64) {
         unsafe {
@@ -50,6 +57,13 @@ impl DestinationMessageFlyweight {
         }
     }
 
+    #[inline]
+    pub fn channel(&self) -> CString {
+        self.correlated_message_flyweight
+            .flyweight
+            .string_get(offset_of!(DestinationMessageDefn, channel_length) as Index)
+    }
+
     #[inline]
     pub fn set_channel(&mut self, value: &[u8]) {
         self.correlated_message_flyweight
@@ -62,6 +76,18 @@ impl DestinationMessageFlyweight {
         unsafe { offset_of!(DestinationMessageDefn, channel_data) as Index + (*self.m_struct).channel_length as Index }
     }
 
+    // Parent Getters
+
+    #[inline]
+    pub fn client_id(&self) -> i64 {
+        self.correlated_message_flyweight.client_id()
+    }
+
+    #[inline]
+    pub fn correlation_id(&self) -> i64 {
+        self.correlated_message_flyweight.correlation_id()
+    }
+
     // Parent Setters
 
     #[inline]
@@ -59,6 +59,11 @@ pub const ERROR_CODE_UNKNOWN_COUNTER: i32 = 5;
 pub const ERROR_CODE_UNKNOWN_COMMAND_TYPE_ID: i32 = 6;
 pub const ERROR_CODE_MALFORMED_COMMAND: i32 = 7;
 pub const ERROR_CODE_NOT_SUPPORTED: i32 = 8;
+pub const ERROR_CODE_UNKNOWN_HOST: i32 = 9;
+pub const ERROR_CODE_RESOURCE_TEMPORARILY_UNAVAILABLE: i32 = 10;
+pub const ERROR_CODE_STORAGE_SPACE: i32 = 11;
+pub const ERROR_CODE_IMAGE_REJECTED: i32 = 12;
+pub const ERROR_CODE_PUBLICATION_REVOKED: i32 = 13;
 
 pub struct ErrorResponseFlyweight {
     flyweight: Flyweight<ErrorResponseDefn>,
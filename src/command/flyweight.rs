@@ -59,6 +59,17 @@ impl<T: Copy> Flyweight<T> {
         self.buffer.get_bytes(self.base_offset + offset, dest, length);
     }
 
+    /// Read a value which is not necessarily aligned to its type, e.g. an i64 following a 4 byte aligned header.
+    #[inline]
+    pub fn get_unaligned<U: Copy>(&self, offset: Index) -> U {
+        unsafe { self.overlay_struct::<U>(offset).read_unaligned() }
+    }
+
+    #[inline]
+    pub fn put_unaligned<U>(&self, offset: Index, value: U) {
+        unsafe { self.overlay_struct::<U>(offset).write_unaligned(value) }
+    }
+
     #[inline]
     pub fn put<U>(&self, offset: Index, value: U) {
         self.buffer.put::<U>(self.base_offset + offset, value);
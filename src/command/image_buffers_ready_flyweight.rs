@@ -90,6 +90,11 @@ impl ImageBuffersReadyFlyweight {
         unsafe { (*self.flyweight.m_struct).session_id }
     }
 
+    #[inline]
+    pub fn stream_id(&self) -> i32 {
+        unsafe { (*self.flyweight.m_struct).stream_id }
+    }
+
     #[inline]
     pub fn subscription_registration_id(&self) -> i64 {
         unsafe { (*self.flyweight.m_struct).subscription_registration_id }
@@ -13,6 +13,8 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
+use std::ffi::CString;
+
 use crate::command::flyweight::Flyweight;
 use crate::concurrent::atomic_buffer::AtomicBuffer;
 use crate::utils::types::Index;
@@ -71,4 +73,49 @@ impl ImageMessageFlyweight {
     pub fn subscription_registration_id(&self) -> i64 {
         unsafe { (*self.flyweight.m_struct).subscription_registration_id }
     }
+
+    #[inline]
+    pub fn stream_id(&self) -> i32 {
+        unsafe { (*self.flyweight.m_struct).stream_id }
+    }
+
+    #[inline]
+    pub fn channel(&self) -> CString {
+        self.flyweight
+            .string_get(offset_of!(ImageMessageDefn, channel_length) as Index)
+    }
+
+    #[cfg(test)]
+    pub fn length(&self) -> Index {
+        unsafe { offset_of!(ImageMessageDefn, channel_data) as Index + (*self.flyweight.m_struct).channel_length as Index }
+    }
+
+    // Setters
+
+    #[cfg(test)]
+    pub fn set_correlation_id(&mut self, value: i64) {
+        unsafe {
+            (*self.flyweight.m_struct).correlation_id = value;
+        }
+    }
+
+    #[cfg(test)]
+    pub fn set_subscription_registration_id(&mut self, value: i64) {
+        unsafe {
+            (*self.flyweight.m_struct).subscription_registration_id = value;
+        }
+    }
+
+    #[cfg(test)]
+    pub fn set_stream_id(&mut self, value: i32) {
+        unsafe {
+            (*self.flyweight.m_struct).stream_id = value;
+        }
+    }
+
+    #[cfg(test)]
+    pub fn set_channel(&mut self, value: &[u8]) {
+        self.flyweight
+            .string_put(offset_of!(ImageMessageDefn, channel_length) as Index, value);
+    }
 }
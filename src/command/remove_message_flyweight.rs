@@ -60,7 +60,7 @@ impl RemoveMessageFlyweight {
         }
     }
 
-    #[cfg(test)]
+    #[inline]
     pub fn registration_id(&self) -> i64 {
         unsafe { (*self.m_struct).registration_id }
     }
@@ -77,6 +77,18 @@ impl RemoveMessageFlyweight {
         REMOVE_MESSAGE_LENGTH
     }
 
+    // Parent Getters
+
+    #[inline]
+    pub fn client_id(&self) -> i64 {
+        self.correlated_message_flyweight.client_id()
+    }
+
+    #[inline]
+    pub fn correlation_id(&self) -> i64 {
+        self.correlated_message_flyweight.correlation_id()
+    }
+
     // Parent Setters
     #[inline]
     pub fn set_client_id(&mut self, value: i64) {
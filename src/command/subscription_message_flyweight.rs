@@ -13,7 +13,6 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
-#[cfg(test)]
 use std::ffi::CString;
 
 use crate::command::correlated_message_flyweight::{CorrelatedMessageDefn, CorrelatedMessageFlyweight};
@@ -72,11 +71,16 @@ impl SubscriptionMessageFlyweight {
         }
     }
 
-    #[cfg(test)]
+    #[inline]
     pub fn stream_id(&self) -> i32 {
         unsafe { (*self.m_struct).stream_id }
     }
 
+    #[inline]
+    pub fn registration_correlation_id(&self) -> i64 {
+        unsafe { (*self.m_struct).registration_correlation_id }
+    }
+
     #[inline]
     pub fn set_registration_correlation_id(&mut self, value: i64) {
         unsafe {
@@ -91,7 +95,7 @@ impl SubscriptionMessageFlyweight {
         }
     }
 
-    #[cfg(test)]
+    #[inline]
     pub fn channel(&self) -> CString {
         self.correlated_message_flyweight
             .flyweight
@@ -111,7 +115,12 @@ impl SubscriptionMessageFlyweight {
     }
 
     // Parent Getters
-    #[cfg(test)]
+    #[inline]
+    pub fn client_id(&self) -> i64 {
+        self.correlated_message_flyweight.client_id()
+    }
+
+    #[inline]
     pub fn correlation_id(&self) -> i64 {
         self.correlated_message_flyweight.correlation_id()
     }
@@ -63,6 +63,11 @@ impl TerminateDriverFlyweight {
         }
     }
 
+    #[inline]
+    pub fn token_length(&self) -> i32 {
+        unsafe { (*self.m_struct).token_length }
+    }
+
     #[inline]
     pub unsafe fn set_token_buffer(&mut self, token_buffer: *const u8, token_length: Index) {
         (*self.m_struct).token_length = token_length;
@@ -79,6 +84,18 @@ impl TerminateDriverFlyweight {
         unsafe { TERMINATE_DRIVER_LENGTH + (*self.m_struct).token_length as Index }
     }
 
+    // Parent Getters
+
+    #[inline]
+    pub fn client_id(&self) -> i64 {
+        self.correlated_message_flyweight.client_id()
+    }
+
+    #[inline]
+    pub fn correlation_id(&self) -> i64 {
+        self.correlated_message_flyweight.correlation_id()
+    }
+
     // Parent Setters
 
     #[inline]
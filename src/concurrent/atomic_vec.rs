@@ -84,7 +84,6 @@ impl<T> AtomicVec<T> {
                 self.begin_change.store(seq_no, Ordering::Release);
 
                 let buf = mem::take(&mut self.buf);
-                *self = Self::new();
 
                 self.end_change.store(seq_no, Ordering::Release);
 
@@ -136,3 +135,21 @@ impl<T: Clone> AtomicVec<T> {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::concurrent::atomic_vec::AtomicVec;
+
+    #[test]
+    fn should_load_after_take() {
+        let mut atomic_vec = AtomicVec::new();
+        atomic_vec.store(vec![1, 2, 3]);
+
+        assert_eq!(atomic_vec.take(), vec![1, 2, 3]);
+        assert!(atomic_vec.load().is_empty());
+
+        atomic_vec.add(4);
+        assert_eq!(atomic_vec.load(), &vec![4]);
+        assert_eq!(atomic_vec.load_val(), vec![4]);
+    }
+}
@@ -20,6 +20,8 @@ use std::fmt;
 
 use lazy_static::lazy_static;
 
+#[cfg(test)]
+use crate::concurrent::atomic_buffer::AlignedBuffer;
 use crate::concurrent::atomic_buffer::AtomicBuffer;
 use crate::utils::errors::*;
 use crate::utils::misc::CACHE_LINE_LENGTH;
@@ -37,7 +39,13 @@ use crate::utils::types::{Index, Moment, I32_SIZE, I64_SIZE, MAX_MOMENT, U64_SIZ
  *  |                        Counter Value                          |
  *  |                                                               |
  *  +---------------------------------------------------------------+
- *  |                     120 bytes of padding                     ...
+ *  |                       Registration Id                         |
+ *  |                                                               |
+ *  +---------------------------------------------------------------+
+ *  |                          Owner Id                             |
+ *  |                                                               |
+ *  +---------------------------------------------------------------+
+ *  |                     104 bytes of padding                     ...
  * ...                                                              |
  *  +---------------------------------------------------------------+
  *  |                   Repeats to end of buffer                   ...
@@ -75,6 +83,9 @@ pub const RECORD_UNUSED: i32 = 0;
 pub const RECORD_ALLOCATED: i32 = 1;
 pub const RECORD_RECLAIMED: i32 = -1;
 pub const NOT_FREE_TO_REUSE: Moment = MAX_MOMENT;
+pub const DEFAULT_REGISTRATION_ID: i64 = 0;
+/// Owner id of counters not allocated on behalf of a client, e.g. those of the driver.
+pub const DEFAULT_OWNER_ID: i64 = 0;
 
 pub const COUNTER_LENGTH: Index = std::mem::size_of::<CounterValueDefn>() as Index;
 pub const METADATA_LENGTH: Index = std::mem::size_of::<CounterMetaDataDefn>() as Index;
@@ -85,7 +96,9 @@ pub const MAX_KEY_LENGTH: Index = std::mem::size_of::<CounterMetaDataKey>() as I
 #[repr(C, packed(4))]
 struct CounterValueDefn {
     counter_value: u64,
-    pad1: [i8; (2 * CACHE_LINE_LENGTH - I64_SIZE) as usize],
+    registration_id: i64,
+    owner_id: i64,
+    pad1: [i8; (2 * CACHE_LINE_LENGTH - 3 * I64_SIZE) as usize],
 }
 
 // This type is needed just to be able get sizeof of this packed array
@@ -148,6 +161,8 @@ lazy_static! {
     pub static ref LABEL_LENGTH_OFFSET: Index = offset_of!(CounterMetaDataDefn, label_length) as Index;
     pub static ref KEY_OFFSET: Index = offset_of!(CounterMetaDataDefn, key) as Index;
     pub static ref TYPE_ID_OFFSET: Index = offset_of!(CounterMetaDataDefn, type_id) as Index;
+    pub static ref REGISTRATION_ID_OFFSET: Index = offset_of!(CounterValueDefn, registration_id) as Index;
+    pub static ref OWNER_ID_OFFSET: Index = offset_of!(CounterValueDefn, owner_id) as Index;
 }
 
 pub struct CountersReader {
@@ -199,6 +214,22 @@ impl CountersReader {
         Ok(self.values_buffer.get_volatile::<u64>(Self::counter_offset(id)))
     }
 
+    /// Registration id of the counter, set by the driver for counters added by clients.
+    pub fn counter_registration_id(&self, id: i32) -> Result<i64, AeronError> {
+        self.validate_counter_id(id)?;
+        Ok(self
+            .values_buffer
+            .get_volatile::<i64>(Self::counter_offset(id) + *REGISTRATION_ID_OFFSET))
+    }
+
+    /// Client id of the client which added the counter or DEFAULT_OWNER_ID.
+    pub fn counter_owner_id(&self, id: i32) -> Result<i64, AeronError> {
+        self.validate_counter_id(id)?;
+        Ok(self
+            .values_buffer
+            .get_volatile::<i64>(Self::counter_offset(id) + *OWNER_ID_OFFSET))
+    }
+
     pub fn counter_state(&self, id: i32) -> Result<i32, AeronError> {
         self.validate_counter_id(id)?;
         Ok(self.metadata_buffer.get_volatile::<i32>(Self::metadata_offset(id)))
@@ -404,6 +435,14 @@ impl CountersManager {
             .metadata_buffer
             .put_string(record_offset + *LABEL_LENGTH_OFFSET, label.as_bytes());
 
+        let value_offset = CountersReader::counter_offset(counter_id);
+        self.reader
+            .values_buffer
+            .put::<i64>(value_offset + *REGISTRATION_ID_OFFSET, DEFAULT_REGISTRATION_ID);
+        self.reader
+            .values_buffer
+            .put::<i64>(value_offset + *OWNER_ID_OFFSET, DEFAULT_OWNER_ID);
+
         self.reader
             .metadata_buffer
             .put_ordered::<i32>(record_offset, RECORD_ALLOCATED);
@@ -437,6 +476,19 @@ impl CountersManager {
         self.reader.counter_value(id)
     }
 
+    pub fn set_counter_registration_id(&mut self, counter_id: i32, registration_id: i64) {
+        self.reader.values_buffer.put_ordered::<i64>(
+            CountersReader::counter_offset(counter_id) + *REGISTRATION_ID_OFFSET,
+            registration_id,
+        );
+    }
+
+    pub fn set_counter_owner_id(&mut self, counter_id: i32, owner_id: i64) {
+        self.reader
+            .values_buffer
+            .put_ordered::<i64>(CountersReader::counter_offset(counter_id) + *OWNER_ID_OFFSET, owner_id);
+    }
+
     fn next_counter_id(&mut self) -> i32 {
         let now_ms = (self.clock)();
 
@@ -483,6 +535,38 @@ impl CountersManager {
     }
 }
 
+/// Counters buffers with a manager to write counters and a reader over the same buffers for tests.
+#[cfg(test)]
+pub(crate) struct CountersFixture {
+    _metadata: AlignedBuffer,
+    _values: AlignedBuffer,
+    metadata_buffer: AtomicBuffer,
+    values_buffer: AtomicBuffer,
+    pub manager: CountersManager,
+}
+
+#[cfg(test)]
+impl CountersFixture {
+    pub fn new(num_counters: i32) -> Self {
+        let metadata = AlignedBuffer::with_capacity(num_counters * METADATA_LENGTH);
+        let values = AlignedBuffer::with_capacity(num_counters * COUNTER_LENGTH);
+        let metadata_buffer = AtomicBuffer::from_aligned(&metadata);
+        let values_buffer = AtomicBuffer::from_aligned(&values);
+
+        Self {
+            _metadata: metadata,
+            _values: values,
+            metadata_buffer,
+            values_buffer,
+            manager: CountersManager::new(metadata_buffer, values_buffer),
+        }
+    }
+
+    pub fn reader(&self) -> CountersReader {
+        CountersReader::new(self.metadata_buffer, self.values_buffer)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
@@ -792,4 +876,32 @@ mod tests {
         counters_manager.set_counter_value(counter_id, value);
         assert_eq!(counters_manager.counter_value(counter_id).unwrap(), value);
     }
+
+    #[test]
+    fn test_counters_store_registration_and_owner_id() {
+        gen_counters_manager!(counters_manager);
+
+        let counter_id = counters_manager.allocate("abc").unwrap();
+        assert_eq!(
+            counters_manager.reader.counter_registration_id(counter_id).unwrap(),
+            DEFAULT_REGISTRATION_ID
+        );
+        assert_eq!(
+            counters_manager.reader.counter_owner_id(counter_id).unwrap(),
+            DEFAULT_OWNER_ID
+        );
+
+        counters_manager.set_counter_registration_id(counter_id, 42);
+        counters_manager.set_counter_owner_id(counter_id, 7);
+        assert_eq!(counters_manager.reader.counter_registration_id(counter_id).unwrap(), 42);
+        assert_eq!(counters_manager.reader.counter_owner_id(counter_id).unwrap(), 7);
+
+        // Reused counter records start without registration and owner
+        counters_manager.free(counter_id);
+        let counter_id = counters_manager.allocate("def").unwrap();
+        assert_eq!(
+            counters_manager.reader.counter_owner_id(counter_id).unwrap(),
+            DEFAULT_OWNER_ID
+        );
+    }
 }
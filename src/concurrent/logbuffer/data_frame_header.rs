@@ -53,6 +53,9 @@ pub const HDR_TYPE_NAK: u16 = 0x02;
 pub const HDR_TYPE_SM: u16 = 0x03;
 pub const HDR_TYPE_ERR: u16 = 0x04;
 pub const HDR_TYPE_SETUP: u16 = 0x05;
+pub const HDR_TYPE_RTTM: u16 = 0x06;
+pub const HDR_TYPE_RES: u16 = 0x07;
+pub const HDR_TYPE_RSP_SETUP: u16 = 0x0B;
 pub const HDR_TYPE_EXT: u16 = 0xFFFF;
 
 pub const CURRENT_VERSION: u8 = 0x0;
@@ -17,7 +17,7 @@
 use lazy_static::lazy_static;
 
 use crate::concurrent::atomic_buffer::AtomicBuffer;
-use crate::utils::bit_utils::is_power_of_two;
+use crate::utils::bit_utils::{is_power_of_two, number_of_trailing_zeroes};
 use crate::utils::errors::{AeronError, IllegalStateError};
 use crate::utils::misc::CACHE_LINE_LENGTH;
 use crate::utils::types::{Index, I32_SIZE, I64_SIZE};
@@ -125,6 +125,7 @@ lazy_static! {
     pub static ref LOG_END_OF_STREAM_POSITION_OFFSET: Index = offset_of!(LogMetaDataDefn, end_of_stream_position) as Index;
     pub static ref LOG_IS_CONNECTED_OFFSET: Index = offset_of!(LogMetaDataDefn, is_connected) as Index;
     pub static ref LOG_ACTIVE_TRANSPORT_COUNT: Index = offset_of!(LogMetaDataDefn, active_transport_count) as Index;
+    pub static ref LOG_CORRELATION_ID_OFFSET: Index = offset_of!(LogMetaDataDefn, correlation_id) as Index;
     pub static ref LOG_INITIAL_TERM_ID_OFFSET: Index = offset_of!(LogMetaDataDefn, initial_term_id) as Index;
     pub static ref LOG_DEFAULT_FRAME_HEADER_LENGTH_OFFSET: Index =
         offset_of!(LogMetaDataDefn, default_frame_header_length) as Index;
@@ -188,6 +189,14 @@ pub fn initial_term_id(log_meta_data_buffer: &AtomicBuffer) -> i32 {
     log_meta_data_buffer.get::<i32>(*LOG_INITIAL_TERM_ID_OFFSET)
 }
 
+pub fn correlation_id(log_meta_data_buffer: &AtomicBuffer) -> i64 {
+    log_meta_data_buffer.get::<i64>(*LOG_CORRELATION_ID_OFFSET)
+}
+
+pub fn default_frame_header_length(log_meta_data_buffer: &AtomicBuffer) -> i32 {
+    log_meta_data_buffer.get::<i32>(*LOG_DEFAULT_FRAME_HEADER_LENGTH_OFFSET)
+}
+
 pub fn mtu_length(log_meta_data_buffer: &AtomicBuffer) -> i32 {
     log_meta_data_buffer.get::<i32>(*LOG_MTU_LENGTH_OFFSET)
 }
@@ -268,6 +277,43 @@ pub fn compute_term_begin_position(active_term_id: i32, position_bits_to_shift:
     term_count << position_bits_to_shift as i64
 }
 
+/**
+ * Compute the number of bits to shift when dividing a position by the term buffer length.
+ *
+ * @param term_buffer_length to compute the number of bits to shift for.
+ * @return the number of bits to shift to divide by the term buffer length.
+ */
+pub fn position_bits_to_shift(term_buffer_length: Index) -> Result<i32, AeronError> {
+    check_term_length(term_buffer_length)?;
+
+    Ok(number_of_trailing_zeroes(term_buffer_length))
+}
+
+/**
+ * Compute the term id from a position.
+ *
+ * @param position               to calculate from.
+ * @param position_bits_to_shift number of times to left shift the position.
+ * @param initial_term_id        the initial term id that this stream started on.
+ * @return the term id according to the position.
+ */
+pub fn compute_term_id_from_position(position: i64, position_bits_to_shift: i32, initial_term_id: i32) -> i32 {
+    ((position >> position_bits_to_shift) as i32).wrapping_add(initial_term_id)
+}
+
+/**
+ * Compute the term offset from a given position.
+ *
+ * @param position               to calculate from.
+ * @param position_bits_to_shift number of times to left shift the position.
+ * @return the offset within the term that represents the position.
+ */
+pub fn compute_term_offset_from_position(position: i64, position_bits_to_shift: i32) -> Index {
+    let mask = (1_i64 << position_bits_to_shift) - 1;
+
+    (position & mask) as Index
+}
+
 pub fn raw_tail_volatile(log_meta_data_buffer: &AtomicBuffer) -> i64 {
     let partition_index = index_by_term_count(active_term_count(log_meta_data_buffer) as i64);
     log_meta_data_buffer.get_volatile::<i64>(*TERM_TAIL_COUNTER_OFFSET + (partition_index * I64_SIZE))
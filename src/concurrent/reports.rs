@@ -84,11 +84,96 @@ pub mod loss_report_descriptor {
 
 lazy_static! {
     pub static ref OBSERVATION_COUNT_OFFSET: Index = offset_of!(LossReportEntryDefn, observation_count) as Index;
-    pub static ref ENTRY_ALIGNMENT: Index = std::mem::size_of_val(&CACHE_LINE_LENGTH) as Index;
+    pub static ref ENTRY_ALIGNMENT: Index = CACHE_LINE_LENGTH;
 }
 
 pub type LossConsumerHandler = fn(i64, LossReportEntryDefn, CString /* channel */, CString /* source */);
 
+/// Loss report entry copied out of the report.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LossReportEntry {
+    /// Offset of the entry in the report. Entries do not move so the offset identifies the entry between reads.
+    pub offset: Index,
+    pub observation_count: i64,
+    pub total_bytes_lost: i64,
+    pub first_observation_timestamp: i64,
+    pub last_observation_timestamp: i64,
+    pub session_id: i32,
+    pub stream_id: i32,
+    pub channel: String,
+    pub source: String,
+}
+
+/// Source follows the channel without padding so its length prefix is not necessarily aligned.
+fn get_unaligned_string(buffer: &AtomicBuffer, offset: Index) -> CString {
+    let mut length_bytes = [0_u8; 4];
+    length_bytes.copy_from_slice(buffer.as_sub_slice(offset, I32_SIZE));
+
+    buffer.get_string_without_length(offset + I32_SIZE, i32::from_le_bytes(length_bytes))
+}
+
+/// Iterator over the entries of a loss report, see entries().
+pub struct LossReportEntries {
+    buffer: AtomicBuffer,
+    offset: Index,
+}
+
+impl Iterator for LossReportEntries {
+    type Item = LossReportEntry;
+
+    fn next(&mut self) -> Option<LossReportEntry> {
+        let offset = self.offset;
+
+        if offset >= self.buffer.capacity() {
+            return None;
+        }
+
+        let observation_count: i64 = self.buffer.get_volatile::<i64>(offset + *OBSERVATION_COUNT_OFFSET);
+
+        if 0 == observation_count {
+            return None;
+        }
+
+        let channel = self.buffer.get_string(offset + loss_report_descriptor::CHANNEL_OFFSET);
+        let channel_length = channel.as_bytes().len() as Index;
+        let source = get_unaligned_string(
+            &self.buffer,
+            offset + loss_report_descriptor::CHANNEL_OFFSET + I32_SIZE + channel_length,
+        );
+        let source_length = source.as_bytes().len() as Index;
+
+        let record = self.buffer.get::<loss_report_descriptor::LossReportEntryDefn>(offset);
+
+        let record_length = loss_report_descriptor::CHANNEL_OFFSET + I32_SIZE * 2 + channel_length + source_length;
+        self.offset += bit_utils::align(record_length, *ENTRY_ALIGNMENT);
+
+        Some(LossReportEntry {
+            offset,
+            observation_count,
+            total_bytes_lost: record.total_bytes_lost,
+            first_observation_timestamp: record.first_observation_timestamp,
+            last_observation_timestamp: record.last_observation_timestamp,
+            session_id: record.session_id,
+            stream_id: record.stream_id,
+            channel: channel.to_string_lossy().into_owned(),
+            source: source.to_string_lossy().into_owned(),
+        })
+    }
+}
+
+/**
+ * Iterate over the entries of a LossReport contained in the buffer. This can be done concurrently.
+ *
+ * @param buffer containing the loss report.
+ * @return iterator over the entries in the report.
+ */
+pub fn entries(buffer: &AtomicBuffer) -> LossReportEntries {
+    LossReportEntries {
+        buffer: *buffer,
+        offset: 0,
+    }
+}
+
 /**
  * Read a LossReport contained in the buffer. This can be done concurrently.
  *
@@ -113,7 +198,10 @@ pub fn read(buffer: &AtomicBuffer, consumer: LossConsumerHandler) -> i32 {
 
         let channel = buffer.get_string(offset + loss_report_descriptor::CHANNEL_OFFSET);
         let channel_length = channel.as_bytes().len() as Index;
-        let source = buffer.get_string(offset + loss_report_descriptor::CHANNEL_OFFSET + I32_SIZE + channel_length);
+        let source = get_unaligned_string(
+            buffer,
+            offset + loss_report_descriptor::CHANNEL_OFFSET + I32_SIZE + channel_length,
+        );
         let source_length = source.as_bytes().len() as Index;
 
         let record = buffer.get::<loss_report_descriptor::LossReportEntryDefn>(offset);
@@ -127,3 +215,61 @@ pub fn read(buffer: &AtomicBuffer, consumer: LossConsumerHandler) -> i32 {
 
     records_read
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::concurrent::atomic_buffer::AlignedBuffer;
+
+    fn put_entry(buffer: &AtomicBuffer, offset: Index, bytes_lost: i64, session_id: i32, channel: &str, source: &str) -> Index {
+        let record = loss_report_descriptor::LossReportEntryDefn {
+            observation_count: 0,
+            total_bytes_lost: bytes_lost,
+            first_observation_timestamp: 10,
+            last_observation_timestamp: 20,
+            session_id,
+            stream_id: 1001,
+        };
+        buffer.put::<loss_report_descriptor::LossReportEntryDefn>(offset, record);
+
+        let channel_offset = offset + loss_report_descriptor::CHANNEL_OFFSET;
+        buffer.put_string(channel_offset, channel.as_bytes());
+        let source_offset = channel_offset + I32_SIZE + channel.len() as Index;
+        buffer.put_bytes(source_offset, &(source.len() as i32).to_le_bytes());
+        buffer.put_bytes(source_offset + I32_SIZE, source.as_bytes());
+        buffer.put::<i64>(offset + *OBSERVATION_COUNT_OFFSET, 2);
+
+        let record_length = loss_report_descriptor::CHANNEL_OFFSET + I32_SIZE * 2 + (channel.len() + source.len()) as Index;
+        offset + bit_utils::align(record_length, CACHE_LINE_LENGTH)
+    }
+
+    #[test]
+    fn should_iterate_entries_aligned_to_cache_line() {
+        let aligned = AlignedBuffer::with_capacity(1024);
+        let buffer = AtomicBuffer::from_aligned(&aligned);
+
+        let second_offset = put_entry(&buffer, 0, 4096, 77, "aeron:udp?endpoint=localhost:40123", "127.0.0.1:50000");
+        put_entry(
+            &buffer,
+            second_offset,
+            1024,
+            78,
+            "aeron:udp?endpoint=localhost:40124",
+            "127.0.0.1:50001",
+        );
+
+        let entries: Vec<LossReportEntry> = entries(&buffer).collect();
+
+        assert_eq!(second_offset, 2 * CACHE_LINE_LENGTH);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].observation_count, 2);
+        assert_eq!(entries[0].total_bytes_lost, 4096);
+        assert_eq!(entries[0].session_id, 77);
+        assert_eq!(entries[0].stream_id, 1001);
+        assert_eq!(entries[0].channel, "aeron:udp?endpoint=localhost:40123");
+        assert_eq!(entries[0].source, "127.0.0.1:50000");
+        assert_eq!(entries[1].offset, second_offset);
+        assert_eq!(entries[1].source, "127.0.0.1:50001");
+        assert_eq!(read(&buffer, |_, _, _, _| {}), 2);
+    }
+}
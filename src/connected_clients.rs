@@ -0,0 +1,306 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::collections::BTreeMap;
+
+use crate::concurrent::counters::{CountersReader, DEFAULT_OWNER_ID};
+use crate::driver_counters::{DriverCounter, StreamCounterKey};
+use crate::utils::types::Moment;
+
+/// Publication or subscription of a client as seen through the driver stream counters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientResource {
+    pub registration_id: i64,
+    pub stream_id: i32,
+    pub channel: String,
+    /// Session ids of the publication or of the images of the subscription.
+    pub session_ids: Vec<i32>,
+    /// Ids of the driver counters keyed by the registration id of this resource.
+    pub counter_ids: Vec<i32>,
+}
+
+/// Counter added by a client through Aeron::add_counter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientCounter {
+    pub counter_id: i32,
+    pub registration_id: i64,
+    pub type_id: i32,
+    pub label: String,
+}
+
+/// Client connected to the driver, identified by its heartbeat counter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectedClient {
+    pub client_id: i64,
+    pub heartbeat_counter_id: i32,
+    /// Time of the last client heartbeat in ms since epoch.
+    pub last_heartbeat_ms: Moment,
+    pub heartbeat_age_ms: Moment,
+    /// True if the heartbeat age is within client liveness timeout of the driver.
+    pub is_alive: bool,
+    pub publications: Vec<ClientResource>,
+    pub subscriptions: Vec<ClientResource>,
+    /// Counters added by the client, attributed by their owner id.
+    pub counters: Vec<ClientCounter>,
+}
+
+impl ConnectedClient {
+    /// Ids of all counters owned by the client: heartbeat, stream counters of its resources and counters
+    /// it added.
+    pub fn counter_ids(&self) -> Vec<i32> {
+        let mut counter_ids = vec![self.heartbeat_counter_id];
+
+        for resource in self.publications.iter().chain(self.subscriptions.iter()) {
+            counter_ids.extend_from_slice(&resource.counter_ids);
+        }
+        counter_ids.extend(self.counters.iter().map(|counter| counter.counter_id));
+
+        counter_ids
+    }
+}
+
+fn add_to_resource(resources: &mut BTreeMap<i64, ClientResource>, key: StreamCounterKey, counter_id: i32) {
+    let StreamCounterKey {
+        registration_id,
+        session_id,
+        stream_id,
+        channel,
+    } = key;
+
+    let resource = resources.entry(registration_id).or_insert_with(|| ClientResource {
+        registration_id,
+        stream_id,
+        channel,
+        session_ids: Vec::new(),
+        counter_ids: Vec::new(),
+    });
+
+    if !resource.session_ids.contains(&session_id) {
+        resource.session_ids.push(session_id);
+    }
+    resource.counter_ids.push(counter_id);
+}
+
+/**
+ * Enumerates clients connected to the driver using client heartbeat counters.
+ *
+ * The driver doesn't record the owner of publications and subscriptions, so they are attributed by their
+ * registration ids: client ids and registration ids are drawn from the same correlation id sequence of the
+ * driver, so a resource is attributed to the client with the greatest client id which is not greater than the
+ * registration id of the resource. This is exact when clients don't create resources concurrently and a best
+ * effort estimate otherwise. Resources registered before the oldest known client are not reported.
+ *
+ * Counters added by clients carry the client id as owner id and are attributed exactly.
+ *
+ * @param counters_reader of the driver CnC file.
+ * @param client_liveness_timeout_ns as stored in the CnC file.
+ * @param now_ms current time in ms since epoch.
+ * @return clients ordered by client id.
+ */
+pub fn connected_clients(
+    counters_reader: &CountersReader,
+    client_liveness_timeout_ns: i64,
+    now_ms: Moment,
+) -> Vec<ConnectedClient> {
+    let mut clients: BTreeMap<i64, ConnectedClient> = BTreeMap::new();
+    let mut publications: BTreeMap<i64, ClientResource> = BTreeMap::new();
+    let mut subscriptions: BTreeMap<i64, ClientResource> = BTreeMap::new();
+    let mut counters: Vec<(i64, ClientCounter)> = Vec::new();
+
+    counters_reader.for_each(
+        |counter_id, type_id, key_buffer, label| match DriverCounter::decode(type_id, key_buffer) {
+            DriverCounter::ClientHeartbeat { client_id } => {
+                let last_heartbeat_ms = counters_reader.counter_value(counter_id).unwrap_or_default();
+                let heartbeat_age_ms = now_ms.saturating_sub(last_heartbeat_ms);
+
+                clients.insert(client_id, ConnectedClient {
+                    client_id,
+                    heartbeat_counter_id: counter_id,
+                    last_heartbeat_ms,
+                    heartbeat_age_ms,
+                    is_alive: (heartbeat_age_ms as i64).saturating_mul(1_000_000) <= client_liveness_timeout_ns,
+                    publications: Vec::new(),
+                    subscriptions: Vec::new(),
+                    counters: Vec::new(),
+                });
+            },
+            DriverCounter::PublisherLimit(key)
+            | DriverCounter::SenderPosition(key)
+            | DriverCounter::SenderLimit(key)
+            | DriverCounter::PublisherPosition(key) => add_to_resource(&mut publications, key, counter_id),
+            DriverCounter::SubscriberPosition(key) => add_to_resource(&mut subscriptions, key, counter_id),
+            DriverCounter::Other { type_id } => {
+                let owner_id = counters_reader.counter_owner_id(counter_id).unwrap_or(DEFAULT_OWNER_ID);
+
+                if owner_id != DEFAULT_OWNER_ID {
+                    counters.push((owner_id, ClientCounter {
+                        counter_id,
+                        registration_id: counters_reader.counter_registration_id(counter_id).unwrap_or_default(),
+                        type_id,
+                        label: label.to_string_lossy().into_owned(),
+                    }));
+                }
+            },
+            _ => {},
+        },
+    );
+
+    for (registration_id, publication) in publications {
+        if let Some((_, client)) = clients.range_mut(..=registration_id).next_back() {
+            client.publications.push(publication);
+        }
+    }
+
+    for (registration_id, subscription) in subscriptions {
+        if let Some((_, client)) = clients.range_mut(..=registration_id).next_back() {
+            client.subscriptions.push(subscription);
+        }
+    }
+
+    for (owner_id, counter) in counters {
+        if let Some(client) = clients.get_mut(&owner_id) {
+            client.counters.push(counter);
+        }
+    }
+
+    clients.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::concurrent::atomic_buffer::AtomicBuffer;
+    use crate::concurrent::counters::CountersFixture;
+    use crate::driver_counters::{
+        CLIENT_HEARTBEAT_TYPE_ID, PUBLISHER_LIMIT_TYPE_ID, SENDER_POSITION_TYPE_ID, STREAM_COUNTER_CHANNEL_OFFSET,
+        STREAM_COUNTER_REGISTRATION_ID_OFFSET, STREAM_COUNTER_SESSION_ID_OFFSET, STREAM_COUNTER_STREAM_ID_OFFSET,
+        SUBSCRIBER_POSITION_TYPE_ID,
+    };
+
+    const NUM_COUNTERS: i32 = 8;
+    const CHANNEL: &str = "aeron:udp?endpoint=localhost:40123";
+    const LIVENESS_TIMEOUT_NS: i64 = 10_000_000_000;
+    const NOW_MS: Moment = 1_000_000;
+
+    fn add_heartbeat(fixture: &mut CountersFixture, client_id: i64, heartbeat_ms: Moment) -> i32 {
+        let id = fixture
+            .manager
+            .allocate_opt(
+                CLIENT_HEARTBEAT_TYPE_ID,
+                Some(&client_id.to_le_bytes()),
+                Option::<fn(&mut AtomicBuffer)>::None,
+                "client-heartbeat",
+            )
+            .unwrap();
+        fixture.manager.set_counter_value(id, heartbeat_ms);
+        id
+    }
+
+    fn add_stream_counter(
+        fixture: &mut CountersFixture,
+        type_id: i32,
+        registration_id: i64,
+        session_id: i32,
+        stream_id: i32,
+    ) -> i32 {
+        fixture
+            .manager
+            .allocate_opt(
+                type_id,
+                None,
+                Some(|key_buffer: &mut AtomicBuffer| {
+                    key_buffer.put::<i64>(STREAM_COUNTER_REGISTRATION_ID_OFFSET, registration_id);
+                    key_buffer.put::<i32>(STREAM_COUNTER_SESSION_ID_OFFSET, session_id);
+                    key_buffer.put::<i32>(STREAM_COUNTER_STREAM_ID_OFFSET, stream_id);
+                    key_buffer.put_string(STREAM_COUNTER_CHANNEL_OFFSET, CHANNEL.as_bytes());
+                }),
+                "stream counter",
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn should_report_client_liveness() {
+        let mut fixture = CountersFixture::new(NUM_COUNTERS);
+        add_heartbeat(&mut fixture, 1, NOW_MS - 100);
+        add_heartbeat(&mut fixture, 5, NOW_MS - 20_000);
+
+        let clients = connected_clients(&fixture.reader(), LIVENESS_TIMEOUT_NS, NOW_MS);
+
+        assert_eq!(clients.len(), 2);
+        assert_eq!(clients[0].client_id, 1);
+        assert_eq!(clients[0].heartbeat_age_ms, 100);
+        assert!(clients[0].is_alive);
+        assert_eq!(clients[1].client_id, 5);
+        assert!(!clients[1].is_alive);
+    }
+
+    #[test]
+    fn should_attribute_resources_to_clients_by_registration_id() {
+        let mut fixture = CountersFixture::new(NUM_COUNTERS);
+        let heartbeat_id = add_heartbeat(&mut fixture, 1, NOW_MS);
+        add_heartbeat(&mut fixture, 10, NOW_MS);
+
+        let pub_limit_id = add_stream_counter(&mut fixture, PUBLISHER_LIMIT_TYPE_ID, 3, 77, 1001);
+        let sender_position_id = add_stream_counter(&mut fixture, SENDER_POSITION_TYPE_ID, 3, 77, 1001);
+        add_stream_counter(&mut fixture, SUBSCRIBER_POSITION_TYPE_ID, 12, 78, 1002);
+        add_stream_counter(&mut fixture, SUBSCRIBER_POSITION_TYPE_ID, 12, 79, 1002);
+
+        let clients = connected_clients(&fixture.reader(), LIVENESS_TIMEOUT_NS, NOW_MS);
+
+        assert_eq!(clients[0].publications.len(), 1);
+        assert!(clients[0].subscriptions.is_empty());
+        assert_eq!(clients[0].publications[0].channel, CHANNEL);
+        assert_eq!(clients[0].publications[0].stream_id, 1001);
+        assert_eq!(clients[0].publications[0].session_ids, vec![77]);
+        assert_eq!(clients[0].counter_ids(), vec![heartbeat_id, pub_limit_id, sender_position_id]);
+
+        assert!(clients[1].publications.is_empty());
+        assert_eq!(clients[1].subscriptions.len(), 1);
+        assert_eq!(clients[1].subscriptions[0].registration_id, 12);
+        assert_eq!(clients[1].subscriptions[0].session_ids, vec![78, 79]);
+    }
+
+    #[test]
+    fn should_attribute_added_counters_to_owner() {
+        let mut fixture = CountersFixture::new(NUM_COUNTERS);
+        let heartbeat_id = add_heartbeat(&mut fixture, 1, NOW_MS);
+        add_heartbeat(&mut fixture, 10, NOW_MS);
+
+        let counter_id = fixture
+            .manager
+            .allocate_opt(1001, None, Option::<fn(&mut AtomicBuffer)>::None, "app counter")
+            .unwrap();
+        fixture.manager.set_counter_registration_id(counter_id, 14);
+        fixture.manager.set_counter_owner_id(counter_id, 1);
+        // Counters without owner aren't attributed
+        fixture
+            .manager
+            .allocate_opt(1001, None, Option::<fn(&mut AtomicBuffer)>::None, "other counter")
+            .unwrap();
+
+        let clients = connected_clients(&fixture.reader(), LIVENESS_TIMEOUT_NS, NOW_MS);
+
+        assert_eq!(clients[0].counters, vec![ClientCounter {
+            counter_id,
+            registration_id: 14,
+            type_id: 1001,
+            label: String::from("app counter"),
+        }]);
+        assert_eq!(clients[0].counter_ids(), vec![heartbeat_id, counter_id]);
+        assert!(clients[1].counters.is_empty());
+    }
+}
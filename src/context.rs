@@ -18,15 +18,16 @@ use std::env;
 use std::ffi::CString;
 use std::sync::Arc;
 
+use crate::channel_status_registry::ErroredChannel;
 use crate::cnc_file_descriptor;
 use crate::concurrent::counters::CountersReader;
 use crate::concurrent::logbuffer::term_reader::ErrorHandler;
 use crate::concurrent::ring_buffer::ManyToOneRingBuffer;
-use crate::driver_proxy::DriverProxy;
+use crate::driver_proxy::{DriverProxy, MAX_TERMINATION_TOKEN_LENGTH};
 use crate::image::Image;
-use crate::utils::errors::{AeronError, GenericError};
+use crate::utils::errors::{AeronError, GenericError, IllegalArgumentError};
 use crate::utils::memory_mapped_file::MemoryMappedFile;
-use crate::utils::misc::{semantic_version_major, semantic_version_to_string};
+use crate::utils::misc::{semantic_version_major, semantic_version_to_string, unix_time_ms};
 use crate::utils::types::{Index, Moment};
 
 /// This name is used for conductor thread and useful when debugging or examining logs from
@@ -272,6 +273,41 @@ where
     }
 }
 
+/**
+ * Function called by Aeron when the channel endpoint of a publication or subscription goes into ERRORED
+ * state, e.g. because the UDP endpoint failed to bind.
+ *
+ * Implementations should do the minimum work for passing off state to another thread for later processing
+ * and should not make a reentrant call back into the Aeron instance.
+ *
+ * @param errored_channel registration id, stream id and channel of the publication or subscription together with
+ * the text of the ChannelEndpointException from the driver if it was received within ERROR_MESSAGE_WAIT_MS of the
+ * channel status going ERRORED.
+ */
+pub trait OnChannelErrored {
+    fn call(&self, errored_channel: &ErroredChannel);
+    fn clone_box(&self) -> Box<dyn OnChannelErrored>;
+}
+
+impl Clone for Box<dyn OnChannelErrored> {
+    fn clone(&self) -> Box<dyn OnChannelErrored> {
+        self.clone_box()
+    }
+}
+
+impl<F> OnChannelErrored for F
+where
+    F: Fn(&ErroredChannel) + Clone + 'static,
+{
+    fn call(&self, errored_channel: &ErroredChannel) {
+        self(errored_channel)
+    }
+
+    fn clone_box(&self) -> Box<dyn OnChannelErrored> {
+        Box::new(self.clone())
+    }
+}
+
 const DEFAULT_MEDIA_DRIVER_TIMEOUT_MS: Moment = 10000;
 const DEFAULT_RESOURCE_LINGER_MS: Moment = 5000;
 
@@ -302,6 +338,8 @@ fn default_on_unavailable_counter_handler(_counters_reader: &CountersReader, _re
 
 fn default_on_close_client_handler() {}
 
+fn default_on_channel_errored_handler(_errored_channel: &ErroredChannel) {}
+
 /**
  * Context provides configuration for the {@link Aeron} class via the {@link Aeron::Aeron} or {@link Aeron::connect}
  * methods and its overloads. It gives applications some control over the interactions with the Aeron Media Driver.
@@ -320,6 +358,7 @@ pub struct Context {
     on_available_counter_handler: Box<dyn OnAvailableCounter>,
     on_unavailable_counter_handler: Box<dyn OnUnavailableCounter>,
     on_close_client_handler: Box<dyn OnCloseClient>,
+    on_channel_errored_handler: Box<dyn OnChannelErrored>,
     media_driver_timeout: Moment,
     resource_linger_timeout: Moment,
     use_conductor_agent_invoker: bool,
@@ -347,6 +386,7 @@ impl Context {
             on_available_counter_handler: Box::new(default_on_available_counter_handler),
             on_unavailable_counter_handler: Box::new(default_on_unavailable_counter_handler),
             on_close_client_handler: Box::new(default_on_close_client_handler),
+            on_channel_errored_handler: Box::new(default_on_channel_errored_handler),
             media_driver_timeout: DEFAULT_MEDIA_DRIVER_TIMEOUT_MS,
             resource_linger_timeout: DEFAULT_RESOURCE_LINGER_MS,
             use_conductor_agent_invoker: false,
@@ -536,6 +576,22 @@ impl Context {
         self.on_close_client_handler.clone_box()
     }
 
+    /**
+     * Set the handler to be called when the channel endpoint of a publication or subscription goes into
+     * ERRORED state.
+     *
+     * @param handler called when event occurs
+     * @return reference to this Context instance
+     */
+    pub fn set_channel_errored_handler(&mut self, handler: impl OnChannelErrored + 'static) -> &Self {
+        self.on_channel_errored_handler = Box::new(handler);
+        self
+    }
+
+    pub fn channel_errored_handler(&self) -> Box<dyn OnChannelErrored> {
+        self.on_channel_errored_handler.clone_box()
+    }
+
     /**
      * Set the amount of time, in milliseconds, that this client will wait until it determines the
      * Media Driver is unavailable. When this happens a DriverTimeoutException will be generated for the error
@@ -633,6 +689,71 @@ impl Context {
         Ok(())
     }
 
+    /**
+     * Same as request_driver_termination() but takes the termination validation token as a slice.
+     * An empty token is sent as a request without a token, a token longer than MAX_TERMINATION_TOKEN_LENGTH
+     * is rejected.
+     */
+    pub fn request_driver_termination_with_token(directory: &str, token: &[u8]) -> Result<(), AeronError> {
+        if token.len() > MAX_TERMINATION_TOKEN_LENGTH as usize {
+            return Err(IllegalArgumentError::TerminationTokenTooLong {
+                token_length: token.len(),
+                limit: MAX_TERMINATION_TOKEN_LENGTH,
+            }
+            .into());
+        }
+
+        Self::request_driver_termination(directory, token.as_ptr() as *mut u8, token.len() as Index)
+    }
+
+    /**
+     * Reads the time of the last driver heartbeat (ms since epoch) from the CnC file in the directory.
+     *
+     * @return None if the CnC file doesn't exist or the driver has not initialised it yet.
+     */
+    pub fn driver_heartbeat_time(directory: &str) -> Result<Option<Moment>, AeronError> {
+        let cnc_filename = String::from(directory) + "/" + cnc_file_descriptor::CNC_FILE;
+
+        match MemoryMappedFile::get_file_size(cnc_filename.clone()) {
+            Ok(size) if size > 0 => {},
+            _ => return Ok(None),
+        }
+
+        let cnc_file = MemoryMappedFile::map_existing(cnc_filename, false)?;
+        let cnc_version = cnc_file_descriptor::cnc_version_volatile(&cnc_file);
+
+        if 0 == cnc_version {
+            return Ok(None);
+        }
+
+        if semantic_version_major(cnc_version) != semantic_version_major(cnc_file_descriptor::CNC_VERSION) {
+            return Err(GenericError::CncVersionDoesntMatch {
+                app_version: semantic_version_to_string(cnc_file_descriptor::CNC_VERSION),
+                file_version: semantic_version_to_string(cnc_version),
+            }
+            .into());
+        }
+
+        let ring_buffer = ManyToOneRingBuffer::new(cnc_file_descriptor::create_to_driver_buffer(&cnc_file))?;
+        let heartbeat_time = ring_buffer.consumer_heartbeat_time();
+
+        if heartbeat_time > 0 {
+            Ok(Some(heartbeat_time as Moment))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /**
+     * Checks if the driver which uses the directory has sent a heartbeat within driver_timeout_ms.
+     */
+    pub fn is_driver_active(directory: &str, driver_timeout_ms: Moment) -> Result<bool, AeronError> {
+        match Self::driver_heartbeat_time(directory)? {
+            Some(heartbeat_time) => Ok(heartbeat_time + driver_timeout_ms > unix_time_ms()),
+            None => Ok(false),
+        }
+    }
+
     pub fn tmp_dir() -> String {
         let mut dir = String::from("/tmp");
 
@@ -655,3 +776,21 @@ impl Context {
         String::from("/dev/shm/aeron-") + &Context::get_user_name()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_reject_too_long_termination_token() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let token = vec![b'x'; MAX_TERMINATION_TOKEN_LENGTH as usize + 1];
+
+        assert!(matches!(
+            Context::request_driver_termination_with_token(tmp_dir.path().to_str().unwrap(), &token),
+            Err(AeronError::IllegalArgument(
+                IllegalArgumentError::TerminationTokenTooLong { .. }
+            ))
+        ));
+    }
+}
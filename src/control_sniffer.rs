@@ -0,0 +1,1101 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::command::client_timeout_flyweight::{ClientTimeoutFlyweight, CLIENT_TIMEOUT_LENGTH};
+use crate::command::control_protocol_events::AeronCommand;
+use crate::command::correlated_message_flyweight::{CorrelatedMessageFlyweight, CORRELATED_MESSAGE_LENGTH};
+use crate::command::counter_message_flyweight::{CounterMessageFlyweight, COUNTER_MESSAGE_LENGTH};
+use crate::command::counter_update_flyweight::{CounterUpdateFlyweight, COUNTER_READY_LENGTH};
+use crate::command::destination_message_flyweight::DestinationMessageFlyweight;
+use crate::command::error_response_flyweight::ErrorResponseFlyweight;
+use crate::command::image_buffers_ready_flyweight::{ImageBuffersReadyFlyweight, IMAGE_BUFFERS_READY_LENGTH};
+use crate::command::image_message_flyweight::ImageMessageFlyweight;
+use crate::command::operation_succeeded_flyweight::{OperationSucceededFlyweight, OPERATION_SUCCEEDED_LENGTH};
+use crate::command::publication_buffers_ready_flyweight::PublicationBuffersReadyFlyweight;
+use crate::command::publication_message_flyweight::PublicationMessageFlyweight;
+use crate::command::remove_message_flyweight::{RemoveMessageFlyweight, REMOVE_MESSAGE_LENGTH};
+use crate::command::subscription_message_flyweight::SubscriptionMessageFlyweight;
+use crate::command::subscription_ready_flyweight::{SubscriptionReadyFlyweight, SUBSCRIPTION_READY_LENGTH};
+use crate::command::terminate_driver_flyweight::{TerminateDriverFlyweight, TERMINATE_DRIVER_LENGTH};
+use crate::concurrent::atomic_buffer::AtomicBuffer;
+use crate::concurrent::broadcast::broadcast_receiver::BroadcastReceiver;
+use crate::concurrent::ring_buffer::{self, record_descriptor, ManyToOneRingBuffer};
+use crate::utils::bit_utils;
+use crate::utils::errors::AeronError;
+use crate::utils::misc::unix_time_ns;
+use crate::utils::types::{Index, I32_SIZE, I64_SIZE};
+
+/// Offsets of the fixed fields before the length prefixed strings of the variable length messages.
+const PUBLICATION_MESSAGE_CHANNEL_OFFSET: Index = CORRELATED_MESSAGE_LENGTH + I32_SIZE;
+const SUBSCRIPTION_MESSAGE_CHANNEL_OFFSET: Index = CORRELATED_MESSAGE_LENGTH + I64_SIZE + I32_SIZE;
+const DESTINATION_MESSAGE_CHANNEL_OFFSET: Index = CORRELATED_MESSAGE_LENGTH + I64_SIZE;
+const IMAGE_MESSAGE_CHANNEL_OFFSET: Index = I64_SIZE * 2 + I32_SIZE;
+const PUBLICATION_READY_LOG_FILE_OFFSET: Index = I64_SIZE * 2 + I32_SIZE * 4;
+const ERROR_RESPONSE_MESSAGE_OFFSET: Index = I64_SIZE + I32_SIZE;
+
+/// Buffer of the CnC file a control message was read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlDirection {
+    ToDriver,
+    ToClients,
+}
+
+impl fmt::Display for ControlDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ControlDirection::ToDriver => write!(f, "CMD_IN"),
+            ControlDirection::ToClients => write!(f, "CMD_OUT"),
+        }
+    }
+}
+
+/**
+ * Control protocol message decoded with the command flyweights. Commands sharing a layout share a variant and keep
+ * the command to tell them apart.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub enum ControlMessage {
+    AddPublication {
+        exclusive: bool,
+        client_id: i64,
+        correlation_id: i64,
+        stream_id: i32,
+        channel: String,
+    },
+    AddSubscription {
+        client_id: i64,
+        correlation_id: i64,
+        registration_correlation_id: i64,
+        stream_id: i32,
+        channel: String,
+    },
+    /// RemovePublication, RemoveSubscription and RemoveCounter.
+    Remove {
+        command: AeronCommand,
+        client_id: i64,
+        correlation_id: i64,
+        registration_id: i64,
+    },
+    /// AddDestination, RemoveDestination, AddRcvDestination and RemoveRcvDestination.
+    Destination {
+        command: AeronCommand,
+        client_id: i64,
+        correlation_id: i64,
+        registration_id: i64,
+        channel: String,
+    },
+    AddCounter {
+        client_id: i64,
+        correlation_id: i64,
+        type_id: i32,
+        key_length: i32,
+        label: String,
+    },
+    /// ClientKeepAlive and ClientClose.
+    Client {
+        command: AeronCommand,
+        client_id: i64,
+        correlation_id: i64,
+    },
+    TerminateDriver {
+        client_id: i64,
+        correlation_id: i64,
+        token_length: i32,
+    },
+    Error {
+        offending_correlation_id: i64,
+        error_code: i32,
+        message: String,
+    },
+    PublicationReady {
+        exclusive: bool,
+        correlation_id: i64,
+        registration_id: i64,
+        session_id: i32,
+        stream_id: i32,
+        position_limit_counter_id: i32,
+        channel_status_indicator_id: i32,
+        log_file_name: String,
+    },
+    SubscriptionReady {
+        correlation_id: i64,
+        channel_status_indicator_id: i32,
+    },
+    AvailableImage {
+        correlation_id: i64,
+        session_id: i32,
+        stream_id: i32,
+        subscription_registration_id: i64,
+        subscriber_position_id: i32,
+        log_file_name: String,
+        source_identity: String,
+    },
+    UnavailableImage {
+        correlation_id: i64,
+        subscription_registration_id: i64,
+        stream_id: i32,
+        channel: String,
+    },
+    OperationSuccess {
+        correlation_id: i64,
+    },
+    /// ResponseOnCounterReady and ResponseOnUnavailableCounter.
+    Counter {
+        command: AeronCommand,
+        correlation_id: i64,
+        counter_id: i32,
+    },
+    ClientTimeout {
+        client_id: i64,
+    },
+    /// Message of a known command which is shorter than its fields claim.
+    Malformed {
+        command: AeronCommand,
+        length: Index,
+    },
+    /// Message of a command id this client doesn't know, e.g. sent by a newer driver.
+    Unknown {
+        type_id: i32,
+        length: Index,
+    },
+}
+
+impl ControlMessage {
+    /**
+     * Decode a control message. The buffer may be longer than the message, e.g. a scratch buffer it was copied to,
+     * fields are only read within the length.
+     *
+     * @param type_id of the record.
+     * @param buffer  with the message at offset 0.
+     * @param length  of the message.
+     * @return the message, Malformed when its fields exceed the length.
+     */
+    pub fn decode(type_id: i32, buffer: AtomicBuffer, length: Index) -> Self {
+        let command = match AeronCommand::try_from_command_id(type_id) {
+            Some(command) => command,
+            None => return ControlMessage::Unknown { type_id, length },
+        };
+
+        decode_command(command, buffer, length).unwrap_or(ControlMessage::Malformed { command, length })
+    }
+
+    /// Client which sent the message, None for messages of the driver which are addressed to a registration.
+    pub fn client_id(&self) -> Option<i64> {
+        match self {
+            ControlMessage::AddPublication { client_id, .. }
+            | ControlMessage::AddSubscription { client_id, .. }
+            | ControlMessage::Remove { client_id, .. }
+            | ControlMessage::Destination { client_id, .. }
+            | ControlMessage::AddCounter { client_id, .. }
+            | ControlMessage::Client { client_id, .. }
+            | ControlMessage::TerminateDriver { client_id, .. }
+            | ControlMessage::ClientTimeout { client_id } => Some(*client_id),
+            _ => None,
+        }
+    }
+
+    /// Correlation and registration ids the message refers to, to follow a registration from command to response.
+    pub fn correlation_ids(&self) -> Vec<i64> {
+        match self {
+            ControlMessage::AddPublication { correlation_id, .. }
+            | ControlMessage::AddCounter { correlation_id, .. }
+            | ControlMessage::Client { correlation_id, .. }
+            | ControlMessage::TerminateDriver { correlation_id, .. }
+            | ControlMessage::SubscriptionReady { correlation_id, .. }
+            | ControlMessage::OperationSuccess { correlation_id }
+            | ControlMessage::Counter { correlation_id, .. } => vec![*correlation_id],
+            ControlMessage::AddSubscription {
+                correlation_id,
+                registration_correlation_id,
+                ..
+            } => vec![*correlation_id, *registration_correlation_id],
+            ControlMessage::Remove {
+                correlation_id,
+                registration_id,
+                ..
+            }
+            | ControlMessage::Destination {
+                correlation_id,
+                registration_id,
+                ..
+            }
+            | ControlMessage::PublicationReady {
+                correlation_id,
+                registration_id,
+                ..
+            } => vec![*correlation_id, *registration_id],
+            ControlMessage::AvailableImage {
+                correlation_id,
+                subscription_registration_id,
+                ..
+            }
+            | ControlMessage::UnavailableImage {
+                correlation_id,
+                subscription_registration_id,
+                ..
+            } => vec![*correlation_id, *subscription_registration_id],
+            ControlMessage::Error {
+                offending_correlation_id,
+                ..
+            } => vec![*offending_correlation_id],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Name of the event as logged by the driver, e.g. ADD_PUBLICATION or ON_PUBLICATION_READY.
+    pub fn name(&self) -> String {
+        let command = match self {
+            ControlMessage::AddPublication { exclusive: false, .. } => AeronCommand::AddPublication,
+            ControlMessage::AddPublication { exclusive: true, .. } => AeronCommand::AddExclusivePublication,
+            ControlMessage::AddSubscription { .. } => AeronCommand::AddSubscription,
+            ControlMessage::Remove { command, .. }
+            | ControlMessage::Destination { command, .. }
+            | ControlMessage::Client { command, .. }
+            | ControlMessage::Counter { command, .. }
+            | ControlMessage::Malformed { command, .. } => *command,
+            ControlMessage::AddCounter { .. } => AeronCommand::AddCounter,
+            ControlMessage::TerminateDriver { .. } => AeronCommand::TerminateDriver,
+            ControlMessage::Error { .. } => AeronCommand::ResponseOnError,
+            ControlMessage::PublicationReady { exclusive: false, .. } => AeronCommand::ResponseOnPublicationReady,
+            ControlMessage::PublicationReady { exclusive: true, .. } => AeronCommand::ResponseOnExclusivePublicationReady,
+            ControlMessage::SubscriptionReady { .. } => AeronCommand::ResponseOnSubscriptionReady,
+            ControlMessage::AvailableImage { .. } => AeronCommand::ResponseOnAvailableImage,
+            ControlMessage::UnavailableImage { .. } => AeronCommand::ResponseOnUnavailableImage,
+            ControlMessage::OperationSuccess { .. } => AeronCommand::ResponseOnOperationSuccess,
+            ControlMessage::ClientTimeout { .. } => AeronCommand::ResponseOnClientTimeout,
+            ControlMessage::Unknown { type_id, .. } => return format!("UNKNOWN_{:#x}", type_id),
+        };
+
+        command_name(command).to_string()
+    }
+}
+
+impl fmt::Display for ControlMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())?;
+
+        match self {
+            ControlMessage::AddPublication {
+                client_id,
+                correlation_id,
+                stream_id,
+                channel,
+                ..
+            } => write!(
+                f,
+                " client_id={} correlation_id={} stream_id={} channel={}",
+                client_id, correlation_id, stream_id, channel
+            ),
+            ControlMessage::AddSubscription {
+                client_id,
+                correlation_id,
+                registration_correlation_id,
+                stream_id,
+                channel,
+            } => write!(
+                f,
+                " client_id={} correlation_id={} registration_correlation_id={} stream_id={} channel={}",
+                client_id, correlation_id, registration_correlation_id, stream_id, channel
+            ),
+            ControlMessage::Remove {
+                client_id,
+                correlation_id,
+                registration_id,
+                ..
+            } => write!(
+                f,
+                " client_id={} correlation_id={} registration_id={}",
+                client_id, correlation_id, registration_id
+            ),
+            ControlMessage::Destination {
+                client_id,
+                correlation_id,
+                registration_id,
+                channel,
+                ..
+            } => write!(
+                f,
+                " client_id={} correlation_id={} registration_id={} channel={}",
+                client_id, correlation_id, registration_id, channel
+            ),
+            ControlMessage::AddCounter {
+                client_id,
+                correlation_id,
+                type_id,
+                key_length,
+                label,
+            } => write!(
+                f,
+                " client_id={} correlation_id={} type_id={} key_length={} label={}",
+                client_id, correlation_id, type_id, key_length, label
+            ),
+            ControlMessage::Client {
+                client_id,
+                correlation_id,
+                ..
+            } => write!(f, " client_id={} correlation_id={}", client_id, correlation_id),
+            ControlMessage::TerminateDriver {
+                client_id,
+                correlation_id,
+                token_length,
+            } => write!(
+                f,
+                " client_id={} correlation_id={} token_length={}",
+                client_id, correlation_id, token_length
+            ),
+            ControlMessage::Error {
+                offending_correlation_id,
+                error_code,
+                message,
+            } => write!(
+                f,
+                " offending_correlation_id={} error_code={} message={}",
+                offending_correlation_id, error_code, message
+            ),
+            ControlMessage::PublicationReady {
+                correlation_id,
+                registration_id,
+                session_id,
+                stream_id,
+                position_limit_counter_id,
+                channel_status_indicator_id,
+                log_file_name,
+                ..
+            } => write!(
+                f,
+                " correlation_id={} registration_id={} session_id={} stream_id={} position_limit_counter_id={} \
+                 channel_status_indicator_id={} log_file_name={}",
+                correlation_id,
+                registration_id,
+                session_id,
+                stream_id,
+                position_limit_counter_id,
+                channel_status_indicator_id,
+                log_file_name
+            ),
+            ControlMessage::SubscriptionReady {
+                correlation_id,
+                channel_status_indicator_id,
+            } => write!(
+                f,
+                " correlation_id={} channel_status_indicator_id={}",
+                correlation_id, channel_status_indicator_id
+            ),
+            ControlMessage::AvailableImage {
+                correlation_id,
+                session_id,
+                stream_id,
+                subscription_registration_id,
+                subscriber_position_id,
+                log_file_name,
+                source_identity,
+            } => write!(
+                f,
+                " correlation_id={} session_id={} stream_id={} subscription_registration_id={} \
+                 subscriber_position_id={} log_file_name={} source_identity={}",
+                correlation_id,
+                session_id,
+                stream_id,
+                subscription_registration_id,
+                subscriber_position_id,
+                log_file_name,
+                source_identity
+            ),
+            ControlMessage::UnavailableImage {
+                correlation_id,
+                subscription_registration_id,
+                stream_id,
+                channel,
+            } => write!(
+                f,
+                " correlation_id={} subscription_registration_id={} stream_id={} channel={}",
+                correlation_id, subscription_registration_id, stream_id, channel
+            ),
+            ControlMessage::OperationSuccess { correlation_id } => write!(f, " correlation_id={}", correlation_id),
+            ControlMessage::Counter {
+                correlation_id,
+                counter_id,
+                ..
+            } => write!(f, " correlation_id={} counter_id={}", correlation_id, counter_id),
+            ControlMessage::ClientTimeout { client_id } => write!(f, " client_id={}", client_id),
+            ControlMessage::Malformed { length, .. } => write!(f, " malformed length={}", length),
+            ControlMessage::Unknown { length, .. } => write!(f, " length={}", length),
+        }
+    }
+}
+
+/// Event names as the Java driver logs them without the CMD_IN_/CMD_OUT_ prefix.
+pub fn command_name(command: AeronCommand) -> &'static str {
+    match command {
+        AeronCommand::Padding => "PADDING",
+        AeronCommand::AddPublication => "ADD_PUBLICATION",
+        AeronCommand::RemovePublication => "REMOVE_PUBLICATION",
+        AeronCommand::AddExclusivePublication => "ADD_EXCLUSIVE_PUBLICATION",
+        AeronCommand::AddSubscription => "ADD_SUBSCRIPTION",
+        AeronCommand::RemoveSubscription => "REMOVE_SUBSCRIPTION",
+        AeronCommand::ClientKeepAlive => "KEEPALIVE_CLIENT",
+        AeronCommand::AddDestination => "ADD_DESTINATION",
+        AeronCommand::RemoveDestination => "REMOVE_DESTINATION",
+        AeronCommand::AddCounter => "ADD_COUNTER",
+        AeronCommand::RemoveCounter => "REMOVE_COUNTER",
+        AeronCommand::ClientClose => "CLIENT_CLOSE",
+        AeronCommand::AddRcvDestination => "ADD_RCV_DESTINATION",
+        AeronCommand::RemoveRcvDestination => "REMOVE_RCV_DESTINATION",
+        AeronCommand::TerminateDriver => "TERMINATE_DRIVER",
+        AeronCommand::ResponseOnError => "ERROR",
+        AeronCommand::ResponseOnAvailableImage => "AVAILABLE_IMAGE",
+        AeronCommand::ResponseOnPublicationReady => "ON_PUBLICATION_READY",
+        AeronCommand::ResponseOnOperationSuccess => "ON_OPERATION_SUCCESS",
+        AeronCommand::ResponseOnUnavailableImage => "ON_UNAVAILABLE_IMAGE",
+        AeronCommand::ResponseOnExclusivePublicationReady => "EXCLUSIVE_PUBLICATION_READY",
+        AeronCommand::ResponseOnSubscriptionReady => "SUBSCRIPTION_READY",
+        AeronCommand::ResponseOnCounterReady => "COUNTER_READY",
+        AeronCommand::ResponseOnUnavailableCounter => "UNAVAILABLE_COUNTER",
+        AeronCommand::ResponseOnClientTimeout => "CLIENT_TIMEOUT",
+        #[cfg(test)]
+        AeronCommand::UnitTestMessageTypeID => "UNIT_TEST",
+    }
+}
+
+/// Offset after the length prefixed string at the offset, None when it doesn't fit in the message.
+fn string_end(buffer: &AtomicBuffer, offset: Index, length: Index) -> Option<Index> {
+    if offset + I32_SIZE > length {
+        return None;
+    }
+
+    let string_length = buffer.get::<i32>(offset) as Index;
+    if string_length < 0 || offset + I32_SIZE + string_length > length {
+        return None;
+    }
+
+    Some(offset + I32_SIZE + string_length)
+}
+
+fn check_length(required: Index, length: Index) -> Option<()> {
+    if required <= length {
+        Some(())
+    } else {
+        None
+    }
+}
+
+fn decode_command(command: AeronCommand, buffer: AtomicBuffer, length: Index) -> Option<ControlMessage> {
+    let message = match command {
+        AeronCommand::AddPublication | AeronCommand::AddExclusivePublication => {
+            string_end(&buffer, PUBLICATION_MESSAGE_CHANNEL_OFFSET, length)?;
+            let flyweight = PublicationMessageFlyweight::new(buffer, 0);
+            ControlMessage::AddPublication {
+                exclusive: command == AeronCommand::AddExclusivePublication,
+                client_id: flyweight.client_id(),
+                correlation_id: flyweight.correlation_id(),
+                stream_id: flyweight.stream_id(),
+                channel: flyweight.channel().to_string_lossy().into_owned(),
+            }
+        },
+        AeronCommand::AddSubscription => {
+            string_end(&buffer, SUBSCRIPTION_MESSAGE_CHANNEL_OFFSET, length)?;
+            let flyweight = SubscriptionMessageFlyweight::new(buffer, 0);
+            ControlMessage::AddSubscription {
+                client_id: flyweight.client_id(),
+                correlation_id: flyweight.correlation_id(),
+                registration_correlation_id: flyweight.registration_correlation_id(),
+                stream_id: flyweight.stream_id(),
+                channel: flyweight.channel().to_string_lossy().into_owned(),
+            }
+        },
+        AeronCommand::RemovePublication | AeronCommand::RemoveSubscription | AeronCommand::RemoveCounter => {
+            check_length(REMOVE_MESSAGE_LENGTH, length)?;
+            let flyweight = RemoveMessageFlyweight::new(buffer, 0);
+            ControlMessage::Remove {
+                command,
+                client_id: flyweight.client_id(),
+                correlation_id: flyweight.correlation_id(),
+                registration_id: flyweight.registration_id(),
+            }
+        },
+        AeronCommand::AddDestination
+        | AeronCommand::RemoveDestination
+        | AeronCommand::AddRcvDestination
+        | AeronCommand::RemoveRcvDestination => {
+            string_end(&buffer, DESTINATION_MESSAGE_CHANNEL_OFFSET, length)?;
+            let flyweight = DestinationMessageFlyweight::new(buffer, 0);
+            ControlMessage::Destination {
+                command,
+                client_id: flyweight.client_id(),
+                correlation_id: flyweight.correlation_id(),
+                registration_id: flyweight.registration_id(),
+                channel: flyweight.channel().to_string_lossy().into_owned(),
+            }
+        },
+        AeronCommand::AddCounter => {
+            let key_end = string_end(&buffer, COUNTER_MESSAGE_LENGTH, length)?;
+            let label_offset = bit_utils::align(key_end, I32_SIZE);
+            string_end(&buffer, label_offset, length)?;
+            let flyweight = CounterMessageFlyweight::new(buffer, 0);
+            ControlMessage::AddCounter {
+                client_id: flyweight.client_id(),
+                correlation_id: flyweight.correlation_id(),
+                type_id: flyweight.type_id(),
+                key_length: flyweight.key_length(),
+                label: flyweight.label().to_string_lossy().into_owned(),
+            }
+        },
+        AeronCommand::ClientKeepAlive | AeronCommand::ClientClose => {
+            check_length(CORRELATED_MESSAGE_LENGTH, length)?;
+            let flyweight = CorrelatedMessageFlyweight::new(buffer, 0);
+            ControlMessage::Client {
+                command,
+                client_id: flyweight.client_id(),
+                correlation_id: flyweight.correlation_id(),
+            }
+        },
+        AeronCommand::TerminateDriver => {
+            check_length(TERMINATE_DRIVER_LENGTH, length)?;
+            let flyweight = TerminateDriverFlyweight::new(buffer, 0);
+            ControlMessage::TerminateDriver {
+                client_id: flyweight.client_id(),
+                correlation_id: flyweight.correlation_id(),
+                token_length: flyweight.token_length(),
+            }
+        },
+        AeronCommand::ResponseOnError => {
+            string_end(&buffer, ERROR_RESPONSE_MESSAGE_OFFSET, length)?;
+            let flyweight = ErrorResponseFlyweight::new(buffer, 0);
+            ControlMessage::Error {
+                offending_correlation_id: flyweight.offending_command_correlation_id(),
+                error_code: flyweight.error_code(),
+                message: flyweight.error_message().to_string_lossy().into_owned(),
+            }
+        },
+        AeronCommand::ResponseOnPublicationReady | AeronCommand::ResponseOnExclusivePublicationReady => {
+            string_end(&buffer, PUBLICATION_READY_LOG_FILE_OFFSET, length)?;
+            let flyweight = PublicationBuffersReadyFlyweight::new(buffer, 0);
+            ControlMessage::PublicationReady {
+                exclusive: command == AeronCommand::ResponseOnExclusivePublicationReady,
+                correlation_id: flyweight.correlation_id(),
+                registration_id: flyweight.registration_id(),
+                session_id: flyweight.session_id(),
+                stream_id: flyweight.stream_id(),
+                position_limit_counter_id: flyweight.position_limit_counter_id(),
+                channel_status_indicator_id: flyweight.channel_status_indicator_id(),
+                log_file_name: flyweight.log_file_name().to_string_lossy().into_owned(),
+            }
+        },
+        AeronCommand::ResponseOnSubscriptionReady => {
+            check_length(SUBSCRIPTION_READY_LENGTH, length)?;
+            let flyweight = SubscriptionReadyFlyweight::new(buffer, 0);
+            ControlMessage::SubscriptionReady {
+                correlation_id: flyweight.correlation_id(),
+                channel_status_indicator_id: flyweight.channel_status_indicator_id(),
+            }
+        },
+        AeronCommand::ResponseOnAvailableImage => {
+            let log_file_name_end = string_end(&buffer, IMAGE_BUFFERS_READY_LENGTH, length)?;
+            string_end(&buffer, bit_utils::align(log_file_name_end, I32_SIZE), length)?;
+            let flyweight = ImageBuffersReadyFlyweight::new(buffer, 0);
+            ControlMessage::AvailableImage {
+                correlation_id: flyweight.correlation_id(),
+                session_id: flyweight.session_id(),
+                stream_id: flyweight.stream_id(),
+                subscription_registration_id: flyweight.subscription_registration_id(),
+                subscriber_position_id: flyweight.subscriber_position_id(),
+                log_file_name: flyweight.log_file_name().to_string_lossy().into_owned(),
+                source_identity: flyweight.source_identity().to_string_lossy().into_owned(),
+            }
+        },
+        AeronCommand::ResponseOnUnavailableImage => {
+            string_end(&buffer, IMAGE_MESSAGE_CHANNEL_OFFSET, length)?;
+            let flyweight = ImageMessageFlyweight::new(buffer, 0);
+            ControlMessage::UnavailableImage {
+                correlation_id: flyweight.correlation_id(),
+                subscription_registration_id: flyweight.subscription_registration_id(),
+                stream_id: flyweight.stream_id(),
+                channel: flyweight.channel().to_string_lossy().into_owned(),
+            }
+        },
+        AeronCommand::ResponseOnOperationSuccess => {
+            check_length(OPERATION_SUCCEEDED_LENGTH, length)?;
+            let flyweight = OperationSucceededFlyweight::new(buffer, 0);
+            ControlMessage::OperationSuccess {
+                correlation_id: flyweight.correlation_id(),
+            }
+        },
+        AeronCommand::ResponseOnCounterReady | AeronCommand::ResponseOnUnavailableCounter => {
+            check_length(COUNTER_READY_LENGTH, length)?;
+            let flyweight = CounterUpdateFlyweight::new(buffer, 0);
+            ControlMessage::Counter {
+                command,
+                correlation_id: flyweight.correlation_id(),
+                counter_id: flyweight.counter_id(),
+            }
+        },
+        AeronCommand::ResponseOnClientTimeout => {
+            check_length(CLIENT_TIMEOUT_LENGTH, length)?;
+            let flyweight = ClientTimeoutFlyweight::new(buffer, 0);
+            ControlMessage::ClientTimeout {
+                client_id: flyweight.client_id(),
+            }
+        },
+        _ => ControlMessage::Unknown {
+            type_id: command as i32,
+            length,
+        },
+    };
+
+    Some(message)
+}
+
+/// Control message with the time it was read and the buffer it was read from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ControlEvent {
+    pub timestamp_ns: i64,
+    pub direction: ControlDirection,
+    pub message: ControlMessage,
+}
+
+impl fmt::Display for ControlEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}_{}", self.direction, self.message)
+    }
+}
+
+/**
+ * Selects the events of a client or a registration. Responses of the driver carry no client id, they are selected
+ * when they refer to a correlation id seen in a command of the client, so a registration can be followed from the
+ * command to the images and errors of it.
+ */
+#[derive(Debug, Clone, Default)]
+pub struct ControlFilter {
+    client_id: Option<i64>,
+    correlation_id: Option<i64>,
+    client_correlation_ids: HashSet<i64>,
+}
+
+impl ControlFilter {
+    pub fn new(client_id: Option<i64>, correlation_id: Option<i64>) -> Self {
+        Self {
+            client_id,
+            correlation_id,
+            client_correlation_ids: HashSet::new(),
+        }
+    }
+
+    pub fn matches(&mut self, message: &ControlMessage) -> bool {
+        let correlation_ids = message.correlation_ids();
+
+        if let Some(correlation_id) = self.correlation_id {
+            if !correlation_ids.contains(&correlation_id) {
+                return false;
+            }
+        }
+
+        let client_id = match self.client_id {
+            Some(client_id) => client_id,
+            None => return true,
+        };
+
+        let is_match = match message.client_id() {
+            Some(message_client_id) => message_client_id == client_id,
+            None => correlation_ids.iter().any(|id| self.client_correlation_ids.contains(id)),
+        };
+
+        if is_match {
+            // Images get a correlation id of their own which their unavailable image message refers to
+            self.client_correlation_ids.extend(correlation_ids);
+        }
+
+        is_match
+    }
+}
+
+/**
+ * Reads the records of a ManyToOneRingBuffer without consuming them, so the driver still gets every command.
+ *
+ * Reading starts at the tail. Records the consumer zeroed before they could be read are counted as lost, as is a
+ * record the consumer zeroed while it was copied, which is detected by its header changing during the copy.
+ */
+pub struct RingBufferObserver {
+    buffer: AtomicBuffer,
+    capacity: Index,
+    max_msg_length: Index,
+    tail_position_index: Index,
+    head_position_index: Index,
+    position: i64,
+    lost_bytes: i64,
+}
+
+impl RingBufferObserver {
+    pub fn new(buffer: AtomicBuffer) -> Result<Self, AeronError> {
+        let ring_buffer = ManyToOneRingBuffer::new(buffer)?;
+        let capacity = ring_buffer.capacity();
+        let tail_position_index = capacity + ring_buffer::TAIL_POSITION_OFFSET;
+
+        Ok(Self {
+            buffer,
+            capacity,
+            max_msg_length: ring_buffer.max_msg_len(),
+            tail_position_index,
+            head_position_index: capacity + ring_buffer::HEAD_POSITION_OFFSET,
+            position: buffer.get_volatile::<i64>(tail_position_index),
+            lost_bytes: 0,
+        })
+    }
+
+    pub fn max_msg_length(&self) -> Index {
+        self.max_msg_length
+    }
+
+    /// Bytes of records consumed by the driver before they could be read.
+    pub fn lost_bytes(&self) -> i64 {
+        self.lost_bytes
+    }
+
+    /**
+     * Copy the records written since the last read to the scratch buffer and pass them to the handler.
+     *
+     * @param scratch of at least max_msg_length bytes.
+     * @param handler called with the type id and the length of the message in the scratch buffer.
+     * @return number of messages read.
+     */
+    pub fn read<F: FnMut(i32, Index)>(&mut self, scratch: &AtomicBuffer, mut handler: F) -> usize {
+        let mut messages_read = 0;
+
+        loop {
+            let tail = self.buffer.get_volatile::<i64>(self.tail_position_index);
+            if self.position >= tail {
+                break;
+            }
+
+            let head = self.buffer.get_volatile::<i64>(self.head_position_index);
+            if self.position < head {
+                self.lost_bytes += head - self.position;
+                self.position = head;
+                continue;
+            }
+
+            let record_index = (self.position & (self.capacity - 1) as i64) as Index;
+            let header = self.buffer.get_volatile::<i64>(record_index);
+            let record_length = record_descriptor::record_length(header);
+            if record_length <= 0 {
+                // Claimed but not committed yet
+                break;
+            }
+
+            let msg_length = (record_length - record_descriptor::HEADER_LENGTH).min(scratch.capacity());
+            scratch.copy_from(
+                0,
+                &self.buffer,
+                record_descriptor::encoded_msg_offset(record_index),
+                msg_length,
+            );
+
+            if self.buffer.get_volatile::<i64>(record_index) != header {
+                continue;
+            }
+
+            self.position += bit_utils::align(record_length, record_descriptor::ALIGNMENT) as i64;
+
+            let type_id = record_descriptor::message_type_id(header);
+            if type_id != AeronCommand::Padding as i32 {
+                handler(type_id, msg_length);
+                messages_read += 1;
+            }
+        }
+
+        messages_read
+    }
+}
+
+/**
+ * Tails the to-driver ring buffer and the to-clients broadcast buffer of a CnC file without taking part in the
+ * protocol, to see what clients ask the driver and what it answers.
+ */
+pub struct ControlSniffer {
+    to_driver: RingBufferObserver,
+    to_clients: BroadcastReceiver,
+    // record_descriptor::ALIGNMENT longer than needed to copy messages to an aligned offset, see scratch()
+    scratch: Vec<u8>,
+    scratch_length: Index,
+}
+
+impl ControlSniffer {
+    /**
+     * Start reading both buffers at their current end.
+     *
+     * @param to_driver_buffer  of the CnC file, see cnc_file_descriptor::create_to_driver_buffer.
+     * @param to_clients_buffer of the CnC file, see cnc_file_descriptor::create_to_clients_buffer.
+     */
+    pub fn new(to_driver_buffer: AtomicBuffer, to_clients_buffer: AtomicBuffer) -> Result<Self, AeronError> {
+        let to_driver = RingBufferObserver::new(to_driver_buffer)?;
+        let to_clients = BroadcastReceiver::new(to_clients_buffer)?;
+        let scratch_length = to_driver.max_msg_length().max(to_clients.capacity() / 8);
+
+        Ok(Self {
+            to_driver,
+            to_clients,
+            scratch: vec![0; (scratch_length + record_descriptor::ALIGNMENT) as usize],
+            scratch_length,
+        })
+    }
+
+    /// Messages are decoded in place, so they are copied to an offset aligned for the flyweights.
+    fn scratch(&mut self) -> AtomicBuffer {
+        let offset = self.scratch.as_ptr().align_offset(record_descriptor::ALIGNMENT as usize);
+        AtomicBuffer::wrap_slice(&mut self.scratch[offset..offset + self.scratch_length as usize])
+    }
+
+    /// Bytes of commands the driver consumed before they could be read.
+    pub fn lost_to_driver_bytes(&self) -> i64 {
+        self.to_driver.lost_bytes()
+    }
+
+    /// Times the driver overwrote responses before they could be read.
+    pub fn to_clients_lapped_count(&self) -> u64 {
+        self.to_clients.lapped_count()
+    }
+
+    /**
+     * Read the messages written to both buffers since the last poll.
+     *
+     * @param handler called with each decoded event.
+     * @return number of events read.
+     */
+    pub fn poll<F: FnMut(ControlEvent)>(&mut self, mut handler: F) -> usize {
+        let scratch = self.scratch();
+        let timestamp_ns = unix_time_ns() as i64;
+        let mut events = 0;
+
+        events += self.to_driver.read(&scratch, |type_id, length| {
+            handler(ControlEvent {
+                timestamp_ns,
+                direction: ControlDirection::ToDriver,
+                message: ControlMessage::decode(type_id, scratch, length),
+            })
+        });
+
+        while self.to_clients.receive_next() {
+            let type_id = self.to_clients.type_id();
+            let length = (self.to_clients.length() as Index).min(scratch.capacity());
+            scratch.copy_from(0, self.to_clients.buffer(), self.to_clients.offset(), length);
+
+            // The transmitter may have lapped the receiver while the message was copied
+            if !self.to_clients.validate() {
+                continue;
+            }
+
+            handler(ControlEvent {
+                timestamp_ns,
+                direction: ControlDirection::ToClients,
+                message: ControlMessage::decode(type_id, scratch, length),
+            });
+            events += 1;
+        }
+
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::concurrent::atomic_buffer::AlignedBuffer;
+    use crate::concurrent::broadcast::broadcast_buffer_descriptor;
+    use crate::concurrent::broadcast::broadcast_transmitter::BroadcastTransmitter;
+
+    const CAPACITY: Index = 1024;
+
+    struct Buffers {
+        _to_driver: AlignedBuffer,
+        _to_clients: AlignedBuffer,
+        _message: AlignedBuffer,
+        to_driver_buffer: AtomicBuffer,
+        to_clients_buffer: AtomicBuffer,
+        message_buffer: AtomicBuffer,
+    }
+
+    impl Buffers {
+        fn new() -> Self {
+            let to_driver = AlignedBuffer::with_capacity(CAPACITY + ring_buffer::TRAILER_LENGTH);
+            let to_clients = AlignedBuffer::with_capacity(CAPACITY + broadcast_buffer_descriptor::TRAILER_LENGTH);
+            let message = AlignedBuffer::with_capacity(256);
+
+            Self {
+                to_driver_buffer: AtomicBuffer::from_aligned(&to_driver),
+                to_clients_buffer: AtomicBuffer::from_aligned(&to_clients),
+                message_buffer: AtomicBuffer::from_aligned(&message),
+                _to_driver: to_driver,
+                _to_clients: to_clients,
+                _message: message,
+            }
+        }
+
+        fn add_subscription(&self, ring_buffer: &ManyToOneRingBuffer, client_id: i64, correlation_id: i64) {
+            let mut flyweight = SubscriptionMessageFlyweight::new(self.message_buffer, 0);
+            flyweight.set_client_id(client_id);
+            flyweight.set_correlation_id(correlation_id);
+            flyweight.set_registration_correlation_id(-1);
+            flyweight.set_stream_id(1001);
+            flyweight.set_channel(b"aeron:ipc");
+
+            ring_buffer
+                .write(AeronCommand::AddSubscription, self.message_buffer, 0, flyweight.length())
+                .unwrap();
+        }
+
+        fn unavailable_image(&self, transmitter: &mut BroadcastTransmitter, subscription_registration_id: i64) {
+            let mut flyweight = ImageMessageFlyweight::new(self.message_buffer, 0);
+            flyweight.set_correlation_id(42);
+            flyweight.set_subscription_registration_id(subscription_registration_id);
+            flyweight.set_stream_id(1001);
+            flyweight.set_channel(b"aeron:ipc");
+
+            transmitter
+                .transmit(
+                    AeronCommand::ResponseOnUnavailableImage as i32,
+                    &self.message_buffer,
+                    0,
+                    flyweight.length(),
+                )
+                .unwrap();
+        }
+    }
+
+    fn poll_all(sniffer: &mut ControlSniffer) -> Vec<ControlEvent> {
+        let mut events = Vec::new();
+        sniffer.poll(|event| events.push(event));
+        events
+    }
+
+    #[test]
+    fn should_decode_commands_and_responses_without_consuming_commands() {
+        let buffers = Buffers::new();
+        let ring_buffer = ManyToOneRingBuffer::new(buffers.to_driver_buffer).unwrap();
+        let mut transmitter = BroadcastTransmitter::new(buffers.to_clients_buffer).unwrap();
+        let mut sniffer = ControlSniffer::new(buffers.to_driver_buffer, buffers.to_clients_buffer).unwrap();
+
+        buffers.add_subscription(&ring_buffer, 7, 5);
+        buffers.unavailable_image(&mut transmitter, 5);
+
+        let events = poll_all(&mut sniffer);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].direction, ControlDirection::ToDriver);
+        assert_eq!(
+            events[0].to_string(),
+            "CMD_IN_ADD_SUBSCRIPTION client_id=7 correlation_id=5 registration_correlation_id=-1 stream_id=1001 \
+             channel=aeron:ipc"
+        );
+        assert_eq!(events[1].direction, ControlDirection::ToClients);
+        assert_eq!(
+            events[1].message,
+            ControlMessage::UnavailableImage {
+                correlation_id: 42,
+                subscription_registration_id: 5,
+                stream_id: 1001,
+                channel: String::from("aeron:ipc"),
+            }
+        );
+
+        assert!(poll_all(&mut sniffer).is_empty());
+        assert_eq!(ring_buffer.read_all(|_command, _buffer| {}), 1);
+        assert_eq!(sniffer.lost_to_driver_bytes(), 0);
+    }
+
+    #[test]
+    fn should_count_commands_consumed_before_they_were_read() {
+        let buffers = Buffers::new();
+        let ring_buffer = ManyToOneRingBuffer::new(buffers.to_driver_buffer).unwrap();
+        let mut sniffer = ControlSniffer::new(buffers.to_driver_buffer, buffers.to_clients_buffer).unwrap();
+
+        buffers.add_subscription(&ring_buffer, 7, 5);
+        assert_eq!(ring_buffer.read_all(|_command, _buffer| {}), 1);
+
+        assert!(poll_all(&mut sniffer).is_empty());
+        assert_eq!(sniffer.lost_to_driver_bytes(), 56);
+
+        buffers.add_subscription(&ring_buffer, 7, 6);
+        assert_eq!(poll_all(&mut sniffer).len(), 1);
+    }
+
+    #[test]
+    fn should_follow_registrations_of_a_client() {
+        let add_subscription = |client_id, correlation_id| ControlMessage::AddSubscription {
+            client_id,
+            correlation_id,
+            registration_correlation_id: -1,
+            stream_id: 1001,
+            channel: String::from("aeron:ipc"),
+        };
+        let available_image = |correlation_id, subscription_registration_id| ControlMessage::AvailableImage {
+            correlation_id,
+            session_id: 3,
+            stream_id: 1001,
+            subscription_registration_id,
+            subscriber_position_id: 1,
+            log_file_name: String::from("3.logbuffer"),
+            source_identity: String::from("aeron:ipc"),
+        };
+        let unavailable_image = ControlMessage::UnavailableImage {
+            correlation_id: 42,
+            subscription_registration_id: 5,
+            stream_id: 1001,
+            channel: String::from("aeron:ipc"),
+        };
+
+        let mut filter = ControlFilter::new(Some(7), None);
+        assert!(filter.matches(&add_subscription(7, 5)));
+        assert!(!filter.matches(&add_subscription(8, 6)));
+        assert!(filter.matches(&available_image(42, 5)));
+        assert!(!filter.matches(&available_image(43, 6)));
+        assert!(filter.matches(&unavailable_image));
+
+        let mut filter = ControlFilter::new(None, Some(6));
+        assert!(!filter.matches(&add_subscription(7, 5)));
+        assert!(filter.matches(&add_subscription(8, 6)));
+        assert!(filter.matches(&available_image(43, 6)));
+    }
+
+    #[test]
+    fn should_not_read_strings_past_the_message() {
+        let aligned = AlignedBuffer::with_capacity(128);
+        let buffer = AtomicBuffer::from_aligned(&aligned);
+        let mut flyweight = PublicationMessageFlyweight::new(buffer, 0);
+        flyweight.set_client_id(1);
+        flyweight.set_correlation_id(2);
+        flyweight.set_stream_id(1001);
+        flyweight.set_channel(b"aeron:udp?endpoint=localhost:40123");
+        let length = flyweight.length();
+
+        assert_eq!(
+            ControlMessage::decode(AeronCommand::AddPublication as i32, buffer, length - 1),
+            ControlMessage::Malformed {
+                command: AeronCommand::AddPublication,
+                length: length - 1,
+            }
+        );
+        assert_eq!(
+            ControlMessage::decode(0x0F, buffer, length).to_string(),
+            format!("UNKNOWN_0xf length={}", length)
+        );
+        assert_eq!(
+            ControlMessage::decode(AeronCommand::AddExclusivePublication as i32, buffer, length).to_string(),
+            "ADD_EXCLUSIVE_PUBLICATION client_id=1 correlation_id=2 stream_id=1001 \
+             channel=aeron:udp?endpoint=localhost:40123"
+        );
+    }
+}
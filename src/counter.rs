@@ -21,7 +21,8 @@ use std::sync::{Arc, Mutex};
 use crate::client_conductor::ClientConductor;
 use crate::concurrent::atomic_buffer::AtomicBuffer;
 use crate::concurrent::atomic_counter::AtomicCounter;
-use crate::utils::errors::AeronError;
+use crate::concurrent::counters::{MAX_KEY_LENGTH, MAX_LABEL_LENGTH};
+use crate::utils::errors::{AeronError, IllegalArgumentError};
 
 pub struct Counter {
     // inherits from AtomicCounter
@@ -74,6 +75,61 @@ impl Counter {
     pub fn id(&self) -> i32 {
         self.atomic_counter.id()
     }
+
+    /// Inherited from AtomicCounter
+    pub fn increment(&self) {
+        self.atomic_counter.increment()
+    }
+
+    /// Inherited from AtomicCounter
+    pub fn increment_ordered(&self) {
+        self.atomic_counter.increment_ordered()
+    }
+
+    /// Inherited from AtomicCounter
+    pub fn set(&self, value: i64) {
+        self.atomic_counter.set(value)
+    }
+
+    /// Inherited from AtomicCounter
+    pub fn set_ordered(&self, value: i64) {
+        self.atomic_counter.set_ordered(value)
+    }
+
+    /// Inherited from AtomicCounter
+    pub fn set_weak(&self, value: i64) {
+        self.atomic_counter.set_weak(value)
+    }
+
+    /// Inherited from AtomicCounter
+    pub fn get_and_add(&self, value: i64) -> i64 {
+        self.atomic_counter.get_and_add(value)
+    }
+
+    /// Inherited from AtomicCounter
+    pub fn get_and_add_ordered(&self, increment: i64) -> i64 {
+        self.atomic_counter.get_and_add_ordered(increment)
+    }
+
+    /// Inherited from AtomicCounter
+    pub fn get_and_set(&self, value: i64) -> i64 {
+        self.atomic_counter.get_and_set(value)
+    }
+
+    /// Inherited from AtomicCounter
+    pub fn compare_and_set(&self, expected_value: i64, update_value: i64) -> bool {
+        self.atomic_counter.compare_and_set(expected_value, update_value)
+    }
+
+    /// Inherited from AtomicCounter
+    pub fn get(&self) -> i64 {
+        self.atomic_counter.get()
+    }
+
+    /// Inherited from AtomicCounter
+    pub fn get_weak(&self) -> i64 {
+        self.atomic_counter.get_weak()
+    }
 }
 
 impl Drop for Counter {
@@ -85,3 +141,123 @@ impl Drop for Counter {
             .release_counter(self.registration_id);
     }
 }
+
+/**
+ * Builder of the key and label of a counter to be added with Aeron::add_counter.
+ *
+ * Key fields are encoded the same way as the driver encodes keys of its counters: integers in little endian
+ * and strings prefixed with their i32 length, so tools can decode them with AtomicBuffer getters.
+ */
+#[derive(Debug, Default, Clone)]
+pub struct CounterKeyBuilder {
+    key: Vec<u8>,
+    label: String,
+}
+
+impl CounterKeyBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn clear(&mut self) -> &mut Self {
+        self.key.clear();
+        self.label.clear();
+        self
+    }
+
+    pub fn append_i32(&mut self, value: i32) -> Result<&mut Self, AeronError> {
+        self.append_key_bytes(&value.to_le_bytes())
+    }
+
+    pub fn append_i64(&mut self, value: i64) -> Result<&mut Self, AeronError> {
+        self.append_key_bytes(&value.to_le_bytes())
+    }
+
+    pub fn append_string(&mut self, value: &str) -> Result<&mut Self, AeronError> {
+        let mut bytes = (value.len() as i32).to_le_bytes().to_vec();
+        bytes.extend_from_slice(value.as_bytes());
+
+        self.append_key_bytes(&bytes)
+    }
+
+    pub fn append_key_bytes(&mut self, bytes: &[u8]) -> Result<&mut Self, AeronError> {
+        let key_length = self.key.len() + bytes.len();
+
+        if key_length > MAX_KEY_LENGTH as usize {
+            return Err(IllegalArgumentError::KeyLengthIsOutOfBounds {
+                key_length,
+                limit: MAX_KEY_LENGTH,
+            }
+            .into());
+        }
+
+        self.key.extend_from_slice(bytes);
+        Ok(self)
+    }
+
+    /// Append text to the label, e.g. "orders: " then the name of the order book.
+    pub fn append_label(&mut self, text: &str) -> Result<&mut Self, AeronError> {
+        let label_length = self.label.len() + text.len();
+
+        if label_length > MAX_LABEL_LENGTH as usize {
+            return Err(IllegalArgumentError::LabelLengthIsOutOfBounds {
+                label_length,
+                limit: MAX_LABEL_LENGTH,
+            }
+            .into());
+        }
+
+        self.label.push_str(text);
+        Ok(self)
+    }
+
+    pub fn key(&self) -> &[u8] {
+        &self.key
+    }
+
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::concurrent::atomic_buffer::AlignedBuffer;
+    use crate::concurrent::counters::MAX_KEY_LENGTH;
+
+    #[test]
+    fn should_build_key_readable_with_atomic_buffer() {
+        let mut builder = CounterKeyBuilder::new();
+        builder
+            .append_i64(10)
+            .unwrap()
+            .append_i32(1001)
+            .unwrap()
+            .append_string("orders")
+            .unwrap()
+            .append_label("orders: ")
+            .unwrap()
+            .append_label("book-1")
+            .unwrap();
+
+        let aligned = AlignedBuffer::with_capacity(MAX_KEY_LENGTH);
+        let buffer = AtomicBuffer::from_aligned(&aligned);
+        buffer.put_bytes(0, builder.key());
+
+        assert_eq!(buffer.get::<i64>(0), 10);
+        assert_eq!(buffer.get::<i32>(8), 1001);
+        assert_eq!(buffer.get_string(12).to_str().unwrap(), "orders");
+        assert_eq!(builder.label(), "orders: book-1");
+    }
+
+    #[test]
+    fn should_reject_too_long_key_and_label() {
+        let mut builder = CounterKeyBuilder::new();
+
+        assert!(builder.append_key_bytes(&[0; MAX_KEY_LENGTH as usize]).is_ok());
+        assert!(builder.append_i32(1).is_err());
+        assert_eq!(builder.key().len(), MAX_KEY_LENGTH as usize);
+        assert!(builder.append_label(&"x".repeat(MAX_LABEL_LENGTH as usize + 1)).is_err());
+    }
+}
@@ -0,0 +1,611 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::fmt;
+
+use crate::concurrent::atomic_buffer::AtomicBuffer;
+use crate::concurrent::counters::{CountersReader, MAX_KEY_LENGTH};
+use crate::heartbeat_timestamp;
+use crate::utils::types::{Index, I32_SIZE};
+
+/// Counter type id of the driver system counters
+pub const SYSTEM_COUNTER_TYPE_ID: i32 = 0;
+/// Counter type id of a publisher limit (pub-lmt)
+pub const PUBLISHER_LIMIT_TYPE_ID: i32 = 1;
+/// Counter type id of a sender position (snd-pos)
+pub const SENDER_POSITION_TYPE_ID: i32 = 2;
+/// Counter type id of a receiver high water mark (rcv-hwm)
+pub const RECEIVER_HWM_TYPE_ID: i32 = 3;
+/// Counter type id of a subscriber position (sub-pos)
+pub const SUBSCRIBER_POSITION_TYPE_ID: i32 = 4;
+/// Counter type id of a receiver position (rcv-pos)
+pub const RECEIVER_POSITION_TYPE_ID: i32 = 5;
+/// Counter type id of a send channel status (snd-channel)
+pub const SEND_CHANNEL_STATUS_TYPE_ID: i32 = 6;
+/// Counter type id of a receive channel status (rcv-channel)
+pub const RECEIVE_CHANNEL_STATUS_TYPE_ID: i32 = 7;
+/// Counter type id of a sender limit (snd-lmt)
+pub const SENDER_LIMIT_TYPE_ID: i32 = 9;
+/// Counter type id of a client heartbeat timestamp
+pub const CLIENT_HEARTBEAT_TYPE_ID: i32 = heartbeat_timestamp::CLIENT_HEARTBEAT_TYPE_ID;
+/// Counter type id of a publisher position (pub-pos)
+pub const PUBLISHER_POSITION_TYPE_ID: i32 = 12;
+/// Counter type id of sender back pressure events (snd-bpe)
+pub const SENDER_BPE_TYPE_ID: i32 = 13;
+/// Counter type id of a local socket address of a channel endpoint (snd-local-addr, rcv-local-addr)
+pub const LOCAL_SOCKET_ADDRESS_TYPE_ID: i32 = 14;
+
+/**
+ * Stream position counters (publisher limit, sender position, receiver HWM, subscriber position etc) have
+ * the following key layout:
+ * <pre>
+ *   0                   1                   2                   3
+ *   0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+ *  +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+ *  |                        Registration ID                        |
+ *  |                                                               |
+ *  +---------------------------------------------------------------+
+ *  |                           Session ID                          |
+ *  +---------------------------------------------------------------+
+ *  |                           Stream ID                           |
+ *  +---------------------------------------------------------------+
+ *  |                         Channel Length                        |
+ *  +---------------------------------------------------------------+
+ *  |                 Channel in ASCII (truncated)                 ...
+ * ...                                                              |
+ *  +---------------------------------------------------------------+
+ * </pre>
+ */
+pub const STREAM_COUNTER_REGISTRATION_ID_OFFSET: Index = 0;
+pub const STREAM_COUNTER_SESSION_ID_OFFSET: Index = 8;
+pub const STREAM_COUNTER_STREAM_ID_OFFSET: Index = 12;
+pub const STREAM_COUNTER_CHANNEL_OFFSET: Index = 16;
+
+/// Channel status counters have the channel (length prefixed) at the start of the key.
+pub const CHANNEL_STATUS_CHANNEL_OFFSET: Index = 0;
+
+/// Local socket address counters have the channel status counter id followed by the address (length prefixed).
+pub const LOCAL_SOCKET_ADDRESS_STATUS_ID_OFFSET: Index = 0;
+pub const LOCAL_SOCKET_ADDRESS_OFFSET: Index = 4;
+
+/// System counters have the system counter id at the start of the key.
+pub const SYSTEM_COUNTER_ID_OFFSET: Index = 0;
+
+/// Ids of the driver system counters, stored in the key of the counters with SYSTEM_COUNTER_TYPE_ID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(i32)]
+pub enum SystemCounterId {
+    BytesSent = 0,
+    BytesReceived = 1,
+    ReceiverProxyFails = 2,
+    SenderProxyFails = 3,
+    ConductorProxyFails = 4,
+    NakMessagesSent = 5,
+    NakMessagesReceived = 6,
+    StatusMessagesSent = 7,
+    StatusMessagesReceived = 8,
+    HeartbeatsSent = 9,
+    HeartbeatsReceived = 10,
+    RetransmitsSent = 11,
+    FlowControlUnderRuns = 12,
+    FlowControlOverRuns = 13,
+    InvalidPackets = 14,
+    Errors = 15,
+    ShortSends = 16,
+    FreeFails = 17,
+    SenderFlowControlLimits = 18,
+    UnblockedPublications = 19,
+    UnblockedCommands = 20,
+    PossibleTtlAsymmetry = 21,
+    ControllableIdleStrategy = 22,
+    LossGapFills = 23,
+    ClientTimeouts = 24,
+    ResolutionChanges = 25,
+    ConductorMaxCycleTime = 26,
+    ConductorCycleTimeThresholdExceeded = 27,
+    SenderMaxCycleTime = 28,
+    SenderCycleTimeThresholdExceeded = 29,
+    ReceiverMaxCycleTime = 30,
+    ReceiverCycleTimeThresholdExceeded = 31,
+    NameResolverMaxTime = 32,
+    NameResolverTimeThresholdExceeded = 33,
+    AeronVersion = 34,
+    BytesCurrentlyMapped = 35,
+    RetransmittedBytes = 36,
+    RetransmitOverflow = 37,
+}
+
+const SYSTEM_COUNTER_IDS: [SystemCounterId; 38] = [
+    SystemCounterId::BytesSent,
+    SystemCounterId::BytesReceived,
+    SystemCounterId::ReceiverProxyFails,
+    SystemCounterId::SenderProxyFails,
+    SystemCounterId::ConductorProxyFails,
+    SystemCounterId::NakMessagesSent,
+    SystemCounterId::NakMessagesReceived,
+    SystemCounterId::StatusMessagesSent,
+    SystemCounterId::StatusMessagesReceived,
+    SystemCounterId::HeartbeatsSent,
+    SystemCounterId::HeartbeatsReceived,
+    SystemCounterId::RetransmitsSent,
+    SystemCounterId::FlowControlUnderRuns,
+    SystemCounterId::FlowControlOverRuns,
+    SystemCounterId::InvalidPackets,
+    SystemCounterId::Errors,
+    SystemCounterId::ShortSends,
+    SystemCounterId::FreeFails,
+    SystemCounterId::SenderFlowControlLimits,
+    SystemCounterId::UnblockedPublications,
+    SystemCounterId::UnblockedCommands,
+    SystemCounterId::PossibleTtlAsymmetry,
+    SystemCounterId::ControllableIdleStrategy,
+    SystemCounterId::LossGapFills,
+    SystemCounterId::ClientTimeouts,
+    SystemCounterId::ResolutionChanges,
+    SystemCounterId::ConductorMaxCycleTime,
+    SystemCounterId::ConductorCycleTimeThresholdExceeded,
+    SystemCounterId::SenderMaxCycleTime,
+    SystemCounterId::SenderCycleTimeThresholdExceeded,
+    SystemCounterId::ReceiverMaxCycleTime,
+    SystemCounterId::ReceiverCycleTimeThresholdExceeded,
+    SystemCounterId::NameResolverMaxTime,
+    SystemCounterId::NameResolverTimeThresholdExceeded,
+    SystemCounterId::AeronVersion,
+    SystemCounterId::BytesCurrentlyMapped,
+    SystemCounterId::RetransmittedBytes,
+    SystemCounterId::RetransmitOverflow,
+];
+
+impl SystemCounterId {
+    pub fn from_id(id: i32) -> Option<Self> {
+        if id >= 0 && (id as usize) < SYSTEM_COUNTER_IDS.len() {
+            Some(SYSTEM_COUNTER_IDS[id as usize])
+        } else {
+            None
+        }
+    }
+
+    pub fn id(self) -> i32 {
+        self as i32
+    }
+
+    /// Label used by the driver for the counter.
+    pub fn label(self) -> &'static str {
+        match self {
+            SystemCounterId::BytesSent => "Bytes sent",
+            SystemCounterId::BytesReceived => "Bytes received",
+            SystemCounterId::ReceiverProxyFails => "Failed offers to ReceiverProxy",
+            SystemCounterId::SenderProxyFails => "Failed offers to SenderProxy",
+            SystemCounterId::ConductorProxyFails => "Failed offers to DriverConductorProxy",
+            SystemCounterId::NakMessagesSent => "NAKs sent",
+            SystemCounterId::NakMessagesReceived => "NAKs received",
+            SystemCounterId::StatusMessagesSent => "Status Messages sent",
+            SystemCounterId::StatusMessagesReceived => "Status Messages received",
+            SystemCounterId::HeartbeatsSent => "Heartbeats sent",
+            SystemCounterId::HeartbeatsReceived => "Heartbeats received",
+            SystemCounterId::RetransmitsSent => "Retransmits sent",
+            SystemCounterId::FlowControlUnderRuns => "Flow control under runs",
+            SystemCounterId::FlowControlOverRuns => "Flow control over runs",
+            SystemCounterId::InvalidPackets => "Invalid packets",
+            SystemCounterId::Errors => "Errors",
+            SystemCounterId::ShortSends => "Short sends",
+            SystemCounterId::FreeFails => "Failed attempts to free log buffers",
+            SystemCounterId::SenderFlowControlLimits => "Sender flow control limits, i.e. back-pressure events",
+            SystemCounterId::UnblockedPublications => "Unblocked Publications",
+            SystemCounterId::UnblockedCommands => "Unblocked Control Commands",
+            SystemCounterId::PossibleTtlAsymmetry => "Possible TTL Asymmetry",
+            SystemCounterId::ControllableIdleStrategy => "ControllableIdleStrategy status",
+            SystemCounterId::LossGapFills => "Loss gap fills",
+            SystemCounterId::ClientTimeouts => "Client liveness timeouts",
+            SystemCounterId::ResolutionChanges => "Resolution changes",
+            SystemCounterId::ConductorMaxCycleTime => "Conductor max cycle time doing its work in ns",
+            SystemCounterId::ConductorCycleTimeThresholdExceeded => "Conductor work cycle exceeded threshold count",
+            SystemCounterId::SenderMaxCycleTime => "Sender max cycle time doing its work in ns",
+            SystemCounterId::SenderCycleTimeThresholdExceeded => "Sender work cycle exceeded threshold count",
+            SystemCounterId::ReceiverMaxCycleTime => "Receiver max cycle time doing its work in ns",
+            SystemCounterId::ReceiverCycleTimeThresholdExceeded => "Receiver work cycle exceeded threshold count",
+            SystemCounterId::NameResolverMaxTime => "Name resolver max time in ns",
+            SystemCounterId::NameResolverTimeThresholdExceeded => "Name resolver exceeded threshold count",
+            SystemCounterId::AeronVersion => "Aeron software: version",
+            SystemCounterId::BytesCurrentlyMapped => "Bytes currently mapped",
+            SystemCounterId::RetransmittedBytes => "Retransmitted bytes",
+            SystemCounterId::RetransmitOverflow => "Retransmit Pool Overflow count",
+        }
+    }
+}
+
+impl fmt::Display for SystemCounterId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+/// Decoded key of a stream position counter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamCounterKey {
+    pub registration_id: i64,
+    pub session_id: i32,
+    pub stream_id: i32,
+    /// Channel of the stream, may be truncated to fit into the key.
+    pub channel: String,
+}
+
+impl StreamCounterKey {
+    pub fn decode(key_buffer: &AtomicBuffer) -> Self {
+        Self {
+            registration_id: key_buffer.get::<i64>(STREAM_COUNTER_REGISTRATION_ID_OFFSET),
+            session_id: key_buffer.get::<i32>(STREAM_COUNTER_SESSION_ID_OFFSET),
+            stream_id: key_buffer.get::<i32>(STREAM_COUNTER_STREAM_ID_OFFSET),
+            channel: decode_key_string(key_buffer, STREAM_COUNTER_CHANNEL_OFFSET),
+        }
+    }
+}
+
+/// Reads length prefixed ASCII string from the key clamping its length to the key capacity.
+fn decode_key_string(key_buffer: &AtomicBuffer, offset: Index) -> String {
+    let capacity = key_buffer.capacity().min(MAX_KEY_LENGTH);
+    let max_length = capacity - offset - I32_SIZE;
+    let length = key_buffer.get::<i32>(offset).clamp(0, max_length);
+
+    key_buffer
+        .get_string_without_length(offset + I32_SIZE, length)
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Counter allocated by the driver decoded from its type id and key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DriverCounter {
+    /// System counter. `counter` is None for ids unknown to this version of the client.
+    System {
+        id: i32,
+        counter: Option<SystemCounterId>,
+    },
+    PublisherLimit(StreamCounterKey),
+    SenderPosition(StreamCounterKey),
+    ReceiverHwm(StreamCounterKey),
+    SubscriberPosition(StreamCounterKey),
+    ReceiverPosition(StreamCounterKey),
+    SenderLimit(StreamCounterKey),
+    PublisherPosition(StreamCounterKey),
+    SenderBpe(StreamCounterKey),
+    SendChannelStatus {
+        channel: String,
+    },
+    ReceiveChannelStatus {
+        channel: String,
+    },
+    LocalSocketAddress {
+        channel_status_id: i32,
+        address: String,
+    },
+    ClientHeartbeat {
+        client_id: i64,
+    },
+    /// Counter of a type not known to the driver model, e.g. allocated by an application.
+    Other {
+        type_id: i32,
+    },
+}
+
+impl DriverCounter {
+    /**
+     * Decode the counter from its type id and key as passed by CountersReader::for_each.
+     *
+     * @param type_id    of the counter.
+     * @param key_buffer of the counter metadata record.
+     * @return decoded counter.
+     */
+    pub fn decode(type_id: i32, key_buffer: &AtomicBuffer) -> Self {
+        match type_id {
+            SYSTEM_COUNTER_TYPE_ID => {
+                let id = key_buffer.get::<i32>(SYSTEM_COUNTER_ID_OFFSET);
+                DriverCounter::System {
+                    id,
+                    counter: SystemCounterId::from_id(id),
+                }
+            },
+            PUBLISHER_LIMIT_TYPE_ID => DriverCounter::PublisherLimit(StreamCounterKey::decode(key_buffer)),
+            SENDER_POSITION_TYPE_ID => DriverCounter::SenderPosition(StreamCounterKey::decode(key_buffer)),
+            RECEIVER_HWM_TYPE_ID => DriverCounter::ReceiverHwm(StreamCounterKey::decode(key_buffer)),
+            SUBSCRIBER_POSITION_TYPE_ID => DriverCounter::SubscriberPosition(StreamCounterKey::decode(key_buffer)),
+            RECEIVER_POSITION_TYPE_ID => DriverCounter::ReceiverPosition(StreamCounterKey::decode(key_buffer)),
+            SENDER_LIMIT_TYPE_ID => DriverCounter::SenderLimit(StreamCounterKey::decode(key_buffer)),
+            PUBLISHER_POSITION_TYPE_ID => DriverCounter::PublisherPosition(StreamCounterKey::decode(key_buffer)),
+            SENDER_BPE_TYPE_ID => DriverCounter::SenderBpe(StreamCounterKey::decode(key_buffer)),
+            SEND_CHANNEL_STATUS_TYPE_ID => DriverCounter::SendChannelStatus {
+                channel: decode_key_string(key_buffer, CHANNEL_STATUS_CHANNEL_OFFSET),
+            },
+            RECEIVE_CHANNEL_STATUS_TYPE_ID => DriverCounter::ReceiveChannelStatus {
+                channel: decode_key_string(key_buffer, CHANNEL_STATUS_CHANNEL_OFFSET),
+            },
+            LOCAL_SOCKET_ADDRESS_TYPE_ID => DriverCounter::LocalSocketAddress {
+                channel_status_id: key_buffer.get::<i32>(LOCAL_SOCKET_ADDRESS_STATUS_ID_OFFSET),
+                address: decode_key_string(key_buffer, LOCAL_SOCKET_ADDRESS_OFFSET),
+            },
+            CLIENT_HEARTBEAT_TYPE_ID => DriverCounter::ClientHeartbeat {
+                client_id: key_buffer.get::<i64>(0),
+            },
+            _ => DriverCounter::Other { type_id },
+        }
+    }
+
+    pub fn type_id(&self) -> i32 {
+        match self {
+            DriverCounter::System { .. } => SYSTEM_COUNTER_TYPE_ID,
+            DriverCounter::PublisherLimit(_) => PUBLISHER_LIMIT_TYPE_ID,
+            DriverCounter::SenderPosition(_) => SENDER_POSITION_TYPE_ID,
+            DriverCounter::ReceiverHwm(_) => RECEIVER_HWM_TYPE_ID,
+            DriverCounter::SubscriberPosition(_) => SUBSCRIBER_POSITION_TYPE_ID,
+            DriverCounter::ReceiverPosition(_) => RECEIVER_POSITION_TYPE_ID,
+            DriverCounter::SenderLimit(_) => SENDER_LIMIT_TYPE_ID,
+            DriverCounter::PublisherPosition(_) => PUBLISHER_POSITION_TYPE_ID,
+            DriverCounter::SenderBpe(_) => SENDER_BPE_TYPE_ID,
+            DriverCounter::SendChannelStatus { .. } => SEND_CHANNEL_STATUS_TYPE_ID,
+            DriverCounter::ReceiveChannelStatus { .. } => RECEIVE_CHANNEL_STATUS_TYPE_ID,
+            DriverCounter::LocalSocketAddress { .. } => LOCAL_SOCKET_ADDRESS_TYPE_ID,
+            DriverCounter::ClientHeartbeat { .. } => CLIENT_HEARTBEAT_TYPE_ID,
+            DriverCounter::Other { type_id } => *type_id,
+        }
+    }
+
+    /// Short name of the counter type as used in the driver labels, e.g. "pub-lmt".
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            DriverCounter::System { .. } => "system",
+            DriverCounter::PublisherLimit(_) => "pub-lmt",
+            DriverCounter::SenderPosition(_) => "snd-pos",
+            DriverCounter::ReceiverHwm(_) => "rcv-hwm",
+            DriverCounter::SubscriberPosition(_) => "sub-pos",
+            DriverCounter::ReceiverPosition(_) => "rcv-pos",
+            DriverCounter::SenderLimit(_) => "snd-lmt",
+            DriverCounter::PublisherPosition(_) => "pub-pos",
+            DriverCounter::SenderBpe(_) => "snd-bpe",
+            DriverCounter::SendChannelStatus { .. } => "snd-channel",
+            DriverCounter::ReceiveChannelStatus { .. } => "rcv-channel",
+            DriverCounter::LocalSocketAddress { .. } => "local-sockaddr",
+            DriverCounter::ClientHeartbeat { .. } => "client-heartbeat",
+            DriverCounter::Other { .. } => "other",
+        }
+    }
+
+    /// Key of the stream position counters, None for other counters.
+    pub fn stream_key(&self) -> Option<&StreamCounterKey> {
+        match self {
+            DriverCounter::PublisherLimit(key)
+            | DriverCounter::SenderPosition(key)
+            | DriverCounter::ReceiverHwm(key)
+            | DriverCounter::SubscriberPosition(key)
+            | DriverCounter::ReceiverPosition(key)
+            | DriverCounter::SenderLimit(key)
+            | DriverCounter::PublisherPosition(key)
+            | DriverCounter::SenderBpe(key) => Some(key),
+            _ => None,
+        }
+    }
+
+    /// Channel of stream position and channel status counters.
+    pub fn channel(&self) -> Option<&str> {
+        match self {
+            DriverCounter::SendChannelStatus { channel } | DriverCounter::ReceiveChannelStatus { channel } => Some(channel),
+            _ => self.stream_key().map(|key| key.channel.as_str()),
+        }
+    }
+
+    /// Registration id the counter is keyed by: stream registration id or client id for heartbeats.
+    pub fn registration_id(&self) -> Option<i64> {
+        match self {
+            DriverCounter::ClientHeartbeat { client_id } => Some(*client_id),
+            _ => self.stream_key().map(|key| key.registration_id),
+        }
+    }
+}
+
+/**
+ * Decode all allocated counters.
+ *
+ * @param counters_reader to read the counters from.
+ * @return (counter id, decoded counter, label) of each allocated counter.
+ */
+pub fn read_all(counters_reader: &CountersReader) -> Vec<(i32, DriverCounter, String)> {
+    let mut counters = Vec::new();
+
+    counters_reader.for_each(|counter_id, type_id, key_buffer, label| {
+        counters.push((
+            counter_id,
+            DriverCounter::decode(type_id, key_buffer),
+            label.to_string_lossy().into_owned(),
+        ));
+    });
+
+    counters
+}
+
+/**
+ * Find the active counter id of a stream position counter or a client heartbeat by its type id and
+ * registration id.
+ *
+ * @param counters_reader to search within.
+ * @param type_id         of the counter.
+ * @param registration_id the counter key starts with.
+ * @return the counter id if found otherwise None.
+ */
+pub fn find_by_type_and_registration_id(counters_reader: &CountersReader, type_id: i32, registration_id: i64) -> Option<i32> {
+    heartbeat_timestamp::find_counter_id_by_registration_id(counters_reader, type_id, registration_id)
+}
+
+/**
+ * Find the active stream position counter by its type id, registration id and session id. Useful for the per
+ * image counters (e.g. subscriber positions) which share registration id of the subscription.
+ *
+ * @return the counter id if found otherwise None.
+ */
+pub fn find_by_type_registration_and_session_id(
+    counters_reader: &CountersReader,
+    type_id: i32,
+    registration_id: i64,
+    session_id: i32,
+) -> Option<i32> {
+    let mut found = None;
+
+    counters_reader.for_each(|counter_id, counter_type_id, key_buffer, _label| {
+        if found.is_none()
+            && counter_type_id == type_id
+            && key_buffer.get::<i64>(STREAM_COUNTER_REGISTRATION_ID_OFFSET) == registration_id
+            && key_buffer.get::<i32>(STREAM_COUNTER_SESSION_ID_OFFSET) == session_id
+        {
+            found = Some(counter_id);
+        }
+    });
+
+    found
+}
+
+/**
+ * Find the counter id of a driver system counter.
+ *
+ * @return the counter id if found otherwise None.
+ */
+pub fn find_system_counter(counters_reader: &CountersReader, system_counter: SystemCounterId) -> Option<i32> {
+    let mut found = None;
+
+    counters_reader.for_each(|counter_id, type_id, key_buffer, _label| {
+        if found.is_none()
+            && type_id == SYSTEM_COUNTER_TYPE_ID
+            && key_buffer.get::<i32>(SYSTEM_COUNTER_ID_OFFSET) == system_counter.id()
+        {
+            found = Some(counter_id);
+        }
+    });
+
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::concurrent::counters::CountersFixture;
+
+    const NUM_COUNTERS: i32 = 8;
+    const CHANNEL: &str = "aeron:udp?endpoint=localhost:40123";
+
+    fn allocate(fixture: &mut CountersFixture, type_id: i32, key_func: impl Fn(&mut AtomicBuffer), label: &str) -> i32 {
+        fixture.manager.allocate_opt(type_id, None, Some(key_func), label).unwrap()
+    }
+
+    fn add_stream_counter(fixture: &mut CountersFixture, type_id: i32, registration_id: i64, session_id: i32) -> i32 {
+        allocate(
+            fixture,
+            type_id,
+            |key_buffer: &mut AtomicBuffer| {
+                key_buffer.put::<i64>(STREAM_COUNTER_REGISTRATION_ID_OFFSET, registration_id);
+                key_buffer.put::<i32>(STREAM_COUNTER_SESSION_ID_OFFSET, session_id);
+                key_buffer.put::<i32>(STREAM_COUNTER_STREAM_ID_OFFSET, 1001);
+                key_buffer.put_string(STREAM_COUNTER_CHANNEL_OFFSET, CHANNEL.as_bytes());
+            },
+            "stream counter",
+        )
+    }
+
+    #[test]
+    fn should_decode_stream_channel_status_and_system_counters() {
+        let mut fixture = CountersFixture::new(NUM_COUNTERS);
+        add_stream_counter(&mut fixture, SUBSCRIBER_POSITION_TYPE_ID, 10, 77);
+        allocate(
+            &mut fixture,
+            RECEIVE_CHANNEL_STATUS_TYPE_ID,
+            |key_buffer: &mut AtomicBuffer| key_buffer.put_string(CHANNEL_STATUS_CHANNEL_OFFSET, CHANNEL.as_bytes()),
+            "rcv-channel",
+        );
+        allocate(
+            &mut fixture,
+            SYSTEM_COUNTER_TYPE_ID,
+            |key_buffer: &mut AtomicBuffer| key_buffer.put::<i32>(SYSTEM_COUNTER_ID_OFFSET, 6),
+            "NAKs received",
+        );
+        allocate(&mut fixture, 1000, |_key_buffer: &mut AtomicBuffer| {}, "application counter");
+
+        let counters = read_all(&fixture.reader());
+
+        assert_eq!(counters.len(), 4);
+        assert_eq!(
+            counters[0].1,
+            DriverCounter::SubscriberPosition(StreamCounterKey {
+                registration_id: 10,
+                session_id: 77,
+                stream_id: 1001,
+                channel: String::from(CHANNEL),
+            })
+        );
+        assert_eq!(counters[0].1.type_name(), "sub-pos");
+        assert_eq!(counters[1].1.channel(), Some(CHANNEL));
+        assert_eq!(counters[2].1, DriverCounter::System {
+            id: 6,
+            counter: Some(SystemCounterId::NakMessagesReceived)
+        });
+        assert_eq!(counters[3].1, DriverCounter::Other { type_id: 1000 });
+        assert_eq!(counters[3].2, "application counter");
+    }
+
+    #[test]
+    fn should_clamp_corrupted_channel_length() {
+        let mut fixture = CountersFixture::new(NUM_COUNTERS);
+        allocate(
+            &mut fixture,
+            SEND_CHANNEL_STATUS_TYPE_ID,
+            |key_buffer: &mut AtomicBuffer| key_buffer.put::<i32>(CHANNEL_STATUS_CHANNEL_OFFSET, i32::MAX),
+            "snd-channel",
+        );
+
+        let counters = read_all(&fixture.reader());
+
+        assert_eq!(
+            counters[0].1.channel().map(str::len),
+            Some((MAX_KEY_LENGTH - I32_SIZE) as usize)
+        );
+    }
+
+    #[test]
+    fn should_find_counters_by_type_registration_and_session_id() {
+        let mut fixture = CountersFixture::new(NUM_COUNTERS);
+        let pub_limit_id = add_stream_counter(&mut fixture, PUBLISHER_LIMIT_TYPE_ID, 10, 77);
+        add_stream_counter(&mut fixture, SUBSCRIBER_POSITION_TYPE_ID, 11, 77);
+        let second_image_id = add_stream_counter(&mut fixture, SUBSCRIBER_POSITION_TYPE_ID, 11, 78);
+        let naks_id = allocate(
+            &mut fixture,
+            SYSTEM_COUNTER_TYPE_ID,
+            |key_buffer: &mut AtomicBuffer| key_buffer.put::<i32>(SYSTEM_COUNTER_ID_OFFSET, 6),
+            "NAKs received",
+        );
+
+        let reader = fixture.reader();
+
+        assert_eq!(
+            find_by_type_and_registration_id(&reader, PUBLISHER_LIMIT_TYPE_ID, 10),
+            Some(pub_limit_id)
+        );
+        assert_eq!(find_by_type_and_registration_id(&reader, SENDER_POSITION_TYPE_ID, 10), None);
+        assert_eq!(
+            find_by_type_registration_and_session_id(&reader, SUBSCRIBER_POSITION_TYPE_ID, 11, 78),
+            Some(second_image_id)
+        );
+        assert_eq!(
+            find_system_counter(&reader, SystemCounterId::NakMessagesReceived),
+            Some(naks_id)
+        );
+        assert_eq!(find_system_counter(&reader, SystemCounterId::Errors), None);
+    }
+}
@@ -24,13 +24,18 @@ use crate::command::destination_message_flyweight::DestinationMessageFlyweight;
 use crate::command::publication_message_flyweight::PublicationMessageFlyweight;
 use crate::command::remove_message_flyweight::RemoveMessageFlyweight;
 use crate::command::subscription_message_flyweight::SubscriptionMessageFlyweight;
-use crate::command::terminate_driver_flyweight::TerminateDriverFlyweight;
+use crate::command::terminate_driver_flyweight::{TerminateDriverFlyweight, TERMINATE_DRIVER_LENGTH};
 use crate::concurrent::atomic_buffer::AtomicBuffer;
 use crate::concurrent::ring_buffer::ManyToOneRingBuffer;
 use crate::log;
 use crate::utils::errors::{AeronError, IllegalStateError};
 use crate::utils::types::Index;
 
+const COMMAND_BUFFER_LENGTH: usize = 512;
+
+/// Longest termination validation token which fits in the terminate driver command.
+pub const MAX_TERMINATION_TOKEN_LENGTH: Index = COMMAND_BUFFER_LENGTH as Index - TERMINATE_DRIVER_LENGTH;
+
 pub struct DriverProxy {
     to_driver_command_buffer: Arc<ManyToOneRingBuffer>,
     client_id: i64,
@@ -334,13 +339,13 @@ impl DriverProxy {
 /// the buffer (may be) faster it could be aligned to CACHE_LINE_LENGTH (64 bytes for modern x86 CPUs)
 #[repr(C, align(16))]
 struct DriverProxyCommandBuffer {
-    data: [u8; 512],
+    data: [u8; COMMAND_BUFFER_LENGTH],
 }
 
 impl Default for DriverProxyCommandBuffer {
     fn default() -> Self {
         Self {
-            data: [0; 512], // zero the memory
+            data: [0; COMMAND_BUFFER_LENGTH], // zero the memory
         }
     }
 }
@@ -306,6 +306,18 @@ impl ExclusivePublication {
         }
     }
 
+    /**
+     * Get the current position together with the initial term id and term length of the stream, which is what
+     * ChannelUriStringBuilder::initial_position needs to create a publication continuing the stream, e.g. after
+     * a restart.
+     *
+     * @return (position, initial_term_id, term_length) or error if the publication is closed.
+     */
+    #[inline]
+    pub fn resume_position(&self) -> Result<(i64, i32, i32), AeronError> {
+        Ok((self.position()?, self.initial_term_id, self.term_buffer_length()))
+    }
+
     /**
      * Get the position limit beyond which this {@link Publication} will be back pressured.
      *
@@ -639,6 +651,7 @@ mod tests {
 
     use lazy_static::lazy_static;
 
+    use crate::channel_status_registry::ErroredChannel;
     use crate::client_conductor::ClientConductor;
     use crate::concurrent::atomic_buffer::{AlignedBuffer, AtomicBuffer};
     use crate::concurrent::broadcast::broadcast_buffer_descriptor;
@@ -709,6 +722,8 @@ mod tests {
 
     fn on_close_client_handler() {}
 
+    fn on_channel_errored_handler(_errored_channel: &ErroredChannel) {}
+
     #[allow(dead_code)]
     struct ExclusivePublicationTest {
         src: AlignedBuffer,
@@ -775,6 +790,7 @@ mod tests {
                 Box::new(on_available_counter_handler),
                 Box::new(on_unavailable_counter_handler),
                 Box::new(on_close_client_handler),
+                Box::new(on_channel_errored_handler),
                 DRIVER_TIMEOUT_MS,
                 RESOURCE_LINGER_TIMEOUT_MS,
                 INTER_SERVICE_TIMEOUT_MS,
@@ -854,6 +870,15 @@ mod tests {
         assert_eq!(position.unwrap(), 0);
     }
 
+    #[test]
+    fn should_report_resume_position() {
+        let test = ExclusivePublicationTest::new();
+        assert_eq!(test.publication.resume_position().unwrap(), (0, TERM_ID_1, TERM_MIN_LENGTH));
+
+        test.publication.close();
+        assert!(test.publication.resume_position().is_err());
+    }
+
     #[test]
     fn should_report_max_message_length() {
         let test = ExclusivePublicationTest::new();
@@ -0,0 +1,326 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::channel_uri::{self, ChannelUri};
+use crate::utils::errors::AeronError;
+
+pub const MAX_FLOW_CONTROL: &str = "max";
+pub const MIN_FLOW_CONTROL: &str = "min";
+pub const TAGGED_FLOW_CONTROL: &str = "tagged";
+
+pub const STATIC_CONGESTION_CONTROL: &str = "static";
+pub const CUBIC_CONGESTION_CONTROL: &str = "cubic";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FlowControlStrategy {
+    /// Sender is limited by the fastest receiver.
+    Max,
+    /// Sender is limited by the slowest receiver.
+    Min,
+    /// Sender is limited by the slowest receiver of the group with matching tag.
+    Tagged,
+}
+
+impl FlowControlStrategy {
+    pub fn name(&self) -> &'static str {
+        match self {
+            FlowControlStrategy::Max => MAX_FLOW_CONTROL,
+            FlowControlStrategy::Min => MIN_FLOW_CONTROL,
+            FlowControlStrategy::Tagged => TAGGED_FLOW_CONTROL,
+        }
+    }
+}
+
+/**
+ * Value of the fc parameter of multicast and multi destination publications, e.g. "min,t:5s" or
+ * "tagged,g:101/3,t:1s".
+ *
+ * Group minimum size is supported by min and tagged strategies, group tag only by tagged strategy and
+ * receiver timeout by both of them. Max strategy has no options.
+ */
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FlowControl {
+    strategy: FlowControlStrategy,
+    group_tag: Option<i64>,
+    group_min_size: Option<i32>,
+    timeout: Option<Duration>,
+}
+
+impl FlowControl {
+    pub fn max() -> Self {
+        Self::new(FlowControlStrategy::Max)
+    }
+
+    pub fn min() -> Self {
+        Self::new(FlowControlStrategy::Min)
+    }
+
+    pub fn tagged() -> Self {
+        Self::new(FlowControlStrategy::Tagged)
+    }
+
+    fn new(strategy: FlowControlStrategy) -> Self {
+        Self {
+            strategy,
+            group_tag: None,
+            group_min_size: None,
+            timeout: None,
+        }
+    }
+
+    pub fn strategy(&self) -> FlowControlStrategy {
+        self.strategy
+    }
+
+    pub fn group_tag(&self) -> Option<i64> {
+        self.group_tag
+    }
+
+    pub fn group_min_size(&self) -> Option<i32> {
+        self.group_min_size
+    }
+
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Tag of the receivers which make the group, only for tagged strategy.
+    pub fn with_group_tag(&mut self, group_tag: i64) -> Result<&mut Self, AeronError> {
+        self.set_group_tag(group_tag).map_err(|reason| self.invalid(reason))?;
+        Ok(self)
+    }
+
+    /// Number of receivers required before the publication is connected, not for max strategy.
+    pub fn with_group_min_size(&mut self, group_min_size: i32) -> Result<&mut Self, AeronError> {
+        self.set_group_min_size(group_min_size)
+            .map_err(|reason| self.invalid(reason))?;
+        Ok(self)
+    }
+
+    /// Time after which a receiver which stopped sending status messages is dropped, not for max strategy.
+    pub fn with_timeout(&mut self, timeout: Duration) -> Result<&mut Self, AeronError> {
+        self.set_timeout(timeout).map_err(|reason| self.invalid(reason))?;
+        Ok(self)
+    }
+
+    fn set_group_tag(&mut self, group_tag: i64) -> Result<(), &'static str> {
+        if self.strategy != FlowControlStrategy::Tagged {
+            return Err("group tag is only supported by tagged flow control");
+        }
+
+        self.group_tag = Some(group_tag);
+        Ok(())
+    }
+
+    fn set_group_min_size(&mut self, group_min_size: i32) -> Result<(), &'static str> {
+        if self.strategy == FlowControlStrategy::Max {
+            return Err("group minimum size is not supported by max flow control");
+        }
+
+        if group_min_size < 0 {
+            return Err("group minimum size must not be negative");
+        }
+
+        self.group_min_size = Some(group_min_size);
+        Ok(())
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) -> Result<(), &'static str> {
+        if self.strategy == FlowControlStrategy::Max {
+            return Err("timeout is not supported by max flow control");
+        }
+
+        if timeout.as_nanos() == 0 || timeout.as_nanos() > i64::MAX as u128 {
+            return Err("timeout must be positive and fit into i64 ns");
+        }
+
+        self.timeout = Some(timeout);
+        Ok(())
+    }
+
+    fn invalid(&self, reason: &str) -> AeronError {
+        ChannelUri::invalid_param(channel_uri::FLOW_CONTROL_PARAM_NAME, &self.to_string(), reason)
+    }
+}
+
+/// Render the duration in the largest unit which keeps it exact, e.g. 5s or 250ms.
+fn format_duration(duration: Duration) -> String {
+    let nanos = duration.as_nanos();
+
+    if nanos % 1_000_000_000 == 0 {
+        format!("{}s", nanos / 1_000_000_000)
+    } else if nanos % 1_000_000 == 0 {
+        format!("{}ms", nanos / 1_000_000)
+    } else if nanos % 1_000 == 0 {
+        format!("{}us", nanos / 1_000)
+    } else {
+        format!("{}ns", nanos)
+    }
+}
+
+impl Display for FlowControl {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.strategy.name())?;
+
+        if self.group_tag.is_some() || self.group_min_size.is_some() {
+            write!(f, ",g:")?;
+            if let Some(group_tag) = self.group_tag {
+                write!(f, "{}", group_tag)?;
+            }
+            if let Some(group_min_size) = self.group_min_size {
+                write!(f, "/{}", group_min_size)?;
+            }
+        }
+
+        if let Some(timeout) = self.timeout {
+            write!(f, ",t:{}", format_duration(timeout))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for FlowControl {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut parts = value.split(',');
+        let mut flow_control = match parts.next() {
+            Some(MAX_FLOW_CONTROL) => Self::max(),
+            Some(MIN_FLOW_CONTROL) => Self::min(),
+            Some(TAGGED_FLOW_CONTROL) => Self::tagged(),
+            _ => return Err(String::from("strategy must be one of max, min or tagged")),
+        };
+
+        for option in parts {
+            if let Some(group) = option.strip_prefix("g:") {
+                let (group_tag, group_min_size) = match group.split_once('/') {
+                    Some((group_tag, group_min_size)) => (group_tag, Some(group_min_size)),
+                    None => (group, None),
+                };
+
+                if !group_tag.is_empty() {
+                    let group_tag = group_tag
+                        .parse::<i64>()
+                        .map_err(|_| format!("invalid group tag '{}'", group_tag))?;
+                    flow_control.set_group_tag(group_tag)?;
+                }
+
+                if let Some(group_min_size) = group_min_size {
+                    let group_min_size = group_min_size
+                        .parse::<i32>()
+                        .map_err(|_| format!("invalid group minimum size '{}'", group_min_size))?;
+                    flow_control.set_group_min_size(group_min_size)?;
+                }
+            } else if let Some(timeout) = option.strip_prefix("t:") {
+                flow_control.set_timeout(channel_uri::parse_duration(timeout)?)?;
+            } else {
+                return Err(format!(
+                    "unknown option '{}', expected g:<tag>/<min size> or t:<timeout>",
+                    option
+                ));
+            }
+        }
+
+        Ok(flow_control)
+    }
+}
+
+/// Value of the cc parameter selecting congestion control of a receiver.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CongestionControl {
+    /// Static window given by rcv-wnd or the default receiver window.
+    Static,
+    /// CUBIC congestion control adjusting the receiver window to the measured RTT.
+    Cubic,
+}
+
+impl Display for CongestionControl {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            CongestionControl::Static => write!(f, "{}", STATIC_CONGESTION_CONTROL),
+            CongestionControl::Cubic => write!(f, "{}", CUBIC_CONGESTION_CONTROL),
+        }
+    }
+}
+
+impl FromStr for CongestionControl {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            STATIC_CONGESTION_CONTROL => Ok(CongestionControl::Static),
+            CUBIC_CONGESTION_CONTROL => Ok(CongestionControl::Cubic),
+            _ => Err(String::from("must be one of static or cubic")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_render_and_parse_flow_control() {
+        let mut tagged = FlowControl::tagged();
+        tagged
+            .with_group_tag(101)
+            .unwrap()
+            .with_group_min_size(3)
+            .unwrap()
+            .with_timeout(Duration::from_secs(1))
+            .unwrap();
+
+        let mut min = FlowControl::min();
+        min.with_timeout(Duration::from_millis(250)).unwrap();
+
+        for (flow_control, value) in [
+            (FlowControl::max(), "max"),
+            (min, "min,t:250ms"),
+            (tagged, "tagged,g:101/3,t:1s"),
+        ] {
+            assert_eq!(flow_control.to_string(), value);
+            assert_eq!(value.parse::<FlowControl>().unwrap(), flow_control);
+        }
+
+        let min_group: FlowControl = "min,g:/5,t:5s".parse().unwrap();
+        assert_eq!(min_group.group_tag(), None);
+        assert_eq!(min_group.group_min_size(), Some(5));
+        assert_eq!(min_group.timeout(), Some(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn should_reject_invalid_flow_control() {
+        assert!(FlowControl::max().with_timeout(Duration::from_secs(1)).is_err());
+        assert!(FlowControl::min().with_group_tag(1).is_err());
+        assert!(FlowControl::tagged().with_group_min_size(-1).is_err());
+
+        for value in &["maximum", "max,t:1s", "min,g:5", "tagged,g:x/1", "tagged,t:5 s", "min,x:1"] {
+            assert!(value.parse::<FlowControl>().is_err(), "{} should be rejected", value);
+        }
+    }
+
+    #[test]
+    fn should_render_and_parse_congestion_control() {
+        assert_eq!(CongestionControl::Cubic.to_string(), "cubic");
+        assert_eq!("static".parse::<CongestionControl>().unwrap(), CongestionControl::Static);
+        assert!("reno".parse::<CongestionControl>().is_err());
+    }
+}
@@ -0,0 +1,532 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::collections::{HashMap, HashSet};
+
+use crate::concurrent::agent_runner::Agent;
+use crate::concurrent::counters::CountersReader;
+use crate::driver_counters::{
+    DriverCounter, PUBLISHER_LIMIT_TYPE_ID, PUBLISHER_POSITION_TYPE_ID, RECEIVER_HWM_TYPE_ID, SUBSCRIBER_POSITION_TYPE_ID,
+};
+use crate::exclusive_publication::ExclusivePublication;
+use crate::image::Image;
+use crate::publication::Publication;
+use crate::utils::errors::AeronError;
+use crate::utils::misc::unix_time_ms;
+use crate::utils::types::Moment;
+
+/// Interval between lag checks when run as an agent.
+pub const DEFAULT_CHECK_INTERVAL_MS: Moment = 1000;
+
+/**
+ * Lag above which a subscriber is reported as slow. The term window is the half of the term length
+ * which a publisher may advance ahead of the slowest subscriber.
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LagThreshold {
+    Bytes(i64),
+    TermWindowPercent(f64),
+}
+
+impl LagThreshold {
+    pub fn bytes(&self, term_length: i32) -> i64 {
+        match self {
+            LagThreshold::Bytes(bytes) => *bytes,
+            LagThreshold::TermWindowPercent(percent) => ((term_length / 2) as f64 * percent / 100.0) as i64,
+        }
+    }
+}
+
+/// Stream watched by the monitor, identified by the registration ids the client holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MonitoredStream {
+    /// All subscribers of a publication: lag is measured against the publisher position.
+    Publication {
+        registration_id: i64,
+        session_id: i32,
+        term_length: i32,
+    },
+    /// Subscriber of an image: lag is measured against the receiver high water mark, or the publisher
+    /// position for IPC.
+    Image {
+        subscription_registration_id: i64,
+        session_id: i32,
+        term_length: i32,
+    },
+}
+
+impl MonitoredStream {
+    pub fn session_id(&self) -> i32 {
+        match self {
+            MonitoredStream::Publication { session_id, .. } | MonitoredStream::Image { session_id, .. } => *session_id,
+        }
+    }
+
+    pub fn term_length(&self) -> i32 {
+        match self {
+            MonitoredStream::Publication { term_length, .. } | MonitoredStream::Image { term_length, .. } => *term_length,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LagReason {
+    /// Lag crossed the threshold. Reported again only after lag dropped below it.
+    ThresholdExceeded,
+    /// Lag has grown on each of the last `intervals` checks.
+    Growing { intervals: u32 },
+}
+
+/// Subscriber reported by the LagMonitor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlowSubscriber {
+    pub stream: MonitoredStream,
+    pub subscriber_registration_id: i64,
+    pub subscriber_counter_id: i32,
+    pub subscriber_position: i64,
+    /// Position the subscriber lags behind: publisher position or receiver high water mark.
+    pub reference_position: i64,
+    pub publisher_limit: Option<i64>,
+    pub lag: i64,
+    pub reason: LagReason,
+}
+
+/**
+ * Function called by LagMonitor when a subscriber is slow.
+ *
+ * @param slow_subscriber describing the lag and why it was reported.
+ */
+pub trait OnSlowSubscriber: Send {
+    fn call(&self, slow_subscriber: &SlowSubscriber);
+    fn clone_box(&self) -> Box<dyn OnSlowSubscriber>;
+}
+
+impl Clone for Box<dyn OnSlowSubscriber> {
+    fn clone(&self) -> Box<dyn OnSlowSubscriber> {
+        self.clone_box()
+    }
+}
+
+impl<F> OnSlowSubscriber for F
+where
+    F: Fn(&SlowSubscriber) + Clone + Send + 'static,
+{
+    fn call(&self, slow_subscriber: &SlowSubscriber) {
+        self(slow_subscriber)
+    }
+
+    fn clone_box(&self) -> Box<dyn OnSlowSubscriber> {
+        Box::new(self.clone())
+    }
+}
+
+struct LagState {
+    last_lag: i64,
+    growing_intervals: u32,
+    is_over_threshold: bool,
+}
+
+struct StreamCounter {
+    counter_id: i32,
+    counter: DriverCounter,
+    value: i64,
+}
+
+/**
+ * Agent which watches the position counters of chosen streams and reports subscribers which lag
+ * behind by more than a threshold or whose lag keeps growing. It may be run on its own thread with
+ * AgentRunner or be invoked from an application duty cycle with AgentInvoker.
+ */
+pub struct LagMonitor {
+    counters_reader: CountersReader,
+    streams: Vec<MonitoredStream>,
+    threshold: Option<LagThreshold>,
+    growth_intervals: Option<u32>,
+    check_interval_ms: Moment,
+    last_check_ms: Moment,
+    lag_states: HashMap<(MonitoredStream, i32), LagState>,
+    on_slow_subscriber: Box<dyn OnSlowSubscriber>,
+}
+
+impl LagMonitor {
+    pub fn new(counters_reader: CountersReader, on_slow_subscriber: impl OnSlowSubscriber + 'static) -> Self {
+        Self {
+            counters_reader,
+            streams: Vec::new(),
+            threshold: None,
+            growth_intervals: None,
+            check_interval_ms: DEFAULT_CHECK_INTERVAL_MS,
+            last_check_ms: 0,
+            lag_states: HashMap::new(),
+            on_slow_subscriber: Box::new(on_slow_subscriber),
+        }
+    }
+
+    pub fn set_threshold(&mut self, threshold: LagThreshold) -> &Self {
+        self.threshold = Some(threshold);
+        self
+    }
+
+    /// Report subscribers whose lag has grown on this many consecutive checks.
+    pub fn set_growth_intervals(&mut self, intervals: u32) -> &Self {
+        self.growth_intervals = Some(intervals);
+        self
+    }
+
+    pub fn set_check_interval_ms(&mut self, interval_ms: Moment) -> &Self {
+        self.check_interval_ms = interval_ms;
+        self
+    }
+
+    pub fn add_stream(&mut self, stream: MonitoredStream) {
+        if !self.streams.contains(&stream) {
+            self.streams.push(stream);
+        }
+    }
+
+    pub fn remove_stream(&mut self, stream: &MonitoredStream) {
+        self.streams.retain(|monitored| monitored != stream);
+        self.lag_states.retain(|(monitored, _), _| monitored != stream);
+    }
+
+    pub fn add_publication(&mut self, publication: &Publication) {
+        self.add_stream(MonitoredStream::Publication {
+            registration_id: publication.original_registration_id(),
+            session_id: publication.session_id(),
+            term_length: publication.term_buffer_length(),
+        });
+    }
+
+    pub fn add_exclusive_publication(&mut self, publication: &ExclusivePublication) {
+        self.add_stream(MonitoredStream::Publication {
+            registration_id: publication.registration_id(),
+            session_id: publication.session_id(),
+            term_length: publication.term_buffer_length(),
+        });
+    }
+
+    pub fn add_image(&mut self, image: &Image) {
+        self.add_stream(MonitoredStream::Image {
+            subscription_registration_id: image.subscription_registration_id(),
+            session_id: image.session_id(),
+            term_length: image.term_buffer_length(),
+        });
+    }
+
+    pub fn streams(&self) -> &[MonitoredStream] {
+        &self.streams
+    }
+
+    fn read_stream_counters(&self) -> Vec<StreamCounter> {
+        let mut stream_counters = Vec::new();
+
+        self.counters_reader.for_each(|counter_id, type_id, key_buffer, _label| {
+            let counter = DriverCounter::decode(type_id, key_buffer);
+
+            if counter.stream_key().is_some() {
+                stream_counters.push(StreamCounter {
+                    counter_id,
+                    counter,
+                    value: self.counters_reader.counter_value(counter_id).unwrap_or_default() as i64,
+                });
+            }
+        });
+
+        stream_counters
+    }
+
+    /**
+     * Check lag of all subscribers of the monitored streams and call the handler for slow ones.
+     *
+     * @return number of slow subscribers reported.
+     */
+    pub fn check(&mut self) -> i32 {
+        let stream_counters = self.read_stream_counters();
+        let find = |type_id: i32, matches: &dyn Fn(i64, i32, i32) -> bool| {
+            stream_counters.iter().find(|stream_counter| {
+                stream_counter.counter.type_id() == type_id
+                    && stream_counter
+                        .counter
+                        .stream_key()
+                        .is_some_and(|key| matches(key.registration_id, key.session_id, key.stream_id))
+            })
+        };
+
+        let mut observed = Vec::new();
+
+        for stream in &self.streams {
+            let session_id = stream.session_id();
+            let (reference, publisher_limit, subscribers) = match *stream {
+                MonitoredStream::Publication { registration_id, .. } => {
+                    let publisher_position = match find(PUBLISHER_POSITION_TYPE_ID, &|registration, session, _| {
+                        registration == registration_id && session == session_id
+                    }) {
+                        Some(publisher_position) => publisher_position,
+                        None => continue,
+                    };
+                    let stream_id = publisher_position.counter.stream_key().map_or(0, |key| key.stream_id);
+                    let publisher_limit = find(PUBLISHER_LIMIT_TYPE_ID, &|registration, session, _| {
+                        registration == registration_id && session == session_id
+                    });
+                    let subscribers: Vec<&StreamCounter> = stream_counters
+                        .iter()
+                        .filter(|stream_counter| {
+                            matches!(&stream_counter.counter, DriverCounter::SubscriberPosition(key)
+                                if key.session_id == session_id && key.stream_id == stream_id)
+                        })
+                        .collect();
+
+                    (publisher_position.value, publisher_limit, subscribers)
+                },
+                MonitoredStream::Image {
+                    subscription_registration_id,
+                    ..
+                } => {
+                    let subscriber = match find(SUBSCRIBER_POSITION_TYPE_ID, &|registration, session, _| {
+                        registration == subscription_registration_id && session == session_id
+                    }) {
+                        Some(subscriber) => subscriber,
+                        None => continue,
+                    };
+                    let stream_id = subscriber.counter.stream_key().map_or(0, |key| key.stream_id);
+                    let same_stream = |_: i64, session: i32, stream: i32| session == session_id && stream == stream_id;
+                    let reference = match find(RECEIVER_HWM_TYPE_ID, &same_stream)
+                        .or_else(|| find(PUBLISHER_POSITION_TYPE_ID, &same_stream))
+                    {
+                        Some(reference) => reference,
+                        None => continue,
+                    };
+                    let publisher_limit = find(PUBLISHER_LIMIT_TYPE_ID, &same_stream);
+
+                    (reference.value, publisher_limit, vec![subscriber])
+                },
+            };
+
+            for subscriber in subscribers {
+                observed.push(SlowSubscriber {
+                    stream: *stream,
+                    subscriber_registration_id: subscriber.counter.registration_id().unwrap_or_default(),
+                    subscriber_counter_id: subscriber.counter_id,
+                    subscriber_position: subscriber.value,
+                    reference_position: reference,
+                    publisher_limit: publisher_limit.map(|limit| limit.value),
+                    lag: reference - subscriber.value,
+                    reason: LagReason::ThresholdExceeded,
+                });
+            }
+        }
+
+        let mut reported = 0;
+        let mut seen = HashSet::new();
+
+        for mut subscriber in observed {
+            let key = (subscriber.stream, subscriber.subscriber_counter_id);
+            seen.insert(key);
+
+            let state = self.lag_states.entry(key).or_insert(LagState {
+                last_lag: subscriber.lag,
+                growing_intervals: 0,
+                is_over_threshold: false,
+            });
+
+            if subscriber.lag > state.last_lag {
+                state.growing_intervals += 1;
+            } else {
+                state.growing_intervals = 0;
+            }
+            state.last_lag = subscriber.lag;
+
+            if let Some(threshold) = self.threshold {
+                if subscriber.lag > threshold.bytes(subscriber.stream.term_length()) {
+                    if !state.is_over_threshold {
+                        state.is_over_threshold = true;
+                        subscriber.reason = LagReason::ThresholdExceeded;
+                        self.on_slow_subscriber.call(&subscriber);
+                        reported += 1;
+                    }
+                } else {
+                    state.is_over_threshold = false;
+                }
+            }
+
+            if let Some(intervals) = self.growth_intervals {
+                if intervals > 0 && state.growing_intervals >= intervals {
+                    subscriber.reason = LagReason::Growing {
+                        intervals: state.growing_intervals,
+                    };
+                    state.growing_intervals = 0;
+                    self.on_slow_subscriber.call(&subscriber);
+                    reported += 1;
+                }
+            }
+        }
+
+        self.lag_states.retain(|key, _| seen.contains(key));
+
+        reported
+    }
+}
+
+impl Agent for LagMonitor {
+    fn on_start(&mut self) -> Result<(), AeronError> {
+        Ok(())
+    }
+
+    fn do_work(&mut self) -> Result<i32, AeronError> {
+        let now_ms = unix_time_ms();
+
+        if now_ms < self.last_check_ms + self.check_interval_ms {
+            return Ok(0);
+        }
+
+        self.last_check_ms = now_ms;
+        Ok(self.check())
+    }
+
+    fn on_close(&mut self) -> Result<(), AeronError> {
+        self.lag_states.clear();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::concurrent::atomic_buffer::AtomicBuffer;
+    use crate::concurrent::counters::CountersFixture;
+    use crate::driver_counters::{
+        STREAM_COUNTER_CHANNEL_OFFSET, STREAM_COUNTER_REGISTRATION_ID_OFFSET, STREAM_COUNTER_SESSION_ID_OFFSET,
+        STREAM_COUNTER_STREAM_ID_OFFSET,
+    };
+
+    const NUM_COUNTERS: i32 = 16;
+    const SESSION_ID: i32 = 77;
+    const STREAM_ID: i32 = 1001;
+    const TERM_LENGTH: i32 = 64 * 1024;
+
+    fn add_position(fixture: &mut CountersFixture, type_id: i32, registration_id: i64, position: i64) -> i32 {
+        let id = fixture
+            .manager
+            .allocate_opt(
+                type_id,
+                None,
+                Some(|key_buffer: &mut AtomicBuffer| {
+                    key_buffer.put::<i64>(STREAM_COUNTER_REGISTRATION_ID_OFFSET, registration_id);
+                    key_buffer.put::<i32>(STREAM_COUNTER_SESSION_ID_OFFSET, SESSION_ID);
+                    key_buffer.put::<i32>(STREAM_COUNTER_STREAM_ID_OFFSET, STREAM_ID);
+                    key_buffer.put_string(STREAM_COUNTER_CHANNEL_OFFSET, b"aeron:ipc");
+                }),
+                "position",
+            )
+            .unwrap();
+        set(fixture, id, position);
+        id
+    }
+
+    fn set(fixture: &mut CountersFixture, counter_id: i32, position: i64) {
+        fixture.manager.set_counter_value(counter_id, position as u64);
+    }
+
+    fn monitor(fixture: &CountersFixture) -> (LagMonitor, Arc<Mutex<Vec<SlowSubscriber>>>) {
+        let reported = Arc::new(Mutex::new(Vec::new()));
+        let sink = reported.clone();
+        let monitor = LagMonitor::new(fixture.reader(), move |slow: &SlowSubscriber| {
+            sink.lock().unwrap().push(slow.clone())
+        });
+
+        (monitor, reported)
+    }
+
+    #[test]
+    fn should_report_subscribers_of_publication_over_threshold_once() {
+        let mut fixture = CountersFixture::new(NUM_COUNTERS);
+        let publisher_position = add_position(&mut fixture, PUBLISHER_POSITION_TYPE_ID, 3, 8192);
+        add_position(&mut fixture, PUBLISHER_LIMIT_TYPE_ID, 3, 40960);
+        let slow_id = add_position(&mut fixture, SUBSCRIBER_POSITION_TYPE_ID, 5, 0);
+        add_position(&mut fixture, SUBSCRIBER_POSITION_TYPE_ID, 6, 8192);
+
+        let (mut monitor, reported) = monitor(&fixture);
+        monitor.set_threshold(LagThreshold::Bytes(4096));
+        monitor.add_stream(MonitoredStream::Publication {
+            registration_id: 3,
+            session_id: SESSION_ID,
+            term_length: TERM_LENGTH,
+        });
+
+        assert_eq!(monitor.check(), 1);
+        assert_eq!(monitor.check(), 0);
+        {
+            let reported = reported.lock().unwrap();
+            assert_eq!(reported[0].subscriber_counter_id, slow_id);
+            assert_eq!(reported[0].subscriber_registration_id, 5);
+            assert_eq!(reported[0].lag, 8192);
+            assert_eq!(reported[0].publisher_limit, Some(40960));
+            assert_eq!(reported[0].reason, LagReason::ThresholdExceeded);
+        }
+
+        set(&mut fixture, slow_id, 8192);
+        assert_eq!(monitor.check(), 0);
+
+        set(&mut fixture, publisher_position, 16384);
+        assert_eq!(monitor.check(), 2);
+    }
+
+    #[test]
+    fn should_use_term_window_percentage_and_receiver_hwm_for_images() {
+        let mut fixture = CountersFixture::new(NUM_COUNTERS);
+        add_position(&mut fixture, RECEIVER_HWM_TYPE_ID, 4, 20000);
+        let subscriber_id = add_position(&mut fixture, SUBSCRIBER_POSITION_TYPE_ID, 5, 0);
+
+        let (mut monitor, reported) = monitor(&fixture);
+        monitor.set_threshold(LagThreshold::TermWindowPercent(50.0));
+        monitor.add_stream(MonitoredStream::Image {
+            subscription_registration_id: 5,
+            session_id: SESSION_ID,
+            term_length: TERM_LENGTH,
+        });
+
+        assert_eq!(LagThreshold::TermWindowPercent(50.0).bytes(TERM_LENGTH), 16384);
+        assert_eq!(monitor.check(), 1);
+        assert_eq!(reported.lock().unwrap()[0].reference_position, 20000);
+
+        set(&mut fixture, subscriber_id, 10000);
+        assert_eq!(monitor.check(), 0);
+    }
+
+    #[test]
+    fn should_report_lag_growing_for_intervals() {
+        let mut fixture = CountersFixture::new(NUM_COUNTERS);
+        let publisher_position = add_position(&mut fixture, PUBLISHER_POSITION_TYPE_ID, 3, 0);
+        add_position(&mut fixture, SUBSCRIBER_POSITION_TYPE_ID, 5, 0);
+
+        let (mut monitor, reported) = monitor(&fixture);
+        monitor.set_growth_intervals(2);
+        monitor.add_stream(MonitoredStream::Publication {
+            registration_id: 3,
+            session_id: SESSION_ID,
+            term_length: TERM_LENGTH,
+        });
+
+        assert_eq!(monitor.check(), 0);
+        set(&mut fixture, publisher_position, 100);
+        assert_eq!(monitor.check(), 0);
+        set(&mut fixture, publisher_position, 200);
+        assert_eq!(monitor.check(), 1);
+        assert_eq!(reported.lock().unwrap()[0].reason, LagReason::Growing { intervals: 2 });
+    }
+}
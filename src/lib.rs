@@ -20,23 +20,36 @@
 extern crate memoffset;
 
 pub mod aeron;
+pub mod app_counters;
 pub mod buffer_builder;
+pub mod channel_status_registry;
 pub mod channel_uri;
 pub mod channel_uri_string_builder;
 pub mod client_conductor;
 pub mod cnc_file_descriptor;
 pub mod command;
 pub mod concurrent;
+pub mod connected_clients;
 pub mod context;
+pub mod control_sniffer;
 pub mod counter;
+pub mod driver_counters;
 pub mod driver_listener_adapter;
 pub mod driver_proxy;
 pub mod example_config;
 pub mod exclusive_publication;
+pub mod flow_control;
 pub mod fragment_assembler;
 pub mod heartbeat_timestamp;
 pub mod image;
+pub mod lag_monitor;
+pub mod log_inspector;
+pub mod loss_proxy;
+pub mod packet_dissector;
+pub mod pcap;
+pub mod prometheus_exporter;
 pub mod protocol;
 pub mod publication;
+pub mod stream_stat;
 pub mod subscription;
 pub mod utils;
@@ -0,0 +1,515 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::convert::TryFrom;
+use std::ffi::OsString;
+use std::fmt;
+use std::path::Path;
+
+use crate::concurrent::atomic_buffer::AtomicBuffer;
+use crate::concurrent::logbuffer::data_frame_header::{self, DataFrameHeaderDefn};
+use crate::concurrent::logbuffer::frame_descriptor::{self, FRAME_ALIGNMENT};
+use crate::concurrent::logbuffer::log_buffer_descriptor::{self, LOG_META_DATA_SECTION_INDEX, PARTITION_COUNT};
+use crate::protocol::frame::FrameType;
+use crate::utils::bit_utils;
+use crate::utils::errors::AeronError;
+use crate::utils::log_buffers::LogBuffers;
+use crate::utils::types::Index;
+
+/// Fields of the log meta data section, see LogMetaDataDefn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LogMetaData {
+    pub raw_tails: [i64; PARTITION_COUNT as usize],
+    pub active_term_count: i32,
+    pub end_of_stream_position: i64,
+    pub is_connected: bool,
+    pub active_transport_count: i32,
+    pub correlation_id: i64,
+    pub initial_term_id: i32,
+    pub default_frame_header_length: i32,
+    pub mtu_length: i32,
+    pub term_length: i32,
+    pub page_size: i32,
+}
+
+impl LogMetaData {
+    pub fn read(log_meta_data_buffer: &AtomicBuffer) -> Self {
+        let mut raw_tails = [0; PARTITION_COUNT as usize];
+        for (partition_index, raw_tail) in raw_tails.iter_mut().enumerate() {
+            *raw_tail = log_buffer_descriptor::raw_tail_by_partition_index(log_meta_data_buffer, partition_index as Index);
+        }
+
+        Self {
+            raw_tails,
+            active_term_count: log_buffer_descriptor::active_term_count(log_meta_data_buffer),
+            end_of_stream_position: log_buffer_descriptor::end_of_stream_position(log_meta_data_buffer),
+            is_connected: log_buffer_descriptor::is_connected(log_meta_data_buffer),
+            active_transport_count: log_buffer_descriptor::active_transport_count(log_meta_data_buffer),
+            correlation_id: log_buffer_descriptor::correlation_id(log_meta_data_buffer),
+            initial_term_id: log_buffer_descriptor::initial_term_id(log_meta_data_buffer),
+            default_frame_header_length: log_buffer_descriptor::default_frame_header_length(log_meta_data_buffer),
+            mtu_length: log_buffer_descriptor::mtu_length(log_meta_data_buffer),
+            term_length: log_buffer_descriptor::term_length(log_meta_data_buffer),
+            page_size: log_buffer_descriptor::page_size(log_meta_data_buffer),
+        }
+    }
+
+    pub fn active_partition_index(&self) -> Index {
+        log_buffer_descriptor::index_by_term_count(self.active_term_count as i64)
+    }
+
+    pub fn tail_term_id(&self, partition_index: Index) -> i32 {
+        log_buffer_descriptor::term_id(self.raw_tails[partition_index as usize])
+    }
+
+    /// Offset of the tail in the term of the partition, limited to the term length.
+    pub fn tail_term_offset(&self, partition_index: Index) -> Index {
+        log_buffer_descriptor::term_offset(self.raw_tails[partition_index as usize], self.term_length as i64)
+    }
+}
+
+/// Data frame header as found in a term or as the default frame header of the log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameHeader {
+    pub frame_length: i32,
+    pub version: u8,
+    pub flags: u8,
+    pub frame_type: u16,
+    pub term_offset: i32,
+    pub session_id: i32,
+    pub stream_id: i32,
+    pub term_id: i32,
+    pub reserved_value: i64,
+}
+
+impl FrameHeader {
+    /// Read the header at the offset, the frame length with volatile semantics as it commits the frame.
+    pub fn read(buffer: &AtomicBuffer, offset: Index) -> Self {
+        let header = buffer.get::<DataFrameHeaderDefn>(offset);
+
+        Self {
+            frame_length: frame_descriptor::frame_length_volatile(buffer, offset),
+            version: header.version,
+            flags: header.flags,
+            frame_type: header.frame_type,
+            term_offset: header.term_offset,
+            session_id: header.session_id,
+            stream_id: header.stream_id,
+            term_id: header.term_id,
+            reserved_value: header.reserved_value,
+        }
+    }
+
+    pub fn type_name(&self) -> String {
+        FrameType::from_type_id(self.frame_type)
+            .map(|frame_type| frame_type.name().to_string())
+            .unwrap_or_else(|| format!("{:#06x}", self.frame_type))
+    }
+}
+
+impl fmt::Display for FrameHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} len={} version={} flags={:#04x} term_offset={} session_id={} stream_id={} term_id={} reserved_value={}",
+            self.type_name(),
+            self.frame_length,
+            self.version,
+            self.flags,
+            self.term_offset,
+            self.session_id,
+            self.stream_id,
+            self.term_id,
+            self.reserved_value
+        )
+    }
+}
+
+/// Reason a frame can't be trusted, e.g. a publisher died while writing it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameIssue {
+    /// Negative frame length of a claimed frame which was never committed.
+    Uncommitted,
+    LengthTooShort,
+    ExceedsTerm,
+    TermOffsetMismatch,
+    TermIdMismatch {
+        expected_term_id: i32,
+    },
+    UnsupportedVersion,
+    UnknownType,
+}
+
+impl FrameIssue {
+    /// Frames after the issue can't be found, the frame length can't be trusted.
+    pub fn stops_scan(&self) -> bool {
+        matches!(self, FrameIssue::LengthTooShort | FrameIssue::ExceedsTerm)
+    }
+}
+
+impl fmt::Display for FrameIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FrameIssue::Uncommitted => write!(f, "torn: frame was claimed but not committed"),
+            FrameIssue::LengthTooShort => write!(f, "torn: frame length is shorter than the header"),
+            FrameIssue::ExceedsTerm => write!(f, "torn: frame exceeds the term"),
+            FrameIssue::TermOffsetMismatch => write!(f, "torn: term offset doesn't match the frame offset"),
+            FrameIssue::TermIdMismatch { expected_term_id } => {
+                write!(f, "torn: term id doesn't match term id {} of the term", expected_term_id)
+            },
+            FrameIssue::UnsupportedVersion => write!(f, "torn: unsupported version"),
+            FrameIssue::UnknownType => write!(f, "torn: unknown frame type"),
+        }
+    }
+}
+
+/// Frame or run of zeroed frames found walking a term.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TermEntry {
+    Frame {
+        offset: Index,
+        header: FrameHeader,
+        issue: Option<FrameIssue>,
+    },
+    /// Frame alignments with a zero frame length, a gap in an image or a frame not committed yet.
+    Zeroed { offset: Index, length: Index },
+}
+
+/**
+ * Walk the frames of a term from its start.
+ *
+ * @param term  buffer of the term.
+ * @param limit offset to walk to, e.g. the tail offset of the term or its length.
+ * @return frames and zeroed runs in the order of the term.
+ */
+pub fn scan_term(term: &AtomicBuffer, limit: Index) -> Vec<TermEntry> {
+    let limit = limit.min(term.capacity());
+    let mut entries = Vec::new();
+    let mut expected_term_id = None;
+    let mut offset = 0;
+
+    while offset + data_frame_header::LENGTH <= limit {
+        let frame_length = frame_descriptor::frame_length_volatile(term, offset);
+
+        if frame_length == 0 {
+            let zeroed_offset = offset;
+            while offset < limit && frame_descriptor::frame_length_volatile(term, offset) == 0 {
+                offset += FRAME_ALIGNMENT;
+            }
+
+            entries.push(TermEntry::Zeroed {
+                offset: zeroed_offset,
+                length: offset.min(limit) - zeroed_offset,
+            });
+            continue;
+        }
+
+        let header = FrameHeader::read(term, offset);
+        let issue = frame_issue(&header, offset, term.capacity(), expected_term_id);
+
+        if issue.is_none() && expected_term_id.is_none() {
+            expected_term_id = Some(header.term_id);
+        }
+
+        entries.push(TermEntry::Frame { offset, header, issue });
+
+        if issue.is_some_and(|issue| issue.stops_scan()) {
+            break;
+        }
+
+        // The frame fits in the term, otherwise the scan would have stopped.
+        offset += bit_utils::align(frame_length.abs(), FRAME_ALIGNMENT);
+    }
+
+    entries
+}
+
+fn frame_issue(header: &FrameHeader, offset: Index, term_length: Index, expected_term_id: Option<i32>) -> Option<FrameIssue> {
+    // The length may be garbage, e.g. i32::MIN, so compare in i64.
+    let frame_length = header.frame_length.unsigned_abs() as i64;
+
+    let issue = if frame_length < data_frame_header::LENGTH as i64 {
+        FrameIssue::LengthTooShort
+    } else if offset as i64 + frame_length > term_length as i64 {
+        FrameIssue::ExceedsTerm
+    } else if header.frame_length < 0 {
+        FrameIssue::Uncommitted
+    } else if header.version != data_frame_header::CURRENT_VERSION {
+        FrameIssue::UnsupportedVersion
+    } else if header.frame_type != data_frame_header::HDR_TYPE_DATA && header.frame_type != data_frame_header::HDR_TYPE_PAD {
+        FrameIssue::UnknownType
+    } else if header.term_offset != offset {
+        FrameIssue::TermOffsetMismatch
+    } else {
+        match expected_term_id {
+            Some(expected_term_id) if expected_term_id != header.term_id => FrameIssue::TermIdMismatch { expected_term_id },
+            _ => return None,
+        }
+    };
+
+    Some(issue)
+}
+
+/**
+ * Payload of a frame found by scan_term, limited to the term.
+ *
+ * @param term      buffer of the term.
+ * @param offset    of the frame in the term.
+ * @param header    of the frame.
+ * @param max_bytes of the payload to return.
+ */
+pub fn frame_payload<'a>(term: &'a AtomicBuffer, offset: Index, header: &FrameHeader, max_bytes: usize) -> &'a [u8] {
+    let payload_offset = offset + data_frame_header::LENGTH;
+    let payload_length = (header.frame_length.unsigned_abs() as i64 - data_frame_header::LENGTH as i64)
+        .min((term.capacity() - payload_offset) as i64)
+        .min(i64::try_from(max_bytes).unwrap_or(i64::MAX))
+        .max(0) as Index;
+
+    term.as_sub_slice(payload_offset, payload_length)
+}
+
+/// Log buffer file of a publication or image mapped read only to inspect its meta data and terms.
+pub struct LogInspector {
+    log_buffers: LogBuffers,
+}
+
+impl LogInspector {
+    pub fn open<P: fmt::Display + AsRef<Path> + Into<OsString>>(file_path: P) -> Result<Self, AeronError> {
+        Ok(Self {
+            log_buffers: LogBuffers::from_existing_read_only(file_path)?,
+        })
+    }
+
+    pub fn meta_data(&self) -> LogMetaData {
+        LogMetaData::read(&self.log_buffers.atomic_buffer(LOG_META_DATA_SECTION_INDEX))
+    }
+
+    pub fn default_frame_header(&self) -> FrameHeader {
+        let meta_data_buffer = self.log_buffers.atomic_buffer(LOG_META_DATA_SECTION_INDEX);
+        FrameHeader::read(&log_buffer_descriptor::default_frame_header(&meta_data_buffer), 0)
+    }
+
+    pub fn term_buffer(&self, partition_index: Index) -> AtomicBuffer {
+        self.log_buffers.atomic_buffer(partition_index)
+    }
+
+    /**
+     * Walk the frames of a partition.
+     *
+     * @param partition_index of the term.
+     * @param to_tail         stop at the tail of the term, otherwise walk the whole term, e.g. for images which
+     *                        don't maintain the tails.
+     */
+    pub fn scan_partition(&self, partition_index: Index, to_tail: bool) -> Vec<TermEntry> {
+        let term = self.term_buffer(partition_index);
+        let limit = if to_tail {
+            self.meta_data().tail_term_offset(partition_index)
+        } else {
+            term.capacity()
+        };
+
+        scan_term(&term, limit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use crate::concurrent::logbuffer::data_frame_header::{self, DataFrameHeaderDefn};
+    use crate::concurrent::logbuffer::log_buffer_descriptor::{self, LOG_META_DATA_LENGTH};
+    use crate::log_inspector::{frame_payload, FrameHeader, FrameIssue, LogInspector, TermEntry};
+    use crate::utils::errors::{AeronError, IllegalStateError};
+    use crate::utils::memory_mapped_file::MemoryMappedFile;
+    use crate::utils::types::Index;
+
+    const TERM_LENGTH: Index = 64 * 1024;
+    const SESSION_ID: i32 = 7;
+    const STREAM_ID: i32 = 1001;
+    const TERM_ID: i32 = 3;
+
+    fn create_log(tmp_dir: &tempfile::TempDir, length: Index) -> String {
+        let file_path = tmp_dir.path().join("1.logbuffer");
+        File::create(&file_path).unwrap().set_len(length as u64).unwrap();
+        file_path.to_str().unwrap().to_string()
+    }
+
+    fn header(frame_length: i32, term_offset: i32, term_id: i32) -> DataFrameHeaderDefn {
+        DataFrameHeaderDefn {
+            frame_length,
+            version: data_frame_header::CURRENT_VERSION,
+            flags: 0xc0,
+            frame_type: data_frame_header::HDR_TYPE_DATA,
+            term_offset,
+            session_id: SESSION_ID,
+            stream_id: STREAM_ID,
+            term_id,
+            reserved_value: 0,
+        }
+    }
+
+    /// Inspector of a new log and a writable mapping of the log to put frames with, the inspector maps it read only.
+    fn open_log(tmp_dir: &tempfile::TempDir) -> (LogInspector, MemoryMappedFile) {
+        let file_path = create_log(tmp_dir, TERM_LENGTH * 3 + LOG_META_DATA_LENGTH);
+        let log_file = MemoryMappedFile::map_existing(file_path.clone(), false).unwrap();
+
+        let meta_data = log_file.atomic_buffer(TERM_LENGTH * 3, LOG_META_DATA_LENGTH);
+        meta_data.put::<i32>(*log_buffer_descriptor::LOG_TERM_LENGTH_OFFSET, TERM_LENGTH);
+        meta_data.put::<i32>(*log_buffer_descriptor::LOG_PAGE_SIZE_OFFSET, 4096);
+        meta_data.put::<i32>(*log_buffer_descriptor::LOG_MTU_LENGTH_OFFSET, 1408);
+        meta_data.put::<i32>(*log_buffer_descriptor::LOG_INITIAL_TERM_ID_OFFSET, TERM_ID);
+        meta_data.put::<i32>(
+            *log_buffer_descriptor::LOG_DEFAULT_FRAME_HEADER_LENGTH_OFFSET,
+            data_frame_header::LENGTH,
+        );
+        meta_data.put(log_buffer_descriptor::LOG_DEFAULT_FRAME_HEADER_OFFSET, header(0, 0, TERM_ID));
+        log_buffer_descriptor::initialize_tail_with_term_id(&meta_data, 0, TERM_ID);
+        log_buffer_descriptor::set_is_connected(&meta_data, true);
+
+        (LogInspector::open(file_path).unwrap(), log_file)
+    }
+
+    #[test]
+    fn should_read_meta_data_and_default_frame_header() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let (inspector, _log_file) = open_log(&tmp_dir);
+
+        let meta_data = inspector.meta_data();
+        assert_eq!(meta_data.term_length, TERM_LENGTH);
+        assert_eq!(meta_data.mtu_length, 1408);
+        assert_eq!(meta_data.initial_term_id, TERM_ID);
+        assert!(meta_data.is_connected);
+        assert_eq!(meta_data.active_partition_index(), 0);
+        assert_eq!(meta_data.tail_term_id(0), TERM_ID);
+        assert_eq!(meta_data.tail_term_offset(0), 0);
+
+        let default_frame_header = inspector.default_frame_header();
+        assert_eq!(default_frame_header.session_id, SESSION_ID);
+        assert_eq!(default_frame_header.stream_id, STREAM_ID);
+        assert_eq!(
+            default_frame_header.to_string(),
+            "DATA len=0 version=0 flags=0xc0 term_offset=0 session_id=7 stream_id=1001 term_id=3 reserved_value=0"
+        );
+    }
+
+    #[test]
+    fn should_walk_frames_and_flag_torn_and_zeroed_frames() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let (inspector, log_file) = open_log(&tmp_dir);
+        let term = log_file.atomic_buffer(0, TERM_LENGTH);
+
+        term.put(0, header(37, 0, TERM_ID));
+        term.put_bytes(data_frame_header::LENGTH, b"hello log inspector");
+        term.put(64, header(40, 64, TERM_ID + 1));
+        term.put(192, header(-40, 192, TERM_ID));
+        term.put(256, header(32, 256, TERM_ID));
+
+        let meta_data = log_file.atomic_buffer(TERM_LENGTH * 3, LOG_META_DATA_LENGTH);
+        meta_data.put::<i64>(
+            *log_buffer_descriptor::TERM_TAIL_COUNTER_OFFSET,
+            ((TERM_ID as i64) << 32) | 288,
+        );
+
+        let entries = inspector.scan_partition(0, true);
+        assert_eq!(entries.len(), 5);
+
+        match entries[0] {
+            TermEntry::Frame { offset, header, issue } => {
+                assert_eq!(offset, 0);
+                assert_eq!(header.frame_length, 37);
+                assert_eq!(issue, None);
+                assert_eq!(frame_payload(&term, offset, &header, 5), b"hello");
+            },
+            _ => panic!("expected frame"),
+        }
+        assert_eq!(entries[1], TermEntry::Frame {
+            offset: 64,
+            header: FrameHeader::read(&term, 64),
+            issue: Some(FrameIssue::TermIdMismatch {
+                expected_term_id: TERM_ID
+            }),
+        });
+        assert_eq!(entries[2], TermEntry::Zeroed { offset: 128, length: 64 });
+        assert!(matches!(entries[3], TermEntry::Frame {
+            offset: 192,
+            issue: Some(FrameIssue::Uncommitted),
+            ..
+        }));
+        assert!(matches!(entries[4], TermEntry::Frame {
+            offset: 256,
+            issue: None,
+            ..
+        }));
+
+        let entries = inspector.scan_partition(0, false);
+        assert_eq!(entries.len(), 6);
+        assert_eq!(entries[5], TermEntry::Zeroed {
+            offset: 288,
+            length: TERM_LENGTH - 288
+        });
+    }
+
+    #[test]
+    fn should_stop_at_frame_exceeding_term() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let (inspector, log_file) = open_log(&tmp_dir);
+        let term = log_file.atomic_buffer(0, TERM_LENGTH);
+
+        term.put(0, header(TERM_LENGTH + 1, 0, TERM_ID));
+        term.put(64, header(32, 64, TERM_ID));
+
+        let entries = inspector.scan_partition(0, false);
+        assert_eq!(entries.len(), 1);
+        assert!(matches!(entries[0], TermEntry::Frame {
+            issue: Some(FrameIssue::ExceedsTerm),
+            ..
+        }));
+    }
+
+    #[test]
+    fn should_stop_at_garbage_frame_length() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let (inspector, log_file) = open_log(&tmp_dir);
+        let term = log_file.atomic_buffer(0, TERM_LENGTH);
+
+        for frame_length in [i32::MIN, i32::MAX, -TERM_LENGTH] {
+            term.put(0, header(32, 0, TERM_ID));
+            term.put(32, header(frame_length, 32, TERM_ID));
+
+            let entries = inspector.scan_partition(0, false);
+            assert_eq!(entries.len(), 2);
+            match entries[1] {
+                TermEntry::Frame { offset, header, issue } => {
+                    assert_eq!(issue, Some(FrameIssue::ExceedsTerm));
+                    let payload = frame_payload(&term, offset, &header, usize::MAX);
+                    assert_eq!(payload.len(), (TERM_LENGTH - 64) as usize);
+                },
+                _ => panic!("expected frame"),
+            }
+        }
+    }
+
+    #[test]
+    fn should_reject_log_shorter_than_its_layout() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let file_path = create_log(&tmp_dir, LOG_META_DATA_LENGTH - 1);
+
+        assert!(matches!(
+            LogInspector::open(file_path),
+            Err(AeronError::IllegalState(IllegalStateError::LogBufferFileTooShort { .. }))
+        ));
+    }
+}
@@ -0,0 +1,251 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::fs;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::channel_uri;
+use crate::loss_proxy::rules::{Direction, Rule};
+use crate::protocol::frame::FrameType;
+use crate::utils::errors::{AeronError, GenericError, IllegalArgumentError};
+
+/// Datagrams received on the listen address are forwarded to the target and replies sent back to the client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Route {
+    pub listen: SocketAddr,
+    pub target: SocketAddr,
+}
+
+/**
+ * Routes and rules of a LossProxy read from a config file with one statement per line, '#' starts a comment:
+ * <pre>
+ *   route 127.0.0.1:40124 127.0.0.1:40125
+ *   seed 42
+ *   drop type=data rate=0.05 stream=1001
+ *   delay type=sm,nak delay=20ms jitter=5ms rate=0.5
+ *   duplicate type=data copies=1 rate=0.01
+ *   reorder type=data hold=10ms rate=0.02 dir=forward
+ * </pre>
+ * Rule options are type (comma separated frame type names), stream, session, dir (forward or reverse) and rate,
+ * besides the options of the action. Durations take ns, us, ms or s suffixes.
+ */
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProxyConfig {
+    pub routes: Vec<Route>,
+    pub rules: Vec<Rule>,
+    /// Seed of the random number generator to make a run repeatable.
+    pub seed: Option<u64>,
+}
+
+impl ProxyConfig {
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, AeronError> {
+        let text = fs::read_to_string(path).map_err(|err| GenericError::Custom(err.to_string()))?;
+
+        Self::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, AeronError> {
+        let mut config = Self::default();
+
+        for (index, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            config
+                .parse_statement(line)
+                .map_err(|reason| IllegalArgumentError::InvalidProxyConfig { line: index + 1, reason })?;
+        }
+
+        Ok(config)
+    }
+
+    fn parse_statement(&mut self, line: &str) -> Result<(), String> {
+        let mut words = line.split_whitespace();
+        let keyword = words.next().unwrap_or_default();
+        let args: Vec<&str> = words.collect();
+
+        match keyword {
+            "route" => {
+                if args.len() != 2 {
+                    return Err(String::from("route expects <listen address> <target address>"));
+                }
+                self.routes.push(Route {
+                    listen: parse_address(args[0])?,
+                    target: parse_address(args[1])?,
+                });
+            },
+            "seed" => {
+                let seed = args.first().ok_or_else(|| String::from("seed expects a number"))?;
+                self.seed = Some(seed.parse().map_err(|_| format!("invalid seed '{}'", seed))?);
+            },
+            "drop" | "delay" | "duplicate" | "reorder" => self.rules.push(parse_rule(keyword, &args)?),
+            _ => return Err(format!("unknown statement '{}'", keyword)),
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_address(value: &str) -> Result<SocketAddr, String> {
+    value.parse().map_err(|_| format!("invalid address '{}'", value))
+}
+
+fn parse_duration(name: &str, value: Option<&str>, default: Duration) -> Result<Duration, String> {
+    match value {
+        Some(value) => channel_uri::parse_duration(value).map_err(|reason| format!("invalid {} '{}': {}", name, value, reason)),
+        None => Ok(default),
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("invalid {} '{}'", name, value))
+}
+
+fn parse_frame_type(name: &str) -> Result<FrameType, String> {
+    let frame_types = [
+        FrameType::Pad,
+        FrameType::Data,
+        FrameType::Nak,
+        FrameType::StatusMessage,
+        FrameType::Error,
+        FrameType::Setup,
+        FrameType::RttMeasurement,
+        FrameType::Resolution,
+        FrameType::ResponseSetup,
+    ];
+
+    frame_types
+        .iter()
+        .find(|frame_type| frame_type.name().eq_ignore_ascii_case(name))
+        .copied()
+        .ok_or_else(|| format!("unknown frame type '{}'", name))
+}
+
+fn parse_rule(action: &str, args: &[&str]) -> Result<Rule, String> {
+    let mut options = Vec::with_capacity(args.len());
+    for arg in args {
+        let (name, value) = arg
+            .split_once('=')
+            .ok_or_else(|| format!("option '{}' must be <name>=<value>", arg))?;
+        options.push((name, value));
+    }
+    let option = |name: &str| options.iter().find(|(option, _)| *option == name).map(|(_, value)| *value);
+
+    let mut rule = match action {
+        "drop" => Rule::drop_frames(),
+        "delay" => Rule::delay(
+            parse_duration("delay", option("delay"), Duration::from_millis(10))?,
+            parse_duration("jitter", option("jitter"), Duration::from_millis(0))?,
+        ),
+        "duplicate" => Rule::duplicate(option("copies").map_or(Ok(1), |copies| parse_number("copies", copies))?),
+        _ => Rule::reorder(parse_duration("hold", option("hold"), Duration::from_millis(10))?),
+    };
+
+    for (name, value) in &options {
+        match *name {
+            "rate" => {
+                rule.with_rate(parse_number("rate", value)?).map_err(|err| err.to_string())?;
+            },
+            "type" => {
+                let frame_types = value.split(',').map(parse_frame_type).collect::<Result<Vec<_>, _>>()?;
+                rule.with_frame_types(&frame_types);
+            },
+            "stream" => {
+                rule.with_stream_id(parse_number("stream", value)?);
+            },
+            "session" => {
+                rule.with_session_id(parse_number("session", value)?);
+            },
+            "dir" => {
+                rule.with_direction(match *value {
+                    "forward" => Direction::Forward,
+                    "reverse" => Direction::Reverse,
+                    _ => return Err(format!("dir must be forward or reverse, got '{}'", value)),
+                });
+            },
+            "delay" | "jitter" if action == "delay" => {},
+            "copies" if action == "duplicate" => {},
+            "hold" if action == "reorder" => {},
+            _ => return Err(format!("unknown option '{}' for {}", name, action)),
+        }
+    }
+
+    Ok(rule)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loss_proxy::rules::Action;
+
+    #[test]
+    fn should_parse_routes_and_rules() {
+        let config = ProxyConfig::parse(
+            "# proxy between two drivers\n\
+             route 127.0.0.1:40124 127.0.0.1:40125\n\
+             seed 42\n\
+             \n\
+             drop type=data rate=0.05 stream=1001   # lose 5% of data\n\
+             delay type=SM,nak delay=20ms jitter=5ms dir=reverse\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.routes,
+            vec![Route {
+                listen: "127.0.0.1:40124".parse().unwrap(),
+                target: "127.0.0.1:40125".parse().unwrap(),
+            }]
+        );
+        assert_eq!(config.seed, Some(42));
+
+        let mut drop = Rule::drop_frames();
+        drop.with_rate(0.05)
+            .unwrap()
+            .with_frame_types(&[FrameType::Data])
+            .with_stream_id(1001);
+        let mut delay = Rule::new(Action::Delay {
+            delay: Duration::from_millis(20),
+            jitter: Duration::from_millis(5),
+        });
+        delay
+            .with_frame_types(&[FrameType::StatusMessage, FrameType::Nak])
+            .with_direction(Direction::Reverse);
+        assert_eq!(config.rules, vec![drop, delay]);
+    }
+
+    #[test]
+    fn should_report_line_of_invalid_statement() {
+        for (text, line) in &[
+            ("route 127.0.0.1:1", 1),
+            ("seed 1\ndrop rate=2", 2),
+            ("\n\nduplicate type=foo", 3),
+            ("drop copies=1", 1),
+            ("shuffle", 1),
+        ] {
+            match ProxyConfig::parse(text) {
+                Err(AeronError::IllegalArgument(IllegalArgumentError::InvalidProxyConfig { line: error_line, .. })) => {
+                    assert_eq!(error_line, *line, "{}", text)
+                },
+                result => panic!("{} should be rejected, got {:?}", text, result),
+            }
+        }
+    }
+}
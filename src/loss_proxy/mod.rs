@@ -0,0 +1,514 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, UdpSocket};
+use std::ops::Range;
+use std::time::{Duration, Instant};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::concurrent::agent_runner::Agent;
+use crate::concurrent::atomic_buffer::AtomicBuffer;
+use crate::concurrent::logbuffer::frame_descriptor::FRAME_ALIGNMENT;
+use crate::packet_dissector::MAX_UDP_PAYLOAD_LENGTH;
+use crate::protocol::frame::{self, Frame};
+use crate::utils::bit_utils;
+use crate::utils::errors::{AeronError, GenericError};
+use crate::utils::types::Index;
+
+pub mod config;
+pub mod rules;
+
+use config::{ProxyConfig, Route};
+use rules::{Action, Direction, Rule};
+
+/// Datagrams read from a socket in one duty cycle before moving on to the next socket.
+const MAX_DATAGRAMS_PER_POLL: usize = 64;
+
+/// Counts of what the proxy did to frames, datagrams which can't be decoded are forwarded unchanged.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProxyStats {
+    pub datagrams_received: u64,
+    pub undecoded_datagrams: u64,
+    pub frames_received: u64,
+    pub frames_forwarded: u64,
+    pub frames_dropped: u64,
+    pub frames_delayed: u64,
+    pub frames_duplicated: u64,
+    pub frames_reordered: u64,
+}
+
+/// Route, client and direction a datagram is sent on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct Path {
+    route: usize,
+    client: SocketAddr,
+    direction: Direction,
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Scheduled {
+    due: Instant,
+    sequence: u64,
+    path: Path,
+    datagram: Vec<u8>,
+}
+
+struct RouteSockets {
+    route: Route,
+    listen_socket: UdpSocket,
+    /// Socket per client to send to the target, so replies of the target can be sent back to the right client.
+    client_sockets: HashMap<SocketAddr, UdpSocket>,
+}
+
+/**
+ * UDP proxy which forwards datagrams between clients and the target of each route and applies loss, delay,
+ * duplication and reordering to the frames matching its rules, e.g. to test two drivers on one host under
+ * network faults.
+ *
+ * Each frame of a datagram is handled on its own, so a batch of data frames may be split. The proxy is an Agent
+ * to run with AgentRunner, rules can be changed while it runs through the Mutex given to the runner.
+ */
+pub struct LossProxy {
+    routes: Vec<RouteSockets>,
+    rules: Vec<Rule>,
+    rng: StdRng,
+    scheduled: BinaryHeap<Reverse<Scheduled>>,
+    held: Vec<Scheduled>,
+    sequence: u64,
+    stats: ProxyStats,
+    // FRAME_ALIGNMENT longer than a datagram to receive at an aligned offset, see receive_buffer()
+    receive_buffer: Vec<u8>,
+}
+
+impl LossProxy {
+    /**
+     * Bind the listen sockets of the routes.
+     *
+     * @param routes to forward.
+     * @param rules  applied in order to each frame.
+     * @param seed   of the random number generator, random when None.
+     */
+    pub fn new(routes: &[Route], rules: Vec<Rule>, seed: Option<u64>) -> Result<Self, AeronError> {
+        let mut route_sockets = Vec::with_capacity(routes.len());
+
+        for route in routes {
+            let listen_socket = UdpSocket::bind(route.listen).map_err(|err| socket_error(route.listen, err))?;
+            listen_socket
+                .set_nonblocking(true)
+                .map_err(|err| socket_error(route.listen, err))?;
+
+            route_sockets.push(RouteSockets {
+                route: *route,
+                listen_socket,
+                client_sockets: HashMap::new(),
+            });
+        }
+
+        Ok(Self {
+            routes: route_sockets,
+            rules,
+            rng: seed.map_or_else(StdRng::from_entropy, StdRng::seed_from_u64),
+            scheduled: BinaryHeap::new(),
+            held: Vec::new(),
+            sequence: 0,
+            stats: ProxyStats::default(),
+            receive_buffer: vec![0; (MAX_UDP_PAYLOAD_LENGTH + FRAME_ALIGNMENT) as usize],
+        })
+    }
+
+    pub fn from_config(config: &ProxyConfig) -> Result<Self, AeronError> {
+        Self::new(&config.routes, config.rules.clone(), config.seed)
+    }
+
+    /// Local address of the listen socket of the route, e.g. when the route was bound to port 0.
+    pub fn listen_address(&self, route: usize) -> Option<SocketAddr> {
+        self.routes
+            .get(route)
+            .and_then(|route| route.listen_socket.local_addr().ok())
+    }
+
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    /// Replace the rules, frames already delayed or held are still sent.
+    pub fn set_rules(&mut self, rules: Vec<Rule>) {
+        self.rules = rules;
+    }
+
+    pub fn stats(&self) -> ProxyStats {
+        self.stats
+    }
+
+    fn poll_route(&mut self, route: usize, now: Instant) -> Result<i32, AeronError> {
+        let mut buffer = self.receive_buffer();
+        let mut work_count = 0;
+
+        for _ in 0..MAX_DATAGRAMS_PER_POLL {
+            let received = self.routes[route].listen_socket.recv_from(buffer.as_mutable_slice());
+            match received {
+                Ok((length, client)) => {
+                    self.client_socket(route, client)?;
+                    let path = Path {
+                        route,
+                        client,
+                        direction: Direction::Forward,
+                    };
+                    self.on_datagram(path, buffer, length, now)?;
+                    work_count += 1;
+                }
+                Err(err) if is_transient(&err) => break,
+                Err(err) => return Err(socket_error(self.routes[route].route.listen, err)),
+            }
+        }
+
+        let clients: Vec<SocketAddr> = self.routes[route].client_sockets.keys().copied().collect();
+        for client in clients {
+            for _ in 0..MAX_DATAGRAMS_PER_POLL {
+                let received = self.routes[route].client_sockets[&client].recv(buffer.as_mutable_slice());
+                match received {
+                    Ok(length) => {
+                        let path = Path {
+                            route,
+                            client,
+                            direction: Direction::Reverse,
+                        };
+                        self.on_datagram(path, buffer, length, now)?;
+                        work_count += 1;
+                    }
+                    Err(err) if is_transient(&err) => break,
+                    Err(err) => return Err(socket_error(self.routes[route].route.target, err)),
+                }
+            }
+        }
+
+        Ok(work_count)
+    }
+
+    /// Frames are decoded in place, so datagrams are received at an offset aligned for the flyweights.
+    fn receive_buffer(&mut self) -> AtomicBuffer {
+        let offset = self.receive_buffer.as_ptr().align_offset(FRAME_ALIGNMENT as usize);
+        AtomicBuffer::wrap_slice(&mut self.receive_buffer[offset..offset + MAX_UDP_PAYLOAD_LENGTH as usize])
+    }
+
+    fn client_socket(&mut self, route: usize, client: SocketAddr) -> Result<(), AeronError> {
+        let route_sockets = &mut self.routes[route];
+        if route_sockets.client_sockets.contains_key(&client) {
+            return Ok(());
+        }
+
+        let target = route_sockets.route.target;
+        let bind_address = match target.ip() {
+            IpAddr::V4(_) => SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0),
+            IpAddr::V6(_) => SocketAddr::new(IpAddr::V6(Ipv6Addr::UNSPECIFIED), 0),
+        };
+        let socket = UdpSocket::bind(bind_address).map_err(|err| socket_error(target, err))?;
+        socket.connect(target).map_err(|err| socket_error(target, err))?;
+        socket.set_nonblocking(true).map_err(|err| socket_error(target, err))?;
+
+        route_sockets.client_sockets.insert(client, socket);
+        Ok(())
+    }
+
+    fn on_datagram(&mut self, path: Path, buffer: AtomicBuffer, length: usize, now: Instant) -> Result<(), AeronError> {
+        self.stats.datagrams_received += 1;
+        let datagram = buffer.as_sub_slice(0, length as Index);
+
+        let frames = match frame::decode_frames(&buffer, 0, length as Index) {
+            Ok(frames) => frames,
+            Err(_) => {
+                self.stats.undecoded_datagrams += 1;
+                return self.send(path, datagram);
+            }
+        };
+
+        let mut forwarded = Vec::with_capacity(length);
+        let mut duplicates = Vec::new();
+        let mut offset = 0;
+
+        for frame in &frames {
+            let range = frame_range(&frames, frame, offset, length);
+            offset = range.end;
+            self.stats.frames_received += 1;
+            let bytes = &datagram[range];
+
+            match rules::select_action(&self.rules, &mut self.rng, path.direction, frame).cloned() {
+                None => {
+                    self.stats.frames_forwarded += 1;
+                    forwarded.extend_from_slice(bytes);
+                }
+                Some(Action::Drop) => self.stats.frames_dropped += 1,
+                Some(Action::Delay { delay, jitter }) => {
+                    self.stats.frames_delayed += 1;
+                    let jitter_ns = self.rng.gen_range(0..=jitter.as_nanos() as u64);
+                    let due = now + delay + Duration::from_nanos(jitter_ns);
+                    let scheduled = self.schedule(path, due, bytes);
+                    self.scheduled.push(Reverse(scheduled));
+                }
+                Some(Action::Duplicate { copies }) => {
+                    self.stats.frames_duplicated += 1;
+                    forwarded.extend_from_slice(bytes);
+                    for _ in 0..copies {
+                        duplicates.push(bytes.to_vec());
+                    }
+                }
+                Some(Action::Reorder { max_hold }) => {
+                    self.stats.frames_reordered += 1;
+                    let held = self.schedule(path, now + max_hold, bytes);
+                    self.held.push(held);
+                }
+            }
+        }
+
+        if !forwarded.is_empty() {
+            self.send(path, &forwarded)?;
+            self.release_held(|held| held.path == path)?;
+        }
+
+        for duplicate in duplicates {
+            self.send(path, &duplicate)?;
+        }
+
+        Ok(())
+    }
+
+    fn schedule(&mut self, path: Path, due: Instant, bytes: &[u8]) -> Scheduled {
+        self.sequence += 1;
+
+        Scheduled {
+            due,
+            sequence: self.sequence,
+            path,
+            datagram: bytes.to_vec(),
+        }
+    }
+
+    fn release_held<F: Fn(&Scheduled) -> bool>(&mut self, is_released: F) -> Result<i32, AeronError> {
+        let (released, held): (Vec<Scheduled>, Vec<Scheduled>) = self.held.drain(..).partition(|held| is_released(held));
+        self.held = held;
+
+        for scheduled in &released {
+            self.send(scheduled.path, &scheduled.datagram)?;
+        }
+
+        Ok(released.len() as i32)
+    }
+
+    fn release_due(&mut self, now: Instant) -> Result<i32, AeronError> {
+        let mut work_count = self.release_held(|held| held.due <= now)?;
+
+        while self.scheduled.peek().is_some_and(|Reverse(scheduled)| scheduled.due <= now) {
+            if let Some(Reverse(scheduled)) = self.scheduled.pop() {
+                self.send(scheduled.path, &scheduled.datagram)?;
+                work_count += 1;
+            }
+        }
+
+        Ok(work_count)
+    }
+
+    fn send(&self, path: Path, datagram: &[u8]) -> Result<(), AeronError> {
+        let route = &self.routes[path.route];
+        let result = match path.direction {
+            Direction::Forward => match route.client_sockets.get(&path.client) {
+                Some(socket) => socket.send(datagram),
+                None => return Ok(()),
+            },
+            Direction::Reverse => route.listen_socket.send_to(datagram, path.client),
+        };
+
+        match result {
+            Ok(_) => Ok(()),
+            // Like the network the proxy stands in for, it drops what it can't send right now
+            Err(err) if is_transient(&err) || err.kind() == io::ErrorKind::ConnectionRefused => Ok(()),
+            Err(err) => Err(socket_error(route.route.target, err)),
+        }
+    }
+}
+
+impl Agent for LossProxy {
+    fn on_start(&mut self) -> Result<(), AeronError> {
+        Ok(())
+    }
+
+    fn do_work(&mut self) -> Result<i32, AeronError> {
+        let now = Instant::now();
+        let mut work_count = self.release_due(now)?;
+
+        for route in 0..self.routes.len() {
+            work_count += self.poll_route(route, now)?;
+        }
+
+        Ok(work_count)
+    }
+
+    fn on_close(&mut self) -> Result<(), AeronError> {
+        // Frames still delayed or held are lost as if the link went down
+        self.scheduled.clear();
+        self.held.clear();
+        Ok(())
+    }
+}
+
+/// Bytes of the frame in the datagram: data frames are aligned within their batch, other frames fill it.
+fn frame_range(frames: &[Frame], frame: &Frame, offset: usize, length: usize) -> Range<usize> {
+    if frames.len() == 1 {
+        return offset..length;
+    }
+
+    let aligned_length = bit_utils::align(frame.encoded_length(), FRAME_ALIGNMENT) as usize;
+    offset..(offset + aligned_length).min(length)
+}
+
+fn is_transient(err: &io::Error) -> bool {
+    matches!(err.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::Interrupted)
+}
+
+fn socket_error(address: SocketAddr, error: io::Error) -> AeronError {
+    GenericError::SocketError {
+        address: address.to_string(),
+        error,
+    }
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::concurrent::atomic_buffer::AlignedBuffer;
+    use crate::concurrent::logbuffer::data_frame_header;
+    use crate::protocol::data_header_flyweight::{self, DataHeaderFlyweight};
+    use crate::protocol::frame::FrameType;
+    use crate::protocol::status_message_flyweight::StatusMessageFlyweight;
+
+    const SESSION_ID: i32 = 11;
+    const STREAM_ID: i32 = 1001;
+
+    fn encode<F: FnOnce(AtomicBuffer) -> Index>(write: F) -> Vec<u8> {
+        let aligned = AlignedBuffer::with_capacity(256);
+        let buffer = AtomicBuffer::from_aligned(&aligned);
+        let length = write(buffer);
+
+        buffer.as_sub_slice(0, length).to_vec()
+    }
+
+    fn data(term_offset: i32) -> Vec<u8> {
+        encode(|buffer| {
+            let mut frame = DataHeaderFlyweight::new(buffer, 0);
+            frame.header_mut().init(
+                data_frame_header::HDR_TYPE_DATA,
+                data_header_flyweight::BEGIN_AND_END_FLAGS,
+                0,
+            );
+            frame.set_session_id(SESSION_ID);
+            frame.set_stream_id(STREAM_ID);
+            frame.set_term_offset(term_offset);
+            frame.set_data(b"payload");
+            frame.header().frame_length()
+        })
+    }
+
+    fn status_message() -> Vec<u8> {
+        encode(|buffer| {
+            let mut frame = StatusMessageFlyweight::new(buffer, 0);
+            frame
+                .header_mut()
+                .init(data_frame_header::HDR_TYPE_SM, 0, StatusMessageFlyweight::header_length());
+            frame.set_session_id(SESSION_ID);
+            frame.set_stream_id(STREAM_ID);
+            frame.set_receiver_window(128 * 1024);
+            StatusMessageFlyweight::header_length()
+        })
+    }
+
+    fn bind_local() -> UdpSocket {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        socket.set_read_timeout(Some(Duration::from_millis(10))).unwrap();
+        socket
+    }
+
+    fn proxy_to(target: &UdpSocket, rules: Vec<Rule>) -> LossProxy {
+        let route = Route {
+            listen: "127.0.0.1:0".parse().unwrap(),
+            target: target.local_addr().unwrap(),
+        };
+        LossProxy::new(&[route], rules, Some(7)).unwrap()
+    }
+
+    /// Run the proxy until a datagram is received on the socket or a second passed.
+    fn receive(proxy: &mut LossProxy, socket: &UdpSocket) -> Option<Vec<u8>> {
+        let mut buffer = [0_u8; 1024];
+        let deadline = Instant::now() + Duration::from_secs(1);
+
+        while Instant::now() < deadline {
+            proxy.do_work().unwrap();
+            if let Ok((length, _)) = socket.recv_from(&mut buffer) {
+                return Some(buffer[..length].to_vec());
+            }
+        }
+
+        None
+    }
+
+    #[test]
+    fn should_drop_data_frames_and_forward_status_messages() {
+        let target = bind_local();
+        let client = bind_local();
+        let mut rule = Rule::drop_frames();
+        rule.with_frame_types(&[FrameType::Data]).with_stream_id(STREAM_ID);
+        let mut proxy = proxy_to(&target, vec![rule]);
+        let listen = proxy.listen_address(0).unwrap();
+
+        client.send_to(&data(0), listen).unwrap();
+        client.send_to(&status_message(), listen).unwrap();
+
+        assert_eq!(receive(&mut proxy, &target), Some(status_message()));
+
+        let stats = proxy.stats();
+        assert_eq!(stats.datagrams_received, 2);
+        assert_eq!(stats.frames_dropped, 1);
+        assert_eq!(stats.frames_forwarded, 1);
+    }
+
+    #[test]
+    fn should_delay_replies_of_the_target() {
+        let target = bind_local();
+        let client = bind_local();
+        let mut proxy = proxy_to(&target, Vec::new());
+        let listen = proxy.listen_address(0).unwrap();
+
+        client.send_to(&data(0), listen).unwrap();
+        let mut buffer = [0_u8; 1024];
+        proxy.do_work().unwrap();
+        let (_, proxy_client) = target.recv_from(&mut buffer).unwrap();
+
+        let mut rule = Rule::delay(Duration::from_millis(50), Duration::from_millis(0));
+        rule.with_direction(Direction::Reverse);
+        proxy.set_rules(vec![rule]);
+
+        let sent = Instant::now();
+        target.send_to(&status_message(), proxy_client).unwrap();
+
+        assert_eq!(receive(&mut proxy, &client), Some(status_message()));
+        assert!(sent.elapsed() >= Duration::from_millis(50));
+        assert_eq!(proxy.stats().frames_delayed, 1);
+    }
+}
@@ -0,0 +1,162 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::time::Duration;
+
+use rand::Rng;
+
+use crate::packet_dissector::SessionKey;
+use crate::protocol::frame::{Frame, FrameType};
+use crate::utils::errors::{AeronError, IllegalArgumentError};
+
+/// Direction of a datagram through the proxy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+    /// From a client to the target of the route, e.g. data and setup frames from a publication.
+    Forward,
+    /// From the target back to the client, e.g. status messages and NAKs from a subscription.
+    Reverse,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Action {
+    Drop,
+    /// Send the frame after the delay plus a random jitter of up to the given duration.
+    Delay { delay: Duration, jitter: Duration },
+    /// Send the frame and the given number of copies of it.
+    Duplicate { copies: u32 },
+    /// Hold the frame until the next datagram on the same path was sent, at most for the given duration.
+    Reorder { max_hold: Duration },
+}
+
+/**
+ * Action applied to matching frames at a rate. A rule without frame types, stream, session or direction
+ * matches every frame. Note heartbeats are data frames too.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    action: Action,
+    rate: f64,
+    frame_types: Vec<FrameType>,
+    stream_id: Option<i32>,
+    session_id: Option<i32>,
+    direction: Option<Direction>,
+}
+
+impl Rule {
+    pub fn new(action: Action) -> Self {
+        Self {
+            action,
+            rate: 1.0,
+            frame_types: Vec::new(),
+            stream_id: None,
+            session_id: None,
+            direction: None,
+        }
+    }
+
+    pub fn drop_frames() -> Self {
+        Self::new(Action::Drop)
+    }
+
+    pub fn delay(delay: Duration, jitter: Duration) -> Self {
+        Self::new(Action::Delay { delay, jitter })
+    }
+
+    pub fn duplicate(copies: u32) -> Self {
+        Self::new(Action::Duplicate { copies })
+    }
+
+    pub fn reorder(max_hold: Duration) -> Self {
+        Self::new(Action::Reorder { max_hold })
+    }
+
+    pub fn action(&self) -> &Action {
+        &self.action
+    }
+
+    pub fn rate(&self) -> f64 {
+        self.rate
+    }
+
+    /**
+     * Set the probability of applying the action to a matching frame.
+     *
+     * @param rate between 0.0 (never) and 1.0 (always, the default).
+     * @return this for a fluent API.
+     */
+    pub fn with_rate(&mut self, rate: f64) -> Result<&mut Self, AeronError> {
+        if !(0.0..=1.0).contains(&rate) {
+            return Err(IllegalArgumentError::InvalidProxyRule(format!("rate {} must be within 0.0 and 1.0", rate)).into());
+        }
+
+        self.rate = rate;
+        Ok(self)
+    }
+
+    /// Match only frames of the types, all types when empty.
+    pub fn with_frame_types(&mut self, frame_types: &[FrameType]) -> &mut Self {
+        self.frame_types = frame_types.to_vec();
+        self
+    }
+
+    pub fn with_stream_id(&mut self, stream_id: i32) -> &mut Self {
+        self.stream_id = Some(stream_id);
+        self
+    }
+
+    pub fn with_session_id(&mut self, session_id: i32) -> &mut Self {
+        self.session_id = Some(session_id);
+        self
+    }
+
+    pub fn with_direction(&mut self, direction: Direction) -> &mut Self {
+        self.direction = Some(direction);
+        self
+    }
+
+    pub fn matches(&self, direction: Direction, frame: &Frame) -> bool {
+        if self.direction.is_some_and(|rule_direction| rule_direction != direction) {
+            return false;
+        }
+
+        if !self.frame_types.is_empty() && !self.frame_types.contains(&frame.frame_type()) {
+            return false;
+        }
+
+        if self.stream_id.is_none() && self.session_id.is_none() {
+            return true;
+        }
+
+        SessionKey::of(frame).is_some_and(|key| {
+            self.stream_id.is_none_or(|stream_id| stream_id == key.stream_id)
+                && self.session_id.is_none_or(|session_id| session_id == key.session_id)
+        })
+    }
+}
+
+/**
+ * Action for the frame: the first matching rule is applied at its rate, when it's not applied the following
+ * rules are tried.
+ *
+ * @return action to apply or None to forward the frame unchanged.
+ */
+pub fn select_action<'a, R: Rng>(rules: &'a [Rule], rng: &mut R, direction: Direction, frame: &Frame) -> Option<&'a Action> {
+    rules
+        .iter()
+        .find(|rule| rule.matches(direction, frame) && (rule.rate >= 1.0 || rng.gen::<f64>() < rule.rate))
+        .map(|rule| &rule.action)
+}
@@ -0,0 +1,431 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+
+use crate::concurrent::atomic_buffer::{AlignedBuffer, AtomicBuffer};
+use crate::concurrent::logbuffer::frame_descriptor::FRAME_ALIGNMENT;
+use crate::protocol::frame::{self, Frame};
+use crate::utils::bit_utils;
+use crate::utils::errors::AeronError;
+use crate::utils::types::Index;
+
+/// Largest UDP payload, datagrams are copied into an aligned buffer of this length before decoding.
+pub const MAX_UDP_PAYLOAD_LENGTH: Index = 64 * 1024;
+
+/// Session and stream a frame belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SessionKey {
+    pub session_id: i32,
+    pub stream_id: i32,
+}
+
+impl SessionKey {
+    /// Session of the frame, None for frames which don't belong to a session like resolution frames.
+    pub fn of(frame: &Frame) -> Option<Self> {
+        let (session_id, stream_id) = match frame {
+            Frame::Pad(frame) | Frame::Data(frame) | Frame::Heartbeat(frame) => (frame.session_id(), frame.stream_id()),
+            Frame::Nak(frame) => (frame.session_id(), frame.stream_id()),
+            Frame::StatusMessage(frame) => (frame.session_id(), frame.stream_id()),
+            Frame::Error(frame) => (frame.session_id(), frame.stream_id()),
+            Frame::Setup(frame) => (frame.session_id(), frame.stream_id()),
+            Frame::RttMeasurement(frame) => (frame.session_id(), frame.stream_id()),
+            Frame::ResponseSetup(frame) => (frame.session_id(), frame.stream_id()),
+            Frame::Resolution(_) => return None,
+        };
+
+        Some(Self { session_id, stream_id })
+    }
+}
+
+/// Range of a term requested for retransmission by a NAK.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NakRange {
+    pub term_id: i32,
+    pub term_offset: i32,
+    pub length: i32,
+}
+
+impl Display for NakRange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}+{}", self.term_id, self.term_offset, self.length)
+    }
+}
+
+/**
+ * Statistics of a session collected from its frames.
+ *
+ * Gaps and retransmits are found by comparing each data or pad frame with the end of the highest frame seen so
+ * far: a frame beyond it leaves a gap, a frame below it is a retransmit. Gaps across term boundaries are only
+ * measured exactly once the term length is known from a setup frame.
+ */
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SessionStats {
+    pub data_frames: u64,
+    /// Payload bytes of data frames, headers excluded.
+    pub data_bytes: u64,
+    pub pad_frames: u64,
+    pub heartbeats: u64,
+    pub end_of_stream: bool,
+    pub setups: u64,
+    pub initial_term_id: Option<i32>,
+    pub term_length: Option<i32>,
+    pub gaps: u64,
+    pub gap_bytes: u64,
+    pub retransmits: u64,
+    pub retransmit_bytes: u64,
+    pub naks: u64,
+    /// Requested ranges with the number of NAKs requesting them.
+    pub nak_ranges: BTreeMap<NakRange, u64>,
+    pub status_messages: u64,
+    pub min_receiver_window: Option<i32>,
+    pub max_receiver_window: Option<i32>,
+    pub last_receiver_window: Option<i32>,
+    pub rtt_measurements: u64,
+    pub errors: u64,
+    /// Term id and offset following the highest data or pad frame.
+    next_term_position: Option<(i32, i32)>,
+}
+
+impl SessionStats {
+    fn on_term_frame(&mut self, term_id: i32, term_offset: i32, frame_length: i32) {
+        let aligned_length = bit_utils::align(frame_length, FRAME_ALIGNMENT);
+
+        let (next_term_id, next_term_offset) = match self.next_term_position {
+            Some(next_term_position) => next_term_position,
+            None => {
+                self.advance_to(term_id, term_offset + aligned_length);
+                return;
+            },
+        };
+
+        let term_delta = term_id.wrapping_sub(next_term_id);
+        if term_delta < 0 || (term_delta == 0 && term_offset < next_term_offset) {
+            self.retransmits += 1;
+            self.retransmit_bytes += aligned_length as u64;
+            return;
+        }
+
+        let gap_bytes = if term_delta == 0 {
+            (term_offset - next_term_offset) as i64
+        } else {
+            match self.term_length {
+                Some(term_length) => {
+                    (term_length - next_term_offset) as i64 + (term_delta - 1) as i64 * term_length as i64 + term_offset as i64
+                },
+                None => term_offset as i64,
+            }
+        };
+
+        if gap_bytes > 0 {
+            self.gaps += 1;
+            self.gap_bytes += gap_bytes as u64;
+        }
+
+        self.advance_to(term_id, term_offset + aligned_length);
+    }
+
+    fn advance_to(&mut self, term_id: i32, term_offset: i32) {
+        self.next_term_position = match self.term_length {
+            Some(term_length) if term_offset >= term_length => Some((term_id.wrapping_add(1), 0)),
+            _ => Some((term_id, term_offset)),
+        };
+    }
+
+    fn on_receiver_window(&mut self, receiver_window: i32) {
+        self.min_receiver_window = Some(
+            self.min_receiver_window
+                .map_or(receiver_window, |min| min.min(receiver_window)),
+        );
+        self.max_receiver_window = Some(
+            self.max_receiver_window
+                .map_or(receiver_window, |max| max.max(receiver_window)),
+        );
+        self.last_receiver_window = Some(receiver_window);
+    }
+}
+
+/**
+ * Decodes UDP payloads into frames and collects per session statistics, e.g. for datagrams read from a capture
+ * with PcapReader.
+ */
+pub struct PacketDissector {
+    aligned_buffer: AlignedBuffer,
+    sessions: BTreeMap<SessionKey, SessionStats>,
+    datagrams: u64,
+    malformed_datagrams: u64,
+}
+
+impl Default for PacketDissector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PacketDissector {
+    pub fn new() -> Self {
+        Self {
+            aligned_buffer: AlignedBuffer::with_capacity(MAX_UDP_PAYLOAD_LENGTH),
+            sessions: BTreeMap::new(),
+            datagrams: 0,
+            malformed_datagrams: 0,
+        }
+    }
+
+    /**
+     * Decode the frames of a datagram and update the statistics of their sessions.
+     *
+     * @param payload  of the UDP datagram.
+     * @param on_frame called with each frame of the datagram.
+     * @return number of frames or error if the datagram is not valid, frames before the error are not reported.
+     */
+    pub fn dissect<F: FnMut(&Frame)>(&mut self, payload: &[u8], mut on_frame: F) -> Result<usize, AeronError> {
+        self.datagrams += 1;
+
+        // Frames are decoded from a copy so they are aligned as in a socket receive buffer
+        let length = payload.len().min(MAX_UDP_PAYLOAD_LENGTH as usize);
+        let buffer = AtomicBuffer::from_aligned(&self.aligned_buffer);
+        buffer.put_bytes(0, &payload[..length]);
+
+        let frames = match frame::decode_frames(&buffer, 0, length as Index) {
+            Ok(frames) => frames,
+            Err(err) => {
+                self.malformed_datagrams += 1;
+                return Err(err);
+            },
+        };
+
+        for frame in &frames {
+            self.on_frame(frame);
+            on_frame(frame);
+        }
+
+        Ok(frames.len())
+    }
+
+    fn on_frame(&mut self, frame: &Frame) {
+        let key = match SessionKey::of(frame) {
+            Some(key) => key,
+            None => return,
+        };
+        let stats = self.sessions.entry(key).or_default();
+
+        match frame {
+            Frame::Data(frame) => {
+                stats.data_frames += 1;
+                stats.data_bytes += frame.data_length() as u64;
+                stats.on_term_frame(frame.term_id(), frame.term_offset(), frame.header().frame_length());
+            },
+            Frame::Pad(frame) => {
+                stats.pad_frames += 1;
+                stats.on_term_frame(frame.term_id(), frame.term_offset(), frame.header().frame_length());
+            },
+            Frame::Heartbeat(frame) => {
+                stats.heartbeats += 1;
+                stats.end_of_stream |= frame.is_end_of_stream();
+            },
+            Frame::Setup(frame) => {
+                stats.setups += 1;
+                stats.initial_term_id = Some(frame.initial_term_id());
+                stats.term_length = Some(frame.term_length());
+                if stats.next_term_position.is_none() {
+                    stats.next_term_position = Some((frame.action_term_id(), frame.term_offset()));
+                }
+            },
+            Frame::Nak(frame) => {
+                stats.naks += 1;
+                *stats
+                    .nak_ranges
+                    .entry(NakRange {
+                        term_id: frame.term_id(),
+                        term_offset: frame.term_offset(),
+                        length: frame.length(),
+                    })
+                    .or_default() += 1;
+            },
+            Frame::StatusMessage(frame) => {
+                stats.status_messages += 1;
+                stats.on_receiver_window(frame.receiver_window());
+            },
+            Frame::RttMeasurement(_) => stats.rtt_measurements += 1,
+            Frame::Error(_) => stats.errors += 1,
+            Frame::Resolution(_) | Frame::ResponseSetup(_) => {},
+        }
+    }
+
+    pub fn sessions(&self) -> &BTreeMap<SessionKey, SessionStats> {
+        &self.sessions
+    }
+
+    pub fn datagrams(&self) -> u64 {
+        self.datagrams
+    }
+
+    pub fn malformed_datagrams(&self) -> u64 {
+        self.malformed_datagrams
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::concurrent::logbuffer::data_frame_header;
+    use crate::protocol::data_header_flyweight::{self, DataHeaderFlyweight};
+    use crate::protocol::header_flyweight::HeaderFlyweight;
+    use crate::protocol::nak_flyweight::NakFlyweight;
+    use crate::protocol::setup_flyweight::SetupFlyweight;
+    use crate::protocol::status_message_flyweight::StatusMessageFlyweight;
+
+    const SESSION_ID: i32 = 11;
+    const STREAM_ID: i32 = 1001;
+    const TERM_LENGTH: i32 = 64 * 1024;
+
+    fn encode<F: FnOnce(AtomicBuffer) -> Index>(write: F) -> Vec<u8> {
+        let aligned = AlignedBuffer::with_capacity(256);
+        let buffer = AtomicBuffer::from_aligned(&aligned);
+        let length = write(buffer);
+
+        buffer.as_sub_slice(0, length).to_vec()
+    }
+
+    fn data(term_id: i32, term_offset: i32, payload_length: usize) -> Vec<u8> {
+        encode(|buffer| {
+            let mut frame = DataHeaderFlyweight::new(buffer, 0);
+            frame.header_mut().init(
+                data_frame_header::HDR_TYPE_DATA,
+                data_header_flyweight::BEGIN_AND_END_FLAGS,
+                0,
+            );
+            frame.set_session_id(SESSION_ID);
+            frame.set_stream_id(STREAM_ID);
+            frame.set_term_id(term_id);
+            frame.set_term_offset(term_offset);
+            frame.set_data(&vec![1_u8; payload_length]);
+            frame.header().frame_length()
+        })
+    }
+
+    fn setup(active_term_id: i32, term_offset: i32) -> Vec<u8> {
+        encode(|buffer| {
+            let mut frame = SetupFlyweight::new(buffer, 0);
+            frame
+                .header_mut()
+                .init(data_frame_header::HDR_TYPE_SETUP, 0, SetupFlyweight::header_length());
+            frame.set_session_id(SESSION_ID);
+            frame.set_stream_id(STREAM_ID);
+            frame.set_initial_term_id(active_term_id);
+            frame.set_action_term_id(active_term_id);
+            frame.set_term_offset(term_offset);
+            frame.set_term_length(TERM_LENGTH);
+            SetupFlyweight::header_length()
+        })
+    }
+
+    fn nak(term_id: i32, term_offset: i32, length: i32) -> Vec<u8> {
+        encode(|buffer| {
+            let mut frame = NakFlyweight::new(buffer, 0);
+            frame
+                .header_mut()
+                .init(data_frame_header::HDR_TYPE_NAK, 0, NakFlyweight::header_length());
+            frame.set_session_id(SESSION_ID);
+            frame.set_stream_id(STREAM_ID);
+            frame.set_term_id(term_id);
+            frame.set_term_offset(term_offset);
+            frame.set_length(length);
+            NakFlyweight::header_length()
+        })
+    }
+
+    fn status_message(receiver_window: i32) -> Vec<u8> {
+        encode(|buffer| {
+            let mut frame = StatusMessageFlyweight::new(buffer, 0);
+            frame
+                .header_mut()
+                .init(data_frame_header::HDR_TYPE_SM, 0, StatusMessageFlyweight::header_length());
+            frame.set_session_id(SESSION_ID);
+            frame.set_stream_id(STREAM_ID);
+            frame.set_receiver_window(receiver_window);
+            StatusMessageFlyweight::header_length()
+        })
+    }
+
+    #[test]
+    fn should_collect_gaps_retransmits_naks_and_windows() {
+        let mut dissector = PacketDissector::new();
+        let mut frames = Vec::new();
+
+        for datagram in [
+            setup(5, 0),
+            data(5, 0, 32),
+            // frame at 64 is lost
+            data(5, 128, 32),
+            nak(5, 64, 64),
+            data(5, 64, 32),
+            // rest of term 5 and start of term 6 are lost
+            data(6, 64, 32),
+            status_message(128 * 1024),
+            status_message(64 * 1024),
+        ] {
+            dissector.dissect(&datagram, |frame| frames.push(frame.frame_type())).unwrap();
+        }
+
+        assert_eq!(frames.len(), 8);
+
+        let stats = &dissector.sessions()[&SessionKey {
+            session_id: SESSION_ID,
+            stream_id: STREAM_ID,
+        }];
+        assert_eq!(stats.data_frames, 4);
+        assert_eq!(stats.data_bytes, 4 * 32);
+        assert_eq!(stats.term_length, Some(TERM_LENGTH));
+        assert_eq!(stats.gaps, 2);
+        assert_eq!(stats.gap_bytes, 64 + (TERM_LENGTH as u64 - 192) + 64);
+        assert_eq!((stats.retransmits, stats.retransmit_bytes), (1, 64));
+        assert_eq!(
+            stats.nak_ranges.iter().collect::<Vec<_>>(),
+            vec![(
+                &NakRange {
+                    term_id: 5,
+                    term_offset: 64,
+                    length: 64
+                },
+                &1
+            )]
+        );
+        assert_eq!(
+            (
+                stats.min_receiver_window,
+                stats.max_receiver_window,
+                stats.last_receiver_window
+            ),
+            (Some(64 * 1024), Some(128 * 1024), Some(64 * 1024))
+        );
+    }
+
+    #[test]
+    fn should_count_malformed_datagrams() {
+        let mut dissector = PacketDissector::new();
+
+        let mut datagram = nak(1, 0, 32);
+        HeaderFlyweight::new(AtomicBuffer::wrap_slice(&mut datagram), 0).set_version(7);
+
+        assert!(dissector.dissect(&datagram, |_| {}).is_err());
+        assert!(dissector.dissect(b"not aeron", |_| {}).is_err());
+        assert_eq!((dissector.datagrams(), dissector.malformed_datagrams()), (2, 2));
+        assert!(dissector.sessions().is_empty());
+    }
+}
@@ -0,0 +1,654 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::io::{self, Read};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use thiserror::Error;
+
+/**
+ * Reader of UDP datagrams from classic pcap and pcapng capture files, e.g. written by tcpdump, so captures can
+ * be dissected without Wireshark. Only the headers needed to find UDP payloads are parsed: Ethernet (with VLAN
+ * tags), Linux cooked (v1 and v2), BSD loopback and raw IP link layers, IPv4 and IPv6. IP fragments after the
+ * first one are skipped as they can't be decoded on their own.
+ */
+const PCAP_MAGIC_MICROS: u32 = 0xA1B2_C3D4;
+const PCAP_MAGIC_NANOS: u32 = 0xA1B2_3C4D;
+const PCAPNG_SECTION_HEADER_BLOCK: u32 = 0x0A0D_0D0A;
+const PCAPNG_BYTE_ORDER_MAGIC: u32 = 0x1A2B_3C4D;
+const PCAPNG_INTERFACE_DESCRIPTION_BLOCK: u32 = 0x0000_0001;
+const PCAPNG_PACKET_BLOCK: u32 = 0x0000_0002;
+const PCAPNG_SIMPLE_PACKET_BLOCK: u32 = 0x0000_0003;
+const PCAPNG_ENHANCED_PACKET_BLOCK: u32 = 0x0000_0006;
+const PCAPNG_OPTION_IF_TSRESOL: u16 = 9;
+
+/// Blocks and packets larger than this are treated as corrupted instead of allocating for them.
+const MAX_RECORD_LENGTH: usize = 256 * 1024 * 1024;
+
+pub const LINKTYPE_NULL: u32 = 0;
+pub const LINKTYPE_ETHERNET: u32 = 1;
+pub const LINKTYPE_RAW: u32 = 101;
+pub const LINKTYPE_LINUX_SLL: u32 = 113;
+pub const LINKTYPE_LINUX_SLL2: u32 = 276;
+/// DLT_RAW as written by some BSDs and older libpcap versions.
+const DLT_RAW_12: u32 = 12;
+const DLT_RAW_14: u32 = 14;
+
+const ETHERTYPE_IPV4: u16 = 0x0800;
+const ETHERTYPE_IPV6: u16 = 0x86DD;
+const ETHERTYPE_VLAN: u16 = 0x8100;
+const ETHERTYPE_QINQ: u16 = 0x88A8;
+
+const IP_PROTOCOL_UDP: u8 = 17;
+const UDP_HEADER_LENGTH: usize = 8;
+
+#[derive(Debug, Error)]
+pub enum PcapError {
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+    #[error("Unknown capture file magic {0:#010x}, expected pcap or pcapng")]
+    UnknownMagic(u32),
+    #[error("Unknown pcapng byte order magic {0:#010x}")]
+    UnknownByteOrder(u32),
+    #[error("Invalid {record} length {length}")]
+    InvalidLength { record: &'static str, length: usize },
+    #[error("Packet refers to unknown interface {0}")]
+    UnknownInterface(u32),
+    #[error("Capture file ends within a {0}")]
+    Truncated(&'static str),
+}
+
+/// UDP datagram found in a capture.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UdpDatagram {
+    /// Capture timestamp in nanoseconds since the epoch.
+    pub timestamp_ns: i64,
+    pub source: SocketAddr,
+    pub destination: SocketAddr,
+    /// UDP payload, shorter than the datagram if the capture was truncated by its snap length.
+    pub payload: Vec<u8>,
+    /// Payload length given by the UDP header.
+    pub original_length: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Pcap { link_type: u32, nanos: bool },
+    PcapNg,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Interface {
+    link_type: u32,
+    /// Timestamp units per second.
+    ticks_per_second: u64,
+}
+
+pub struct PcapReader<R: Read> {
+    reader: R,
+    big_endian: bool,
+    format: Format,
+    interfaces: Vec<Interface>,
+}
+
+impl<R: Read> PcapReader<R> {
+    /**
+     * Read the file header and detect the capture format.
+     *
+     * @param reader of the capture, e.g. a BufReader of the file.
+     */
+    pub fn new(mut reader: R) -> Result<Self, PcapError> {
+        let mut magic = [0_u8; 4];
+        read_exact(&mut reader, &mut magic, "file header")?;
+
+        let le_magic = u32::from_le_bytes(magic);
+        let be_magic = u32::from_be_bytes(magic);
+
+        if le_magic == PCAPNG_SECTION_HEADER_BLOCK {
+            let mut pcap_reader = Self {
+                reader,
+                big_endian: false,
+                format: Format::PcapNg,
+                interfaces: Vec::new(),
+            };
+            pcap_reader.read_section_header()?;
+            return Ok(pcap_reader);
+        }
+
+        let (big_endian, nanos) = match (le_magic, be_magic) {
+            (PCAP_MAGIC_MICROS, _) => (false, false),
+            (PCAP_MAGIC_NANOS, _) => (false, true),
+            (_, PCAP_MAGIC_MICROS) => (true, false),
+            (_, PCAP_MAGIC_NANOS) => (true, true),
+            _ => return Err(PcapError::UnknownMagic(le_magic)),
+        };
+
+        // version, thiszone, sigfigs, snaplen and link type
+        let mut header = [0_u8; 20];
+        read_exact(&mut reader, &mut header, "file header")?;
+
+        let mut pcap_reader = Self {
+            reader,
+            big_endian,
+            format: Format::Pcap { link_type: 0, nanos },
+            interfaces: Vec::new(),
+        };
+        // Upper 16 bits of the link type field may hold FCS information
+        let link_type = pcap_reader.u32_at(&header, 16) & 0xFFFF;
+        pcap_reader.format = Format::Pcap { link_type, nanos };
+
+        Ok(pcap_reader)
+    }
+
+    /**
+     * Read up to the next UDP datagram, other packets are skipped.
+     *
+     * @return next datagram or None at the end of the capture.
+     */
+    pub fn next_datagram(&mut self) -> Result<Option<UdpDatagram>, PcapError> {
+        loop {
+            let (timestamp_ns, link_type, packet) = match self.next_packet()? {
+                Some(packet) => packet,
+                None => return Ok(None),
+            };
+
+            if let Some(datagram) = parse_link_layer(link_type, &packet, timestamp_ns) {
+                return Ok(Some(datagram));
+            }
+        }
+    }
+
+    /// Next captured packet as (timestamp ns, link type, bytes).
+    fn next_packet(&mut self) -> Result<Option<(i64, u32, Vec<u8>)>, PcapError> {
+        match self.format {
+            Format::Pcap { link_type, nanos } => self.next_pcap_packet(link_type, nanos),
+            Format::PcapNg => self.next_pcapng_packet(),
+        }
+    }
+
+    fn next_pcap_packet(&mut self, link_type: u32, nanos: bool) -> Result<Option<(i64, u32, Vec<u8>)>, PcapError> {
+        let mut header = [0_u8; 16];
+        if !self.read_or_eof(&mut header, "packet header")? {
+            return Ok(None);
+        }
+
+        let seconds = self.u32_at(&header, 0) as i64;
+        let fraction = self.u32_at(&header, 4) as i64;
+        let captured_length = self.u32_at(&header, 8) as usize;
+        check_length("packet", captured_length)?;
+
+        let mut packet = vec![0_u8; captured_length];
+        read_exact(&mut self.reader, &mut packet, "packet")?;
+
+        let timestamp_ns = seconds * 1_000_000_000 + if nanos { fraction } else { fraction * 1000 };
+
+        Ok(Some((timestamp_ns, link_type, packet)))
+    }
+
+    fn next_pcapng_packet(&mut self) -> Result<Option<(i64, u32, Vec<u8>)>, PcapError> {
+        loop {
+            let mut header = [0_u8; 8];
+            if !self.read_or_eof(&mut header, "block header")? {
+                return Ok(None);
+            }
+
+            let block_type = self.u32_at(&header, 0);
+            if block_type == PCAPNG_SECTION_HEADER_BLOCK {
+                // A new section may switch the byte order, so the block length is read again after the magic
+                self.read_section_header_after_type(&header[4..8])?;
+                continue;
+            }
+
+            let body = self.read_block_body(self.u32_at(&header, 4) as usize)?;
+
+            match block_type {
+                PCAPNG_INTERFACE_DESCRIPTION_BLOCK => self.read_interface_description(&body)?,
+                PCAPNG_ENHANCED_PACKET_BLOCK | PCAPNG_PACKET_BLOCK => {
+                    if body.len() < 20 {
+                        return Err(PcapError::InvalidLength {
+                            record: "packet block",
+                            length: body.len(),
+                        });
+                    }
+
+                    let interface_id = if block_type == PCAPNG_ENHANCED_PACKET_BLOCK {
+                        self.u32_at(&body, 0)
+                    } else {
+                        self.u16_at(&body, 0) as u32
+                    };
+                    let interface = self.interface(interface_id)?;
+                    let ticks = ((self.u32_at(&body, 4) as u64) << 32) | self.u32_at(&body, 8) as u64;
+                    let captured_length = (self.u32_at(&body, 12) as usize).min(body.len() - 20);
+
+                    return Ok(Some((
+                        ticks_to_nanos(ticks, interface.ticks_per_second),
+                        interface.link_type,
+                        body[20..20 + captured_length].to_vec(),
+                    )));
+                },
+                PCAPNG_SIMPLE_PACKET_BLOCK => {
+                    if body.len() < 4 {
+                        return Err(PcapError::InvalidLength {
+                            record: "simple packet block",
+                            length: body.len(),
+                        });
+                    }
+
+                    let interface = self.interface(0)?;
+                    let original_length = self.u32_at(&body, 0) as usize;
+                    let captured_length = original_length.min(body.len() - 4);
+
+                    // Simple packet blocks have no timestamp
+                    return Ok(Some((0, interface.link_type, body[4..4 + captured_length].to_vec())));
+                },
+                _ => {},
+            }
+        }
+    }
+
+    fn read_section_header(&mut self) -> Result<(), PcapError> {
+        let mut length = [0_u8; 4];
+        read_exact(&mut self.reader, &mut length, "section header")?;
+
+        self.read_section_header_after_type(&length)
+    }
+
+    fn read_section_header_after_type(&mut self, length: &[u8]) -> Result<(), PcapError> {
+        let mut byte_order_magic = [0_u8; 4];
+        read_exact(&mut self.reader, &mut byte_order_magic, "section header")?;
+
+        self.big_endian = match u32::from_le_bytes(byte_order_magic) {
+            PCAPNG_BYTE_ORDER_MAGIC => false,
+            magic if magic.swap_bytes() == PCAPNG_BYTE_ORDER_MAGIC => true,
+            magic => return Err(PcapError::UnknownByteOrder(magic)),
+        };
+        self.interfaces.clear();
+
+        let block_length = self.u32_at(length, 0) as usize;
+        if block_length < 16 {
+            return Err(PcapError::InvalidLength {
+                record: "section header",
+                length: block_length,
+            });
+        }
+
+        // Skip version, section length, options and trailing length
+        let mut rest = vec![0_u8; block_length - 12];
+        read_exact(&mut self.reader, &mut rest, "section header")?;
+
+        Ok(())
+    }
+
+    /// Read the body of a block after type and length, without the trailing length.
+    fn read_block_body(&mut self, block_length: usize) -> Result<Vec<u8>, PcapError> {
+        if block_length < 12 || block_length % 4 != 0 {
+            return Err(PcapError::InvalidLength {
+                record: "block",
+                length: block_length,
+            });
+        }
+        check_length("block", block_length)?;
+
+        let mut body = vec![0_u8; block_length - 8];
+        read_exact(&mut self.reader, &mut body, "block")?;
+        body.truncate(block_length - 12);
+
+        Ok(body)
+    }
+
+    fn read_interface_description(&mut self, body: &[u8]) -> Result<(), PcapError> {
+        if body.len() < 8 {
+            return Err(PcapError::InvalidLength {
+                record: "interface description block",
+                length: body.len(),
+            });
+        }
+
+        let mut interface = Interface {
+            link_type: self.u16_at(body, 0) as u32,
+            ticks_per_second: 1_000_000,
+        };
+
+        let mut offset = 8;
+        while offset + 4 <= body.len() {
+            let code = self.u16_at(body, offset);
+            let length = self.u16_at(body, offset + 2) as usize;
+            let value_offset = offset + 4;
+
+            if code == 0 || value_offset + length > body.len() {
+                break;
+            }
+
+            if code == PCAPNG_OPTION_IF_TSRESOL && length >= 1 {
+                let resolution = body[value_offset];
+                let exponent = (resolution & 0x7F) as u32;
+                interface.ticks_per_second = if resolution & 0x80 == 0 {
+                    10_u64.checked_pow(exponent).unwrap_or(1_000_000)
+                } else {
+                    2_u64.checked_pow(exponent).unwrap_or(1_000_000)
+                };
+            }
+
+            offset = value_offset + ((length + 3) & !3);
+        }
+
+        self.interfaces.push(interface);
+
+        Ok(())
+    }
+
+    fn interface(&self, interface_id: u32) -> Result<Interface, PcapError> {
+        self.interfaces
+            .get(interface_id as usize)
+            .copied()
+            .ok_or(PcapError::UnknownInterface(interface_id))
+    }
+
+    /// Fill the buffer, false if the capture ended before the first byte.
+    fn read_or_eof(&mut self, buffer: &mut [u8], record: &'static str) -> Result<bool, PcapError> {
+        let mut read = 0;
+
+        while read < buffer.len() {
+            match self.reader.read(&mut buffer[read..]) {
+                Ok(0) if read == 0 => return Ok(false),
+                Ok(0) => return Err(PcapError::Truncated(record)),
+                Ok(length) => read += length,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {},
+                Err(err) => return Err(err.into()),
+            }
+        }
+
+        Ok(true)
+    }
+
+    fn u16_at(&self, bytes: &[u8], offset: usize) -> u16 {
+        let value = [bytes[offset], bytes[offset + 1]];
+        if self.big_endian {
+            u16::from_be_bytes(value)
+        } else {
+            u16::from_le_bytes(value)
+        }
+    }
+
+    fn u32_at(&self, bytes: &[u8], offset: usize) -> u32 {
+        let value = [bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]];
+        if self.big_endian {
+            u32::from_be_bytes(value)
+        } else {
+            u32::from_le_bytes(value)
+        }
+    }
+}
+
+impl<R: Read> Iterator for PcapReader<R> {
+    type Item = Result<UdpDatagram, PcapError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_datagram().transpose()
+    }
+}
+
+fn read_exact<R: Read>(reader: &mut R, buffer: &mut [u8], record: &'static str) -> Result<(), PcapError> {
+    reader.read_exact(buffer).map_err(|err| match err.kind() {
+        io::ErrorKind::UnexpectedEof => PcapError::Truncated(record),
+        _ => PcapError::Io(err),
+    })
+}
+
+fn check_length(record: &'static str, length: usize) -> Result<(), PcapError> {
+    if length > MAX_RECORD_LENGTH {
+        return Err(PcapError::InvalidLength { record, length });
+    }
+
+    Ok(())
+}
+
+fn ticks_to_nanos(ticks: u64, ticks_per_second: u64) -> i64 {
+    ((ticks as u128 * 1_000_000_000) / ticks_per_second.max(1) as u128) as i64
+}
+
+fn be_u16(bytes: &[u8], offset: usize) -> Option<u16> {
+    bytes
+        .get(offset..offset + 2)
+        .map(|value| u16::from_be_bytes([value[0], value[1]]))
+}
+
+fn parse_link_layer(link_type: u32, packet: &[u8], timestamp_ns: i64) -> Option<UdpDatagram> {
+    let (ether_type, offset) = match link_type {
+        LINKTYPE_ETHERNET => {
+            let mut offset = 12;
+            let mut ether_type = be_u16(packet, offset)?;
+            while ether_type == ETHERTYPE_VLAN || ether_type == ETHERTYPE_QINQ {
+                offset += 4;
+                ether_type = be_u16(packet, offset)?;
+            }
+            (ether_type, offset + 2)
+        },
+        LINKTYPE_LINUX_SLL => (be_u16(packet, 14)?, 16),
+        LINKTYPE_LINUX_SLL2 => (be_u16(packet, 0)?, 20),
+        LINKTYPE_NULL => {
+            // Address family in the byte order of the capturing host
+            let family = packet.get(0..4)?;
+            let family = u32::from_le_bytes([family[0], family[1], family[2], family[3]])
+                .min(u32::from_be_bytes([family[0], family[1], family[2], family[3]]));
+            match family {
+                2 => (ETHERTYPE_IPV4, 4),
+                24 | 28 | 30 => (ETHERTYPE_IPV6, 4),
+                _ => return None,
+            }
+        },
+        LINKTYPE_RAW | DLT_RAW_12 | DLT_RAW_14 => match packet.first()? >> 4 {
+            4 => (ETHERTYPE_IPV4, 0),
+            6 => (ETHERTYPE_IPV6, 0),
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    let ip_packet = packet.get(offset..)?;
+    match ether_type {
+        ETHERTYPE_IPV4 => parse_ipv4(ip_packet, timestamp_ns),
+        ETHERTYPE_IPV6 => parse_ipv6(ip_packet, timestamp_ns),
+        _ => None,
+    }
+}
+
+fn parse_ipv4(packet: &[u8], timestamp_ns: i64) -> Option<UdpDatagram> {
+    let header_length = ((*packet.first()? & 0x0F) as usize) * 4;
+    if packet.len() < 20 || header_length < 20 || packet[9] != IP_PROTOCOL_UDP {
+        return None;
+    }
+
+    // Only the first fragment holds the UDP header
+    let fragment_offset = be_u16(packet, 6)? & 0x1FFF;
+    if fragment_offset != 0 {
+        return None;
+    }
+
+    let source = IpAddr::V4(Ipv4Addr::new(packet[12], packet[13], packet[14], packet[15]));
+    let destination = IpAddr::V4(Ipv4Addr::new(packet[16], packet[17], packet[18], packet[19]));
+
+    parse_udp(packet.get(header_length..)?, source, destination, timestamp_ns)
+}
+
+fn parse_ipv6(packet: &[u8], timestamp_ns: i64) -> Option<UdpDatagram> {
+    if packet.len() < 40 {
+        return None;
+    }
+
+    let mut source = [0_u8; 16];
+    let mut destination = [0_u8; 16];
+    source.copy_from_slice(&packet[8..24]);
+    destination.copy_from_slice(&packet[24..40]);
+
+    let mut next_header = packet[6];
+    let mut offset = 40;
+    loop {
+        match next_header {
+            IP_PROTOCOL_UDP => break,
+            // Hop-by-hop, routing and destination options headers
+            0 | 43 | 60 => {
+                next_header = *packet.get(offset)?;
+                offset += (*packet.get(offset + 1)? as usize + 1) * 8;
+            },
+            // Fragment header, only the first fragment holds the UDP header
+            44 => {
+                if be_u16(packet, offset + 2)? & 0xFFF8 != 0 {
+                    return None;
+                }
+                next_header = *packet.get(offset)?;
+                offset += 8;
+            },
+            _ => return None,
+        }
+    }
+
+    parse_udp(
+        packet.get(offset..)?,
+        IpAddr::V6(Ipv6Addr::from(source)),
+        IpAddr::V6(Ipv6Addr::from(destination)),
+        timestamp_ns,
+    )
+}
+
+fn parse_udp(segment: &[u8], source: IpAddr, destination: IpAddr, timestamp_ns: i64) -> Option<UdpDatagram> {
+    let source_port = be_u16(segment, 0)?;
+    let destination_port = be_u16(segment, 2)?;
+    let udp_length = be_u16(segment, 4)? as usize;
+    if udp_length < UDP_HEADER_LENGTH {
+        return None;
+    }
+
+    let original_length = udp_length - UDP_HEADER_LENGTH;
+    let available = segment.len() - UDP_HEADER_LENGTH.min(segment.len());
+    let payload = segment.get(UDP_HEADER_LENGTH..UDP_HEADER_LENGTH + original_length.min(available))?;
+
+    Some(UdpDatagram {
+        timestamp_ns,
+        source: SocketAddr::new(source, source_port),
+        destination: SocketAddr::new(destination, destination_port),
+        payload: payload.to_vec(),
+        original_length,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ipv4_udp_frame(payload: &[u8]) -> Vec<u8> {
+        let mut frame = vec![0_u8; 14];
+        frame[12..14].copy_from_slice(&ETHERTYPE_IPV4.to_be_bytes());
+
+        let mut ip = vec![0x45, 0, 0, 0, 0, 0, 0, 0, 64, IP_PROTOCOL_UDP, 0, 0, 10, 0, 0, 1, 10, 0, 0, 2];
+        let total_length = (20 + UDP_HEADER_LENGTH + payload.len()) as u16;
+        ip[2..4].copy_from_slice(&total_length.to_be_bytes());
+        frame.extend_from_slice(&ip);
+
+        frame.extend_from_slice(&40123_u16.to_be_bytes());
+        frame.extend_from_slice(&40124_u16.to_be_bytes());
+        frame.extend_from_slice(&((UDP_HEADER_LENGTH + payload.len()) as u16).to_be_bytes());
+        frame.extend_from_slice(&[0, 0]);
+        frame.extend_from_slice(payload);
+
+        frame
+    }
+
+    fn expected_datagram(timestamp_ns: i64, payload: &[u8]) -> UdpDatagram {
+        UdpDatagram {
+            timestamp_ns,
+            source: "10.0.0.1:40123".parse().unwrap(),
+            destination: "10.0.0.2:40124".parse().unwrap(),
+            payload: payload.to_vec(),
+            original_length: payload.len(),
+        }
+    }
+
+    #[test]
+    fn should_read_classic_pcap() {
+        let packet = ipv4_udp_frame(b"aeron");
+        let mut capture = Vec::new();
+        for value in &[PCAP_MAGIC_MICROS, 0x0004_0002, 0, 0, 65535, LINKTYPE_ETHERNET] {
+            capture.extend_from_slice(&value.to_le_bytes());
+        }
+        for value in &[10_u32, 500, packet.len() as u32, packet.len() as u32] {
+            capture.extend_from_slice(&value.to_le_bytes());
+        }
+        capture.extend_from_slice(&packet);
+
+        let datagrams: Vec<UdpDatagram> = PcapReader::new(capture.as_slice())
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(datagrams, vec![expected_datagram(10_000_500_000, b"aeron")]);
+    }
+
+    #[test]
+    fn should_read_pcapng_with_timestamp_resolution() {
+        let packet = ipv4_udp_frame(b"aeron-ng");
+        let padded_length = (packet.len() + 3) & !3;
+        let mut capture = Vec::new();
+
+        let mut block = |block_type: u32, body: &[u8]| {
+            let length = (body.len() + 12) as u32;
+            capture.extend_from_slice(&block_type.to_le_bytes());
+            capture.extend_from_slice(&length.to_le_bytes());
+            capture.extend_from_slice(body);
+            capture.extend_from_slice(&length.to_le_bytes());
+        };
+
+        let mut section = PCAPNG_BYTE_ORDER_MAGIC.to_le_bytes().to_vec();
+        section.extend_from_slice(&[1, 0, 0, 0]);
+        section.extend_from_slice(&(-1_i64).to_le_bytes());
+        block(PCAPNG_SECTION_HEADER_BLOCK, &section);
+
+        let mut interface = (LINKTYPE_ETHERNET as u16).to_le_bytes().to_vec();
+        interface.extend_from_slice(&[0, 0, 0, 0, 1, 0]);
+        interface.extend_from_slice(&PCAPNG_OPTION_IF_TSRESOL.to_le_bytes());
+        interface.extend_from_slice(&1_u16.to_le_bytes());
+        interface.extend_from_slice(&[9, 0, 0, 0, 0, 0, 0, 0]);
+        block(PCAPNG_INTERFACE_DESCRIPTION_BLOCK, &interface);
+
+        let timestamp_ns: u64 = 1_600_000_000_123_456_789;
+        let mut enhanced = 0_u32.to_le_bytes().to_vec();
+        enhanced.extend_from_slice(&((timestamp_ns >> 32) as u32).to_le_bytes());
+        enhanced.extend_from_slice(&(timestamp_ns as u32).to_le_bytes());
+        enhanced.extend_from_slice(&(packet.len() as u32).to_le_bytes());
+        enhanced.extend_from_slice(&(packet.len() as u32).to_le_bytes());
+        enhanced.extend_from_slice(&packet);
+        enhanced.resize(20 + padded_length, 0);
+        block(PCAPNG_ENHANCED_PACKET_BLOCK, &enhanced);
+
+        let datagrams: Vec<UdpDatagram> = PcapReader::new(capture.as_slice())
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(datagrams, vec![expected_datagram(timestamp_ns as i64, b"aeron-ng")]);
+    }
+
+    #[test]
+    fn should_reject_unknown_format_and_truncated_capture() {
+        assert!(matches!(PcapReader::new(&b"GIF89a"[..]), Err(PcapError::UnknownMagic(_))));
+
+        let mut capture = PCAP_MAGIC_NANOS.to_be_bytes().to_vec();
+        capture.extend_from_slice(&[0; 20]);
+        capture.extend_from_slice(&[0; 10]);
+        let mut reader = PcapReader::new(capture.as_slice()).unwrap();
+        assert!(matches!(reader.next_datagram(), Err(PcapError::Truncated(_))));
+    }
+}
@@ -0,0 +1,329 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use crate::concurrent::counters::{self, CountersReader, MAX_KEY_LENGTH};
+use crate::driver_counters::{DriverCounter, StreamCounterKey, STREAM_COUNTER_CHANNEL_OFFSET};
+use crate::utils::types::I32_SIZE;
+
+/// Content type of Prometheus text exposition format.
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+const ACCEPT_IDLE_SLEEP_MS: u64 = 10;
+
+const GAUGE: &str = "gauge";
+const COUNTER: &str = "counter";
+
+struct MetricFamily {
+    metric_type: &'static str,
+    help: &'static str,
+    samples: Vec<String>,
+}
+
+/// Escapes label value as required by the text exposition format.
+fn escape_label_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn format_labels(labels: &[(&str, String)]) -> String {
+    let labels: Vec<String> = labels
+        .iter()
+        .map(|(name, value)| format!("{}=\"{}\"", name, escape_label_value(value)))
+        .collect();
+
+    format!("{{{}}}", labels.join(","))
+}
+
+/**
+ * Channel in the key is truncated if it doesn't fit, while the label of stream counters ends with the full
+ * channel (e.g. "pub-lmt: 10 20 1001 aeron:udp?endpoint=..."). Prefer the channel from the label if the key one
+ * may be truncated.
+ */
+fn stream_channel(key: &StreamCounterKey, label: &str) -> String {
+    if key.channel.len() as i32 >= MAX_KEY_LENGTH - STREAM_COUNTER_CHANNEL_OFFSET - I32_SIZE {
+        if let Some(channel) = label
+            .split_whitespace()
+            .rev()
+            .find(|token| token.starts_with(key.channel.as_str()))
+        {
+            return String::from(channel);
+        }
+    }
+
+    key.channel.clone()
+}
+
+/// Name, type and help of the metric family the counter belongs to and labels of its sample.
+type Metric = (&'static str, &'static str, &'static str, Vec<(&'static str, String)>);
+
+fn metric_of(counter_id: i32, counter: &DriverCounter, label: &str) -> Metric {
+    match counter {
+        // System counters are mostly cumulative totals (errors, NAKs, bytes etc), so exposed as a counter family
+        DriverCounter::System { id, .. } => ("aeron_system_counter_total", COUNTER, "Driver system counters", vec![
+            ("id", id.to_string()),
+            ("name", String::from(label)),
+        ]),
+        DriverCounter::SendChannelStatus { channel } | DriverCounter::ReceiveChannelStatus { channel } => (
+            "aeron_channel_status",
+            GAUGE,
+            "Status of channel endpoints: 0 initializing, 1 active, 2 closing, -1 errored",
+            vec![
+                ("type", String::from(counter.type_name())),
+                ("counter_id", counter_id.to_string()),
+                ("channel", channel.clone()),
+            ],
+        ),
+        DriverCounter::ClientHeartbeat { client_id } => (
+            "aeron_client_heartbeat_timestamp_ms",
+            GAUGE,
+            "Time of the last heartbeat of the client in ms since epoch",
+            vec![("client_id", client_id.to_string())],
+        ),
+        DriverCounter::LocalSocketAddress {
+            channel_status_id,
+            address,
+        } => (
+            "aeron_local_socket_address",
+            GAUGE,
+            "Local socket addresses of channel endpoints",
+            vec![
+                ("counter_id", counter_id.to_string()),
+                ("channel_status_id", channel_status_id.to_string()),
+                ("address", address.clone()),
+            ],
+        ),
+        DriverCounter::Other { type_id } => (
+            "aeron_counter",
+            GAUGE,
+            "Counters not known to the driver model, e.g. allocated by applications",
+            vec![
+                ("counter_id", counter_id.to_string()),
+                ("type_id", type_id.to_string()),
+                ("label", String::from(label)),
+            ],
+        ),
+        DriverCounter::PublisherLimit(key)
+        | DriverCounter::SenderPosition(key)
+        | DriverCounter::ReceiverHwm(key)
+        | DriverCounter::SubscriberPosition(key)
+        | DriverCounter::ReceiverPosition(key)
+        | DriverCounter::SenderLimit(key)
+        | DriverCounter::PublisherPosition(key)
+        | DriverCounter::SenderBpe(key) => (
+            "aeron_stream_position",
+            GAUGE,
+            "Stream position counters: publisher limit, sender position, receiver HWM, subscriber position etc",
+            vec![
+                ("type", String::from(counter.type_name())),
+                ("registration_id", key.registration_id.to_string()),
+                ("session_id", key.session_id.to_string()),
+                ("stream_id", key.stream_id.to_string()),
+                ("channel", stream_channel(key, label)),
+            ],
+        ),
+    }
+}
+
+/**
+ * Render all allocated counters in Prometheus text exposition format.
+ *
+ * Counters are read on each call, so counters allocated and freed by the driver appear and disappear
+ * between scrapes. A counter freed while being read is skipped.
+ *
+ * @param counters_reader to read the counters from.
+ * @return metrics text.
+ */
+pub fn render_metrics(counters_reader: &CountersReader) -> String {
+    let mut families: BTreeMap<&'static str, MetricFamily> = BTreeMap::new();
+
+    counters_reader.for_each(|counter_id, type_id, key_buffer, label| {
+        let counter = DriverCounter::decode(type_id, key_buffer);
+        let label = label.to_string_lossy();
+        let (name, metric_type, help, labels) = metric_of(counter_id, &counter, &label);
+
+        let value = counters_reader.counter_value(counter_id).unwrap_or_default() as i64;
+
+        // Record could have been freed (and may be reused) while it was read
+        if counters_reader.counter_state(counter_id).ok() != Some(counters::RECORD_ALLOCATED) {
+            return;
+        }
+
+        families
+            .entry(name)
+            .or_insert_with(|| MetricFamily {
+                metric_type,
+                help,
+                samples: Vec::new(),
+            })
+            .samples
+            .push(format!("{}{} {}", name, format_labels(&labels), value));
+    });
+
+    let mut metrics = String::new();
+
+    for (name, family) in families {
+        metrics.push_str(&format!(
+            "# HELP {} {}\n# TYPE {} {}\n",
+            name, family.help, name, family.metric_type
+        ));
+
+        for sample in family.samples {
+            metrics.push_str(&sample);
+            metrics.push('\n');
+        }
+    }
+
+    metrics
+}
+
+/**
+ * Handle one HTTP request: GET /metrics is answered with the rendered metrics, other requests with 404.
+ */
+pub fn handle_connection(mut stream: TcpStream, counters_reader: &CountersReader) -> io::Result<()> {
+    let mut request_line = String::new();
+    let mut reader = BufReader::new(stream.try_clone()?);
+    reader.read_line(&mut request_line)?;
+
+    // Skip headers
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 0 && header != "\r\n" && header != "\n" {
+        header.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let (status, content_type, body) = match (parts.next(), parts.next()) {
+        (Some("GET"), Some(path)) if path == "/metrics" || path.starts_with("/metrics?") => {
+            ("200 OK", CONTENT_TYPE, render_metrics(counters_reader))
+        },
+        _ => ("404 Not Found", "text/plain", String::from("Not found, use /metrics\n")),
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+/**
+ * Serve /metrics on the listener until running is set to false. Requests are handled one by one on the
+ * calling thread; errors of the individual connections are passed to the error handler.
+ */
+pub fn serve(
+    listener: TcpListener,
+    counters_reader: &CountersReader,
+    running: &AtomicBool,
+    mut error_handler: impl FnMut(io::Error),
+) -> io::Result<()> {
+    listener.set_nonblocking(true)?;
+
+    while running.load(Ordering::SeqCst) {
+        match listener.accept() {
+            Ok((stream, _address)) => {
+                let result = stream
+                    .set_nonblocking(false)
+                    .and_then(|_| stream.set_read_timeout(Some(Duration::from_secs(5))))
+                    .and_then(|_| handle_connection(stream, counters_reader));
+
+                if let Err(err) = result {
+                    error_handler(err);
+                }
+            },
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => thread::sleep(Duration::from_millis(ACCEPT_IDLE_SLEEP_MS)),
+            Err(err) => error_handler(err),
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::concurrent::atomic_buffer::AtomicBuffer;
+    use crate::concurrent::counters::CountersFixture;
+    use crate::driver_counters::{
+        PUBLISHER_LIMIT_TYPE_ID, STREAM_COUNTER_REGISTRATION_ID_OFFSET, STREAM_COUNTER_SESSION_ID_OFFSET,
+        STREAM_COUNTER_STREAM_ID_OFFSET, SYSTEM_COUNTER_ID_OFFSET, SYSTEM_COUNTER_TYPE_ID,
+    };
+
+    const NUM_COUNTERS: i32 = 8;
+    const CHANNEL: &str = "aeron:udp?endpoint=localhost:40123";
+
+    #[test]
+    fn should_render_counters_with_labels_from_key() {
+        let mut fixture = CountersFixture::new(NUM_COUNTERS);
+        let naks_id = fixture
+            .manager
+            .allocate_opt(
+                SYSTEM_COUNTER_TYPE_ID,
+                None,
+                Some(|key_buffer: &mut AtomicBuffer| key_buffer.put::<i32>(SYSTEM_COUNTER_ID_OFFSET, 6)),
+                "NAKs received",
+            )
+            .unwrap();
+        let pub_limit_id = fixture
+            .manager
+            .allocate_opt(
+                PUBLISHER_LIMIT_TYPE_ID,
+                None,
+                Some(|key_buffer: &mut AtomicBuffer| {
+                    key_buffer.put::<i64>(STREAM_COUNTER_REGISTRATION_ID_OFFSET, 10);
+                    key_buffer.put::<i32>(STREAM_COUNTER_SESSION_ID_OFFSET, 77);
+                    key_buffer.put::<i32>(STREAM_COUNTER_STREAM_ID_OFFSET, 1001);
+                    key_buffer.put_string(STREAM_COUNTER_CHANNEL_OFFSET, CHANNEL.as_bytes());
+                }),
+                "pub-lmt: 10 77 1001 aeron:udp?endpoint=localhost:40123",
+            )
+            .unwrap();
+        fixture.manager.set_counter_value(naks_id, 5);
+        fixture.manager.set_counter_value(pub_limit_id, 65536);
+
+        let metrics = render_metrics(&fixture.reader());
+
+        assert!(metrics.contains("# TYPE aeron_system_counter_total counter\n"));
+        assert!(metrics.contains("aeron_system_counter_total{id=\"6\",name=\"NAKs received\"} 5\n"));
+        assert!(metrics.contains("# TYPE aeron_stream_position gauge\n"));
+        assert!(metrics.contains(
+            "aeron_stream_position{type=\"pub-lmt\",registration_id=\"10\",session_id=\"77\",stream_id=\"1001\",\
+             channel=\"aeron:udp?endpoint=localhost:40123\"} 65536\n"
+        ));
+    }
+
+    #[test]
+    fn should_drop_freed_counters_and_escape_labels() {
+        let mut fixture = CountersFixture::new(NUM_COUNTERS);
+        let no_key = Option::<fn(&mut AtomicBuffer)>::None;
+        let freed_id = fixture.manager.allocate_opt(1000, None, no_key, "freed").unwrap();
+        fixture.manager.allocate_opt(1000, None, no_key, "app \"counter\"").unwrap();
+        fixture.manager.free(freed_id);
+
+        let metrics = render_metrics(&fixture.reader());
+
+        assert!(!metrics.contains("freed"));
+        assert!(metrics.contains("label=\"app \\\"counter\\\"\""));
+    }
+}
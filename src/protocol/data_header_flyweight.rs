@@ -14,18 +14,32 @@
  * limitations under the License.
  */
 
+use std::fmt;
+
 use crate::concurrent::atomic_buffer::AtomicBuffer;
+use crate::concurrent::logbuffer::data_frame_header;
 use crate::protocol::header_flyweight::{HeaderDefn, HeaderFlyweight};
 use crate::utils::types::Index;
 
 pub const DATA_HEADER_DEFN_SIZE: Index = std::mem::size_of::<DataHeaderDefn>() as Index;
 
+/// Begin fragment flag.
+pub const BEGIN_FLAG: u8 = 0x80;
+/// End fragment flag.
+pub const END_FLAG: u8 = 0x40;
+/// Begin and end fragment flags combined for unfragmented messages.
+pub const BEGIN_AND_END_FLAGS: u8 = BEGIN_FLAG | END_FLAG;
+/// End of stream flag, set on the heartbeat sent when a publication is closed.
+pub const EOS_FLAG: u8 = 0x20;
+
 /**
  * HeaderFlyweight for Data Header
  * <p>
  * <a href="https://github.com/real-logic/Aeron/wiki/Protocol-Specification#data-frame">Data Frame</a>
+ * <p>
+ * Pad frames use the same layout with type HDR_TYPE_PAD. A heartbeat is a data frame with frame length 0
+ * which is sent as the header only.
  */
-
 #[repr(C, packed(4))]
 #[derive(Copy, Clone)]
 pub struct DataHeaderDefn {
@@ -34,10 +48,9 @@ pub struct DataHeaderDefn {
     session_id: i32,
     stream_id: i32,
     term_id: i32,
-    data: [u8; 1],
+    reserved_value: i64,
 }
 
-#[allow(dead_code)]
 pub struct DataHeaderFlyweight {
     header_flyweight: HeaderFlyweight,
     m_struct: *mut DataHeaderDefn, // This is actually part of above field memory space
@@ -53,6 +66,16 @@ impl DataHeaderFlyweight {
         }
     }
 
+    #[inline]
+    pub fn header(&self) -> &HeaderFlyweight {
+        &self.header_flyweight
+    }
+
+    #[inline]
+    pub fn header_mut(&mut self) -> &mut HeaderFlyweight {
+        &mut self.header_flyweight
+    }
+
     // Getters
     #[inline]
     pub fn session_id(&self) -> i32 {
@@ -74,16 +97,38 @@ impl DataHeaderFlyweight {
         unsafe { (*self.m_struct).term_offset }
     }
 
+    #[inline]
+    pub fn reserved_value(&self) -> i64 {
+        unsafe { (*self.m_struct).reserved_value }
+    }
+
+    /// Pointer to the payload following the header.
     #[inline]
     pub fn data(&self) -> *const u8 {
-        unsafe { (*self.m_struct).data.as_ptr() }
+        unsafe { (self.m_struct as *const u8).offset(DATA_HEADER_DEFN_SIZE as isize) }
+    }
+
+    /// Length of the payload, 0 for heartbeats.
+    #[inline]
+    pub fn data_length(&self) -> i32 {
+        (self.header_flyweight.frame_length() - DATA_HEADER_DEFN_SIZE).max(0)
+    }
+
+    #[inline]
+    pub fn is_heartbeat(&self) -> bool {
+        self.header_flyweight.frame_type() == data_frame_header::HDR_TYPE_DATA && self.header_flyweight.frame_length() == 0
+    }
+
+    #[inline]
+    pub fn is_end_of_stream(&self) -> bool {
+        self.header_flyweight.flags() & EOS_FLAG == EOS_FLAG
     }
 
     // Setters
     #[inline]
     pub fn set_session_id(&mut self, value: i32) {
         unsafe {
-            (*self.m_struct).stream_id = value;
+            (*self.m_struct).session_id = value;
         }
     }
 
@@ -108,8 +153,36 @@ impl DataHeaderFlyweight {
         }
     }
 
+    #[inline]
+    pub fn set_reserved_value(&mut self, value: i64) {
+        unsafe {
+            (*self.m_struct).reserved_value = value;
+        }
+    }
+
+    /// Copy the payload after the header and set frame length to cover it.
+    #[inline]
+    pub fn set_data(&mut self, data: &[u8]) {
+        self.header_flyweight.flyweight.put_bytes(DATA_HEADER_DEFN_SIZE, data);
+        self.header_flyweight
+            .set_frame_length(DATA_HEADER_DEFN_SIZE + data.len() as Index);
+    }
+
     #[inline]
     pub const fn header_length() -> Index {
         DATA_HEADER_DEFN_SIZE
     }
 }
+
+impl fmt::Debug for DataHeaderFlyweight {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DataHeaderFlyweight")
+            .field("header", &self.header_flyweight)
+            .field("term_offset", &self.term_offset())
+            .field("session_id", &self.session_id())
+            .field("stream_id", &self.stream_id())
+            .field("term_id", &self.term_id())
+            .field("reserved_value", &self.reserved_value())
+            .finish()
+    }
+}
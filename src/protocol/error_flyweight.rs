@@ -0,0 +1,217 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::fmt;
+
+use crate::concurrent::atomic_buffer::AtomicBuffer;
+use crate::protocol::header_flyweight::{HeaderDefn, HeaderFlyweight};
+use crate::utils::types::Index;
+
+pub const ERROR_DEFN_SIZE: Index = std::mem::size_of::<ErrorDefn>() as Index;
+
+/// Group tag field is set.
+pub const HAS_GROUP_TAG_FLAG: u8 = 0x08;
+
+/**
+ * Flyweight for Error Frames sent by a receiver to reject a publication, e.g. when the stream is invalid.
+ * <p>
+ *    0                   1                   2                   3
+ *    0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+ *   +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+ *   |                        Frame Length                           |
+ *   +---------------+-------------+-+-------------------------------+
+ *   |   Version     |    Flags    |G|          Type (=0x04)         |
+ *   +---------------+-------------+-+-------------------------------+
+ *   |                          Session ID                           |
+ *   +---------------------------------------------------------------+
+ *   |                           Stream ID                           |
+ *   +---------------------------------------------------------------+
+ *   |                          Receiver ID                          |
+ *   |                                                               |
+ *   +---------------------------------------------------------------+
+ *   |                           Group Tag                           |
+ *   |                                                               |
+ *   +---------------------------------------------------------------+
+ *   |                          Error Code                           |
+ *   +---------------------------------------------------------------+
+ *   |                         Error Length                          |
+ *   +---------------------------------------------------------------+
+ *   |                         Error Message                        ...
+ *  ...                                                              |
+ *   +---------------------------------------------------------------+
+ */
+#[repr(C, packed(4))]
+#[derive(Copy, Clone)]
+pub struct ErrorDefn {
+    header: HeaderDefn,
+    session_id: i32,
+    stream_id: i32,
+    receiver_id: i64,
+    group_tag: i64,
+    error_code: i32,
+    error_length: i32,
+}
+
+pub struct ErrorFlyweight {
+    header_flyweight: HeaderFlyweight,
+    m_struct: *mut ErrorDefn, // This is actually part of above field memory space
+}
+
+impl ErrorFlyweight {
+    pub fn new(buffer: AtomicBuffer, offset: Index) -> Self {
+        let header_flyweight = HeaderFlyweight::new(buffer, offset);
+        let m_struct = header_flyweight.flyweight.overlay_struct::<ErrorDefn>(0);
+        Self {
+            header_flyweight,
+            m_struct,
+        }
+    }
+
+    #[inline]
+    pub fn header(&self) -> &HeaderFlyweight {
+        &self.header_flyweight
+    }
+
+    #[inline]
+    pub fn header_mut(&mut self) -> &mut HeaderFlyweight {
+        &mut self.header_flyweight
+    }
+
+    // Getters
+    #[inline]
+    pub fn session_id(&self) -> i32 {
+        unsafe { (*self.m_struct).session_id }
+    }
+
+    #[inline]
+    pub fn stream_id(&self) -> i32 {
+        unsafe { (*self.m_struct).stream_id }
+    }
+
+    #[inline]
+    pub fn receiver_id(&self) -> i64 {
+        unsafe { (*self.m_struct).receiver_id }
+    }
+
+    /// Group tag of the receiver, only if HAS_GROUP_TAG_FLAG is set.
+    #[inline]
+    pub fn group_tag(&self) -> Option<i64> {
+        if self.header_flyweight.flags() & HAS_GROUP_TAG_FLAG == HAS_GROUP_TAG_FLAG {
+            Some(unsafe { (*self.m_struct).group_tag })
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    pub fn error_code(&self) -> i32 {
+        unsafe { (*self.m_struct).error_code }
+    }
+
+    #[inline]
+    pub fn error_length(&self) -> i32 {
+        unsafe { (*self.m_struct).error_length }
+    }
+
+    /// Error message following the fixed fields, invalid UTF-8 is replaced.
+    pub fn error_message(&self) -> String {
+        let mut message = vec![0_u8; self.error_length().max(0) as usize];
+        self.header_flyweight
+            .flyweight
+            .get_bytes(ERROR_DEFN_SIZE, message.as_mut_ptr(), message.len() as Index);
+
+        String::from_utf8_lossy(&message).into_owned()
+    }
+
+    // Setters
+    #[inline]
+    pub fn set_session_id(&mut self, value: i32) {
+        unsafe {
+            (*self.m_struct).session_id = value;
+        }
+    }
+
+    #[inline]
+    pub fn set_stream_id(&mut self, value: i32) {
+        unsafe {
+            (*self.m_struct).stream_id = value;
+        }
+    }
+
+    #[inline]
+    pub fn set_receiver_id(&mut self, value: i64) {
+        unsafe {
+            (*self.m_struct).receiver_id = value;
+        }
+    }
+
+    /// Set the group tag and HAS_GROUP_TAG_FLAG, None clears both.
+    #[inline]
+    pub fn set_group_tag(&mut self, value: Option<i64>) {
+        let flags = self.header_flyweight.flags();
+
+        match value {
+            Some(group_tag) => {
+                self.header_flyweight.set_flags(flags | HAS_GROUP_TAG_FLAG);
+                unsafe {
+                    (*self.m_struct).group_tag = group_tag;
+                }
+            },
+            None => {
+                self.header_flyweight.set_flags(flags & !HAS_GROUP_TAG_FLAG);
+                unsafe {
+                    (*self.m_struct).group_tag = 0;
+                }
+            },
+        }
+    }
+
+    #[inline]
+    pub fn set_error_code(&mut self, value: i32) {
+        unsafe {
+            (*self.m_struct).error_code = value;
+        }
+    }
+
+    /// Copy the message after the fixed fields and set error length and frame length to cover it.
+    pub fn set_error_message(&mut self, message: &str) {
+        self.header_flyweight.flyweight.put_bytes(ERROR_DEFN_SIZE, message.as_bytes());
+        unsafe {
+            (*self.m_struct).error_length = message.len() as i32;
+        }
+        self.header_flyweight
+            .set_frame_length(ERROR_DEFN_SIZE + message.len() as Index);
+    }
+
+    #[inline]
+    pub const fn header_length() -> Index {
+        ERROR_DEFN_SIZE
+    }
+}
+
+impl fmt::Debug for ErrorFlyweight {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ErrorFlyweight")
+            .field("header", &self.header_flyweight)
+            .field("session_id", &self.session_id())
+            .field("stream_id", &self.stream_id())
+            .field("receiver_id", &self.receiver_id())
+            .field("group_tag", &self.group_tag())
+            .field("error_code", &self.error_code())
+            .field("error_message", &self.error_message())
+            .finish()
+    }
+}
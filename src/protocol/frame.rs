@@ -0,0 +1,669 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::fmt::{self, Display, Formatter};
+
+use crate::concurrent::atomic_buffer::AtomicBuffer;
+use crate::concurrent::logbuffer::data_frame_header;
+use crate::concurrent::logbuffer::frame_descriptor::FRAME_ALIGNMENT;
+use crate::protocol::data_header_flyweight::{self, DataHeaderFlyweight};
+use crate::protocol::error_flyweight::{self, ErrorFlyweight};
+use crate::protocol::header_flyweight::HeaderFlyweight;
+use crate::protocol::nak_flyweight::NakFlyweight;
+use crate::protocol::resolution_entry_flyweight::{self, ResolutionEntryFlyweight};
+use crate::protocol::response_setup_flyweight::ResponseSetupFlyweight;
+use crate::protocol::rtt_measurement_flyweight::{self, RttMeasurementFlyweight};
+use crate::protocol::setup_flyweight::{self, SetupFlyweight};
+use crate::protocol::status_message_flyweight::{self, StatusMessageFlyweight};
+use crate::utils::bit_utils;
+use crate::utils::errors::{AeronError, MalformedFrameError};
+use crate::utils::types::Index;
+
+/// Frame types of the Aeron UDP protocol which can be decoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FrameType {
+    Pad,
+    Data,
+    Nak,
+    StatusMessage,
+    Error,
+    Setup,
+    RttMeasurement,
+    Resolution,
+    ResponseSetup,
+}
+
+impl FrameType {
+    pub fn from_type_id(type_id: u16) -> Option<Self> {
+        match type_id {
+            data_frame_header::HDR_TYPE_PAD => Some(FrameType::Pad),
+            data_frame_header::HDR_TYPE_DATA => Some(FrameType::Data),
+            data_frame_header::HDR_TYPE_NAK => Some(FrameType::Nak),
+            data_frame_header::HDR_TYPE_SM => Some(FrameType::StatusMessage),
+            data_frame_header::HDR_TYPE_ERR => Some(FrameType::Error),
+            data_frame_header::HDR_TYPE_SETUP => Some(FrameType::Setup),
+            data_frame_header::HDR_TYPE_RTTM => Some(FrameType::RttMeasurement),
+            data_frame_header::HDR_TYPE_RES => Some(FrameType::Resolution),
+            data_frame_header::HDR_TYPE_RSP_SETUP => Some(FrameType::ResponseSetup),
+            _ => None,
+        }
+    }
+
+    pub fn type_id(&self) -> u16 {
+        match self {
+            FrameType::Pad => data_frame_header::HDR_TYPE_PAD,
+            FrameType::Data => data_frame_header::HDR_TYPE_DATA,
+            FrameType::Nak => data_frame_header::HDR_TYPE_NAK,
+            FrameType::StatusMessage => data_frame_header::HDR_TYPE_SM,
+            FrameType::Error => data_frame_header::HDR_TYPE_ERR,
+            FrameType::Setup => data_frame_header::HDR_TYPE_SETUP,
+            FrameType::RttMeasurement => data_frame_header::HDR_TYPE_RTTM,
+            FrameType::Resolution => data_frame_header::HDR_TYPE_RES,
+            FrameType::ResponseSetup => data_frame_header::HDR_TYPE_RSP_SETUP,
+        }
+    }
+
+    /// Short name as used by the Aeron sources and tools, e.g. SM for status messages.
+    pub fn name(&self) -> &'static str {
+        match self {
+            FrameType::Pad => "PAD",
+            FrameType::Data => "DATA",
+            FrameType::Nak => "NAK",
+            FrameType::StatusMessage => "SM",
+            FrameType::Error => "ERR",
+            FrameType::Setup => "SETUP",
+            FrameType::RttMeasurement => "RTTM",
+            FrameType::Resolution => "RES",
+            FrameType::ResponseSetup => "RSP_SETUP",
+        }
+    }
+
+    /// Length of the fixed fields of the frame, including the common header.
+    pub fn header_length(&self) -> Index {
+        match self {
+            FrameType::Pad | FrameType::Data => DataHeaderFlyweight::header_length(),
+            FrameType::Nak => NakFlyweight::header_length(),
+            FrameType::StatusMessage => StatusMessageFlyweight::header_length(),
+            FrameType::Error => ErrorFlyweight::header_length(),
+            FrameType::Setup => SetupFlyweight::header_length(),
+            FrameType::RttMeasurement => RttMeasurementFlyweight::header_length(),
+            FrameType::Resolution => HeaderFlyweight::header_length(),
+            FrameType::ResponseSetup => ResponseSetupFlyweight::header_length(),
+        }
+    }
+
+    /// Flags defined for the frame type, any other flag makes the frame invalid.
+    pub fn supported_flags(&self) -> u8 {
+        match self {
+            FrameType::Pad | FrameType::Data => data_header_flyweight::BEGIN_AND_END_FLAGS | data_header_flyweight::EOS_FLAG,
+            FrameType::StatusMessage => status_message_flyweight::SEND_SETUP_FLAG | status_message_flyweight::END_OF_STREAM_FLAG,
+            FrameType::Error => error_flyweight::HAS_GROUP_TAG_FLAG,
+            FrameType::Setup => setup_flyweight::SEND_RESPONSE_SETUP_FLAG | setup_flyweight::GROUP_FLAG,
+            FrameType::RttMeasurement => rtt_measurement_flyweight::REPLY_FLAG,
+            FrameType::Nak | FrameType::Resolution | FrameType::ResponseSetup => 0,
+        }
+    }
+}
+
+impl Display for FrameType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Resolution frame with the entries it holds.
+pub struct ResolutionFrame {
+    header: HeaderFlyweight,
+    entries: Vec<ResolutionEntryFlyweight>,
+}
+
+impl ResolutionFrame {
+    #[inline]
+    pub fn header(&self) -> &HeaderFlyweight {
+        &self.header
+    }
+
+    #[inline]
+    pub fn entries(&self) -> &[ResolutionEntryFlyweight] {
+        &self.entries
+    }
+}
+
+impl fmt::Debug for ResolutionFrame {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResolutionFrame")
+            .field("header", &self.header)
+            .field("entries", &self.entries)
+            .finish()
+    }
+}
+
+/**
+ * Frame validated and decoded by decode_frame. Flyweights point into the decoded buffer, so the frame must not
+ * outlive it.
+ */
+#[derive(Debug)]
+pub enum Frame {
+    Pad(DataHeaderFlyweight),
+    Data(DataHeaderFlyweight),
+    /// Data frame with frame length 0 sent to keep the stream alive or to signal end of stream.
+    Heartbeat(DataHeaderFlyweight),
+    Nak(NakFlyweight),
+    StatusMessage(StatusMessageFlyweight),
+    Error(ErrorFlyweight),
+    Setup(SetupFlyweight),
+    RttMeasurement(RttMeasurementFlyweight),
+    Resolution(ResolutionFrame),
+    ResponseSetup(ResponseSetupFlyweight),
+}
+
+impl Frame {
+    pub fn header(&self) -> &HeaderFlyweight {
+        match self {
+            Frame::Pad(frame) | Frame::Data(frame) | Frame::Heartbeat(frame) => frame.header(),
+            Frame::Nak(frame) => frame.header(),
+            Frame::StatusMessage(frame) => frame.header(),
+            Frame::Error(frame) => frame.header(),
+            Frame::Setup(frame) => frame.header(),
+            Frame::RttMeasurement(frame) => frame.header(),
+            Frame::Resolution(frame) => frame.header(),
+            Frame::ResponseSetup(frame) => frame.header(),
+        }
+    }
+
+    pub fn frame_type(&self) -> FrameType {
+        match self {
+            Frame::Pad(_) => FrameType::Pad,
+            Frame::Data(_) | Frame::Heartbeat(_) => FrameType::Data,
+            Frame::Nak(_) => FrameType::Nak,
+            Frame::StatusMessage(_) => FrameType::StatusMessage,
+            Frame::Error(_) => FrameType::Error,
+            Frame::Setup(_) => FrameType::Setup,
+            Frame::RttMeasurement(_) => FrameType::RttMeasurement,
+            Frame::Resolution(_) => FrameType::Resolution,
+            Frame::ResponseSetup(_) => FrameType::ResponseSetup,
+        }
+    }
+
+    /// Bytes taken by the frame in the buffer, heartbeats take the data header although their frame length is 0.
+    pub fn encoded_length(&self) -> Index {
+        match self {
+            Frame::Heartbeat(_) => DataHeaderFlyweight::header_length(),
+            _ => self.header().frame_length(),
+        }
+    }
+}
+
+impl Display for Frame {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let header = self.header();
+        let name = match self {
+            Frame::Heartbeat(_) => "HEARTBEAT",
+            _ => self.frame_type().name(),
+        };
+        write!(f, "{} len={} flags={:#04x}", name, header.frame_length(), header.flags())?;
+
+        match self {
+            Frame::Pad(frame) | Frame::Data(frame) | Frame::Heartbeat(frame) => write!(
+                f,
+                " session_id={} stream_id={} term_id={} term_offset={} reserved_value={}",
+                frame.session_id(),
+                frame.stream_id(),
+                frame.term_id(),
+                frame.term_offset(),
+                frame.reserved_value()
+            ),
+            Frame::Nak(frame) => write!(
+                f,
+                " session_id={} stream_id={} term_id={} term_offset={} length={}",
+                frame.session_id(),
+                frame.stream_id(),
+                frame.term_id(),
+                frame.term_offset(),
+                frame.length()
+            ),
+            Frame::StatusMessage(frame) => {
+                write!(
+                    f,
+                    " session_id={} stream_id={} term_id={} term_offset={} receiver_window={}",
+                    frame.session_id(),
+                    frame.stream_id(),
+                    frame.consumption_term_id(),
+                    frame.consumption_term_offset(),
+                    frame.receiver_window()
+                )?;
+                if let Some(receiver_id) = frame.receiver_id() {
+                    write!(f, " receiver_id={}", receiver_id)?;
+                }
+                if let Some(group_tag) = frame.group_tag() {
+                    write!(f, " group_tag={}", group_tag)?;
+                }
+                Ok(())
+            },
+            Frame::Error(frame) => {
+                write!(
+                    f,
+                    " session_id={} stream_id={} receiver_id={}",
+                    frame.session_id(),
+                    frame.stream_id(),
+                    frame.receiver_id()
+                )?;
+                if let Some(group_tag) = frame.group_tag() {
+                    write!(f, " group_tag={}", group_tag)?;
+                }
+                write!(f, " error_code={} message={:?}", frame.error_code(), frame.error_message())
+            },
+            Frame::Setup(frame) => write!(
+                f,
+                " session_id={} stream_id={} initial_term_id={} active_term_id={} term_offset={} term_length={} mtu={} ttl={}",
+                frame.session_id(),
+                frame.stream_id(),
+                frame.initial_term_id(),
+                frame.action_term_id(),
+                frame.term_offset(),
+                frame.term_length(),
+                frame.mtu(),
+                frame.ttl()
+            ),
+            Frame::RttMeasurement(frame) => write!(
+                f,
+                " session_id={} stream_id={} echo_timestamp={} reception_delta={} receiver_id={}",
+                frame.session_id(),
+                frame.stream_id(),
+                frame.echo_timestamp(),
+                frame.reception_delta(),
+                frame.receiver_id()
+            ),
+            Frame::Resolution(frame) => {
+                for entry in frame.entries() {
+                    write!(f, " [name={:?}", entry.name())?;
+                    if let Some(address) = entry.address() {
+                        write!(f, " address={}", address)?;
+                    }
+                    write!(f, " port={} age_ms={}", entry.udp_port(), entry.age_in_ms())?;
+                    if entry.is_self() {
+                        write!(f, " self")?;
+                    }
+                    write!(f, "]")?;
+                }
+                Ok(())
+            },
+            Frame::ResponseSetup(frame) => write!(
+                f,
+                " session_id={} stream_id={} response_session_id={}",
+                frame.session_id(),
+                frame.stream_id(),
+                frame.response_session_id()
+            ),
+        }
+    }
+}
+
+/**
+ * Validate the frame at the offset: protocol version, frame type, flags defined for the type and the lengths
+ * of the frame and of its variable parts.
+ *
+ * @param buffer holding the frame, it should be at least 4 byte aligned like buffers received from a socket.
+ * @param offset of the frame in the buffer.
+ * @param length of the bytes available for the frame.
+ * @return type of the valid frame.
+ */
+pub fn validate_frame(buffer: &AtomicBuffer, offset: Index, length: Index) -> Result<FrameType, AeronError> {
+    if length < HeaderFlyweight::header_length() {
+        return Err(MalformedFrameError::BufferTooShort {
+            frame_type: String::from("frame"),
+            length,
+            min_length: HeaderFlyweight::header_length(),
+        }
+        .into());
+    }
+
+    let header = HeaderFlyweight::new(*buffer, offset);
+    if header.version() != data_frame_header::CURRENT_VERSION {
+        return Err(MalformedFrameError::UnsupportedVersion(header.version()).into());
+    }
+
+    let frame_type =
+        FrameType::from_type_id(header.frame_type()).ok_or(MalformedFrameError::UnknownFrameType(header.frame_type()))?;
+
+    if header.flags() & !frame_type.supported_flags() != 0 {
+        return Err(MalformedFrameError::UnsupportedFlags {
+            frame_type: frame_type.to_string(),
+            flags: header.flags(),
+        }
+        .into());
+    }
+
+    if length < frame_type.header_length() {
+        return Err(MalformedFrameError::BufferTooShort {
+            frame_type: frame_type.to_string(),
+            length,
+            min_length: frame_type.header_length(),
+        }
+        .into());
+    }
+
+    let frame_length = header.frame_length();
+    let is_heartbeat = frame_type == FrameType::Data && frame_length == 0;
+    if !is_heartbeat && frame_length < frame_type.header_length() {
+        return Err(MalformedFrameError::FrameLengthTooShort {
+            frame_type: frame_type.to_string(),
+            frame_length,
+            min_length: frame_type.header_length(),
+        }
+        .into());
+    }
+
+    if frame_length > length {
+        return Err(MalformedFrameError::FrameLengthExceedsBuffer { frame_length, length }.into());
+    }
+
+    match frame_type {
+        FrameType::Error => {
+            let error_length = ErrorFlyweight::new(*buffer, offset).error_length();
+            if error_length < 0 || error_length > frame_length - ErrorFlyweight::header_length() {
+                return Err(MalformedFrameError::ErrorMessageLengthOutOfRange {
+                    error_length,
+                    frame_length,
+                }
+                .into());
+            }
+        },
+        FrameType::Resolution => {
+            resolution_entries(buffer, offset, frame_length)?;
+        },
+        _ => {},
+    }
+
+    Ok(frame_type)
+}
+
+fn resolution_entries(
+    buffer: &AtomicBuffer,
+    offset: Index,
+    frame_length: Index,
+) -> Result<Vec<ResolutionEntryFlyweight>, AeronError> {
+    let mut entries = Vec::new();
+    let mut entry_offset = HeaderFlyweight::header_length();
+
+    while entry_offset < frame_length {
+        let remaining = frame_length - entry_offset;
+        let min_length = resolution_entry_flyweight::RESOLUTION_HEADER_DEFN_SIZE;
+        if remaining < min_length {
+            return Err(MalformedFrameError::ResolutionEntryExceedsFrame {
+                entry_length: min_length,
+                length: remaining,
+            }
+            .into());
+        }
+
+        let entry = ResolutionEntryFlyweight::new(*buffer, offset + entry_offset);
+        let address_length = ResolutionEntryFlyweight::address_length(entry.res_type())
+            .ok_or(MalformedFrameError::UnknownResolutionType(entry.res_type()))?;
+
+        // Name length follows the address, it has to be in the frame before it can be read
+        let name_length_end = resolution_entry_flyweight::ADDRESS_FIELD_OFFSET + address_length + 2;
+        if remaining < name_length_end {
+            return Err(MalformedFrameError::ResolutionEntryExceedsFrame {
+                entry_length: name_length_end,
+                length: remaining,
+            }
+            .into());
+        }
+
+        let entry_length = entry.entry_length().unwrap_or_default();
+        if entry.name_length() < 0 || entry_length > remaining {
+            return Err(MalformedFrameError::ResolutionEntryExceedsFrame {
+                entry_length,
+                length: remaining,
+            }
+            .into());
+        }
+
+        entries.push(entry);
+        entry_offset += entry_length;
+    }
+
+    Ok(entries)
+}
+
+/**
+ * Validate and decode the frame at the offset.
+ *
+ * @param buffer holding the frame, it should be at least 4 byte aligned like buffers received from a socket.
+ * @param offset of the frame in the buffer.
+ * @param length of the bytes available for the frame.
+ * @return decoded frame pointing into the buffer.
+ */
+pub fn decode_frame(buffer: &AtomicBuffer, offset: Index, length: Index) -> Result<Frame, AeronError> {
+    let frame = match validate_frame(buffer, offset, length)? {
+        FrameType::Pad => Frame::Pad(DataHeaderFlyweight::new(*buffer, offset)),
+        FrameType::Data => {
+            let frame = DataHeaderFlyweight::new(*buffer, offset);
+            if frame.is_heartbeat() {
+                Frame::Heartbeat(frame)
+            } else {
+                Frame::Data(frame)
+            }
+        },
+        FrameType::Nak => Frame::Nak(NakFlyweight::new(*buffer, offset)),
+        FrameType::StatusMessage => Frame::StatusMessage(StatusMessageFlyweight::new(*buffer, offset)),
+        FrameType::Error => Frame::Error(ErrorFlyweight::new(*buffer, offset)),
+        FrameType::Setup => Frame::Setup(SetupFlyweight::new(*buffer, offset)),
+        FrameType::RttMeasurement => Frame::RttMeasurement(RttMeasurementFlyweight::new(*buffer, offset)),
+        FrameType::Resolution => {
+            let header = HeaderFlyweight::new(*buffer, offset);
+            let entries = resolution_entries(buffer, offset, header.frame_length())?;
+            Frame::Resolution(ResolutionFrame { header, entries })
+        },
+        FrameType::ResponseSetup => Frame::ResponseSetup(ResponseSetupFlyweight::new(*buffer, offset)),
+    };
+
+    Ok(frame)
+}
+
+/**
+ * Decode all frames of a datagram. Data and pad frames are batched, each of them aligned to FRAME_ALIGNMENT,
+ * while any other frame is the only frame of its datagram.
+ *
+ * @param buffer holding the datagram, it should be at least 4 byte aligned like buffers received from a socket.
+ * @param offset of the datagram in the buffer.
+ * @param length of the datagram.
+ * @return decoded frames pointing into the buffer.
+ */
+pub fn decode_frames(buffer: &AtomicBuffer, offset: Index, length: Index) -> Result<Vec<Frame>, AeronError> {
+    let mut frames = Vec::new();
+    let mut frame_offset = 0;
+
+    while frame_offset < length {
+        let frame = decode_frame(buffer, offset + frame_offset, length - frame_offset)?;
+        let is_data = matches!(frame, Frame::Pad(_) | Frame::Data(_) | Frame::Heartbeat(_));
+        frame_offset += bit_utils::align(frame.encoded_length(), FRAME_ALIGNMENT);
+        frames.push(frame);
+
+        if !is_data {
+            break;
+        }
+    }
+
+    Ok(frames)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+
+    use super::*;
+    use crate::concurrent::atomic_buffer::AlignedBuffer;
+
+    #[test]
+    fn should_decode_datagram_of_data_frames() {
+        let aligned = AlignedBuffer::with_capacity(256);
+        let buffer = AtomicBuffer::from_aligned(&aligned);
+
+        let mut data = DataHeaderFlyweight::new(buffer, 0);
+        data.header_mut().init(
+            data_frame_header::HDR_TYPE_DATA,
+            data_header_flyweight::BEGIN_AND_END_FLAGS,
+            0,
+        );
+        data.set_session_id(7);
+        data.set_stream_id(1001);
+        data.set_term_id(3);
+        data.set_term_offset(64);
+        data.set_data(b"hello");
+
+        let mut heartbeat = DataHeaderFlyweight::new(buffer, 64);
+        heartbeat.header_mut().init(
+            data_frame_header::HDR_TYPE_DATA,
+            data_header_flyweight::BEGIN_AND_END_FLAGS | data_header_flyweight::EOS_FLAG,
+            0,
+        );
+        heartbeat.set_term_offset(128);
+
+        let frames = decode_frames(&buffer, 0, 96).unwrap();
+        assert_eq!(frames.len(), 2);
+
+        match &frames[0] {
+            Frame::Data(frame) => {
+                assert_eq!(frame.data_length(), 5);
+                assert_eq!(unsafe { std::slice::from_raw_parts(frame.data(), 5) }, b"hello");
+            },
+            frame => panic!("unexpected frame {:?}", frame),
+        }
+        assert_eq!(
+            frames[0].to_string(),
+            "DATA len=37 flags=0xc0 session_id=7 stream_id=1001 term_id=3 term_offset=64 reserved_value=0"
+        );
+
+        match &frames[1] {
+            Frame::Heartbeat(frame) => assert!(frame.is_end_of_stream()),
+            frame => panic!("unexpected frame {:?}", frame),
+        }
+    }
+
+    #[test]
+    fn should_decode_control_frames() {
+        let aligned = AlignedBuffer::with_capacity(256);
+        let buffer = AtomicBuffer::from_aligned(&aligned);
+
+        let mut error = ErrorFlyweight::new(buffer, 0);
+        error.header_mut().init(data_frame_header::HDR_TYPE_ERR, 0, 0);
+        error.set_session_id(1);
+        error.set_stream_id(2);
+        error.set_receiver_id(3);
+        error.set_group_tag(Some(4));
+        error.set_error_code(5);
+        error.set_error_message("invalid stream");
+
+        assert_eq!(
+            decode_frame(&buffer, 0, 256).unwrap().to_string(),
+            "ERR len=54 flags=0x08 session_id=1 stream_id=2 receiver_id=3 group_tag=4 error_code=5 message=\"invalid stream\""
+        );
+
+        let mut rttm = RttMeasurementFlyweight::new(buffer, 0);
+        rttm.header_mut().init(
+            data_frame_header::HDR_TYPE_RTTM,
+            rtt_measurement_flyweight::REPLY_FLAG,
+            RttMeasurementFlyweight::header_length(),
+        );
+        rttm.set_echo_timestamp(100);
+        rttm.set_reception_delta(20);
+        rttm.set_receiver_id(30);
+
+        match decode_frame(&buffer, 0, 256).unwrap() {
+            Frame::RttMeasurement(frame) => {
+                assert!(frame.is_reply());
+                assert_eq!(
+                    (frame.echo_timestamp(), frame.reception_delta(), frame.receiver_id()),
+                    (100, 20, 30)
+                );
+            },
+            frame => panic!("unexpected frame {:?}", frame),
+        }
+
+        let mut response_setup = ResponseSetupFlyweight::new(buffer, 0);
+        response_setup.header_mut().init(
+            data_frame_header::HDR_TYPE_RSP_SETUP,
+            0,
+            ResponseSetupFlyweight::header_length(),
+        );
+        response_setup.set_response_session_id(42);
+
+        assert_eq!(
+            decode_frame(&buffer, 0, 256).unwrap().to_string(),
+            "RSP_SETUP len=20 flags=0x00 session_id=1 stream_id=2 response_session_id=42"
+        );
+    }
+
+    #[test]
+    fn should_decode_resolution_entries() {
+        let aligned = AlignedBuffer::with_capacity(256);
+        let buffer = AtomicBuffer::from_aligned(&aligned);
+
+        let mut frame_length = HeaderFlyweight::header_length();
+        let mut entry = ResolutionEntryFlyweight::new(buffer, frame_length);
+        entry.set_flags(resolution_entry_flyweight::SELF_FLAG);
+        entry.set_udp_port(40123);
+        entry.set_age_in_ms(0);
+        frame_length += entry.set_address_and_name(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)), b"node-a");
+
+        let mut entry = ResolutionEntryFlyweight::new(buffer, frame_length);
+        entry.set_udp_port(40124);
+        entry.set_age_in_ms(250);
+        frame_length += entry.set_address_and_name("::1".parse().unwrap(), b"node-b");
+
+        HeaderFlyweight::new(buffer, 0).init(data_frame_header::HDR_TYPE_RES, 0, frame_length);
+
+        let frame = decode_frame(&buffer, 0, frame_length).unwrap();
+        assert_eq!(
+            frame.to_string(),
+            "RES len=64 flags=0x00 [name=\"node-a\" address=10.0.0.1 port=40123 age_ms=0 self] \
+             [name=\"node-b\" address=::1 port=40124 age_ms=250]"
+        );
+    }
+
+    #[test]
+    fn should_reject_malformed_frames() {
+        let aligned = AlignedBuffer::with_capacity(64);
+        let buffer = AtomicBuffer::from_aligned(&aligned);
+        let mut header = HeaderFlyweight::new(buffer, 0);
+
+        header.init(data_frame_header::HDR_TYPE_NAK, 0, NakFlyweight::header_length());
+        assert_eq!(validate_frame(&buffer, 0, 64).unwrap(), FrameType::Nak);
+        assert!(validate_frame(&buffer, 0, 4).is_err());
+        assert!(validate_frame(&buffer, 0, NakFlyweight::header_length() - 1).is_err());
+
+        header.set_version(1);
+        assert!(validate_frame(&buffer, 0, 64).is_err());
+
+        header.init(0x0F, 0, NakFlyweight::header_length());
+        assert!(validate_frame(&buffer, 0, 64).is_err());
+
+        header.init(data_frame_header::HDR_TYPE_NAK, 0x80, NakFlyweight::header_length());
+        assert!(validate_frame(&buffer, 0, 64).is_err());
+
+        header.init(data_frame_header::HDR_TYPE_SM, 0, 8);
+        assert!(validate_frame(&buffer, 0, 64).is_err());
+
+        header.init(data_frame_header::HDR_TYPE_SM, 0, 128);
+        assert!(validate_frame(&buffer, 0, 64).is_err());
+
+        header.init(data_frame_header::HDR_TYPE_ERR, 0, ErrorFlyweight::header_length());
+        buffer.put::<i32>(ErrorFlyweight::header_length() - 4, 1);
+        assert!(validate_frame(&buffer, 0, 64).is_err());
+    }
+}
@@ -14,26 +14,38 @@
  * limitations under the License.
  */
 
+use std::fmt;
+
 use crate::command::flyweight::Flyweight;
 use crate::concurrent::atomic_buffer::AtomicBuffer;
+use crate::concurrent::logbuffer::data_frame_header;
 use crate::utils::types::Index;
 
 pub const HEADER_DEFN_SIZE: Index = std::mem::size_of::<HeaderDefn>() as Index;
 
 /**
- * Flyweight for command header fields.
+ * Flyweight for general Aeron network protocol header of a message frame.
+ * <p>
+ *    0                   1                   2                   3
+ *    0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+ *   +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+ *   |                        Frame Length                           |
+ *   +---------------+---------------+-------------------------------+
+ *   |   Version     |     Flags     |               Type            |
+ *   +---------------+---------------+-------------------------------+
+ *   |                       Depends on Type                        ...
  */
 #[repr(C, packed(4))]
 #[derive(Copy, Clone)]
 pub struct HeaderDefn {
     frame_length: i32,
-    version: i8,
-    flags: i8,
-    h_type: i16,
+    version: u8,
+    flags: u8,
+    frame_type: u16,
 }
 
-pub(crate) struct HeaderFlyweight {
-    pub flyweight: Flyweight<HeaderDefn>,
+pub struct HeaderFlyweight {
+    pub(crate) flyweight: Flyweight<HeaderDefn>,
 }
 
 impl HeaderFlyweight {
@@ -42,4 +54,79 @@ impl HeaderFlyweight {
             flyweight: Flyweight::new(buffer, offset),
         }
     }
+
+    /// Set all header fields with the current protocol version.
+    #[inline]
+    pub fn init(&mut self, frame_type: u16, flags: u8, frame_length: i32) {
+        self.set_frame_length(frame_length);
+        self.set_version(data_frame_header::CURRENT_VERSION);
+        self.set_flags(flags);
+        self.set_frame_type(frame_type);
+    }
+
+    // Getters
+    #[inline]
+    pub fn frame_length(&self) -> i32 {
+        unsafe { (*self.flyweight.m_struct).frame_length }
+    }
+
+    #[inline]
+    pub fn version(&self) -> u8 {
+        unsafe { (*self.flyweight.m_struct).version }
+    }
+
+    #[inline]
+    pub fn flags(&self) -> u8 {
+        unsafe { (*self.flyweight.m_struct).flags }
+    }
+
+    #[inline]
+    pub fn frame_type(&self) -> u16 {
+        unsafe { (*self.flyweight.m_struct).frame_type }
+    }
+
+    // Setters
+    #[inline]
+    pub fn set_frame_length(&mut self, value: i32) {
+        unsafe {
+            (*self.flyweight.m_struct).frame_length = value;
+        }
+    }
+
+    #[inline]
+    pub fn set_version(&mut self, value: u8) {
+        unsafe {
+            (*self.flyweight.m_struct).version = value;
+        }
+    }
+
+    #[inline]
+    pub fn set_flags(&mut self, value: u8) {
+        unsafe {
+            (*self.flyweight.m_struct).flags = value;
+        }
+    }
+
+    #[inline]
+    pub fn set_frame_type(&mut self, value: u16) {
+        unsafe {
+            (*self.flyweight.m_struct).frame_type = value;
+        }
+    }
+
+    #[inline]
+    pub const fn header_length() -> Index {
+        HEADER_DEFN_SIZE
+    }
+}
+
+impl fmt::Debug for HeaderFlyweight {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HeaderFlyweight")
+            .field("frame_length", &self.frame_length())
+            .field("version", &self.version())
+            .field("flags", &format_args!("{:#04x}", self.flags()))
+            .field("frame_type", &format_args!("{:#06x}", self.frame_type()))
+            .finish()
+    }
 }
@@ -1,5 +1,10 @@
 pub mod data_header_flyweight;
+pub mod error_flyweight;
+pub mod frame;
 pub mod header_flyweight;
 pub mod nak_flyweight;
+pub mod resolution_entry_flyweight;
+pub mod response_setup_flyweight;
+pub mod rtt_measurement_flyweight;
 pub mod setup_flyweight;
 pub mod status_message_flyweight;
@@ -14,6 +14,8 @@
  * limitations under the License.
  */
 
+use std::fmt;
+
 use crate::concurrent::atomic_buffer::AtomicBuffer;
 use crate::protocol::header_flyweight::{HeaderDefn, HeaderFlyweight};
 use crate::utils::types::Index;
@@ -25,7 +27,6 @@ pub const NAK_DEFN_SIZE: Index = std::mem::size_of::<NakDefn>() as Index;
  *
  * https://github.com/real-logic/Aeron/wiki/Protocol-Specification#data-recovery-via-retransmit-request
  */
-
 #[repr(C, packed(4))]
 #[derive(Copy, Clone)]
 pub struct NakDefn {
@@ -37,7 +38,6 @@ pub struct NakDefn {
     length: i32,
 }
 
-#[allow(dead_code)]
 pub struct NakFlyweight {
     header_flyweight: HeaderFlyweight,
     m_struct: *mut NakDefn, // This is actually part of above field memory space
@@ -53,7 +53,22 @@ impl NakFlyweight {
         }
     }
 
+    #[inline]
+    pub fn header(&self) -> &HeaderFlyweight {
+        &self.header_flyweight
+    }
+
+    #[inline]
+    pub fn header_mut(&mut self) -> &mut HeaderFlyweight {
+        &mut self.header_flyweight
+    }
+
     // Getters
+    #[inline]
+    pub fn session_id(&self) -> i32 {
+        unsafe { (*self.m_struct).session_id }
+    }
+
     #[inline]
     pub fn stream_id(&self) -> i32 {
         unsafe { (*self.m_struct).stream_id }
@@ -66,7 +81,7 @@ impl NakFlyweight {
 
     #[inline]
     pub fn term_offset(&self) -> i32 {
-        unsafe { (*self.m_struct).term_id }
+        unsafe { (*self.m_struct).term_offset }
     }
 
     #[inline]
@@ -75,6 +90,13 @@ impl NakFlyweight {
     }
 
     // Setters
+    #[inline]
+    pub fn set_session_id(&mut self, value: i32) {
+        unsafe {
+            (*self.m_struct).session_id = value;
+        }
+    }
+
     #[inline]
     pub fn set_stream_id(&mut self, value: i32) {
         unsafe {
@@ -92,7 +114,7 @@ impl NakFlyweight {
     #[inline]
     pub fn set_term_offset(&mut self, value: i32) {
         unsafe {
-            (*self.m_struct).term_id = value;
+            (*self.m_struct).term_offset = value;
         }
     }
 
@@ -108,3 +130,16 @@ impl NakFlyweight {
         NAK_DEFN_SIZE
     }
 }
+
+impl fmt::Debug for NakFlyweight {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NakFlyweight")
+            .field("header", &self.header_flyweight)
+            .field("session_id", &self.session_id())
+            .field("stream_id", &self.stream_id())
+            .field("term_id", &self.term_id())
+            .field("term_offset", &self.term_offset())
+            .field("length", &self.length())
+            .finish()
+    }
+}
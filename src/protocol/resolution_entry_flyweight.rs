@@ -0,0 +1,250 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use crate::concurrent::atomic_buffer::AtomicBuffer;
+use crate::utils::bit_utils;
+use crate::utils::types::Index;
+
+pub const RESOLUTION_HEADER_DEFN_SIZE: Index = std::mem::size_of::<ResolutionHeaderDefn>() as Index;
+pub const ADDRESS_FIELD_OFFSET: Index = RESOLUTION_HEADER_DEFN_SIZE;
+/// Entries are padded so each of them starts 8 byte aligned within the frame.
+pub const ENTRY_ALIGNMENT: Index = 8;
+
+pub const RES_TYPE_NAME_TO_IP4_MD: i8 = 0x01;
+pub const RES_TYPE_NAME_TO_IP6_MD: i8 = 0x02;
+
+/// Entry describes the node sending the frame.
+pub const SELF_FLAG: u8 = 0x80;
+
+/**
+ * Flyweight for an entry of a Resolution Frame (type 0x07) which follows the frame header, a frame holds as many
+ * entries as fit into it.
+ * <p>
+ *    0                   1                   2                   3
+ *    0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+ *   +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+ *   |  Res Type     |S|    Flags    |            UDP Port           |
+ *   +---------------+-+-------------+-------------------------------+
+ *   |                          Age in ms                            |
+ *   +---------------------------------------------------------------+
+ *   |                Address (4 or 16 bytes by type)               ...
+ *  ...                                                              |
+ *   +-------------------------------+-------------------------------+
+ *   |          Name Length          |            Name              ...
+ *   +-------------------------------+                               |
+ *  ...                  Name padded to 8 byte alignment             |
+ *   +---------------------------------------------------------------+
+ */
+#[repr(C, packed(4))]
+#[derive(Copy, Clone)]
+pub struct ResolutionHeaderDefn {
+    res_type: i8,
+    res_flags: u8,
+    udp_port: u16,
+    age_in_ms: i32,
+}
+
+pub struct ResolutionEntryFlyweight {
+    buffer: AtomicBuffer,
+    offset: Index,
+    m_struct: *mut ResolutionHeaderDefn, // This is actually part of above buffer memory space
+}
+
+impl ResolutionEntryFlyweight {
+    pub fn new(buffer: AtomicBuffer, offset: Index) -> Self {
+        Self {
+            buffer,
+            offset,
+            m_struct: buffer.overlay_struct::<ResolutionHeaderDefn>(offset),
+        }
+    }
+
+    /**
+     * Length of the address for the resolution type.
+     *
+     * @param res_type of the entry.
+     * @return 4 for IPv4, 16 for IPv6 or None for unknown types.
+     */
+    pub fn address_length(res_type: i8) -> Option<Index> {
+        match res_type {
+            RES_TYPE_NAME_TO_IP4_MD => Some(4),
+            RES_TYPE_NAME_TO_IP6_MD => Some(16),
+            _ => None,
+        }
+    }
+
+    /**
+     * Length of the entry including padding.
+     *
+     * @param res_type    of the entry.
+     * @param name_length length of the name in bytes.
+     * @return aligned entry length or None for unknown types.
+     */
+    pub fn entry_length_for(res_type: i8, name_length: Index) -> Option<Index> {
+        Self::address_length(res_type)
+            .map(|address_length| bit_utils::align(ADDRESS_FIELD_OFFSET + address_length + 2 + name_length, ENTRY_ALIGNMENT))
+    }
+
+    // Getters
+    #[inline]
+    pub fn res_type(&self) -> i8 {
+        unsafe { (*self.m_struct).res_type }
+    }
+
+    #[inline]
+    pub fn flags(&self) -> u8 {
+        unsafe { (*self.m_struct).res_flags }
+    }
+
+    #[inline]
+    pub fn is_self(&self) -> bool {
+        self.flags() & SELF_FLAG == SELF_FLAG
+    }
+
+    #[inline]
+    pub fn udp_port(&self) -> u16 {
+        unsafe { (*self.m_struct).udp_port }
+    }
+
+    #[inline]
+    pub fn age_in_ms(&self) -> i32 {
+        unsafe { (*self.m_struct).age_in_ms }
+    }
+
+    /// Address of the entry, None for unknown resolution types.
+    pub fn address(&self) -> Option<IpAddr> {
+        let address_offset = self.offset + ADDRESS_FIELD_OFFSET;
+
+        match self.res_type() {
+            RES_TYPE_NAME_TO_IP4_MD => {
+                let mut octets = [0_u8; 4];
+                self.buffer.get_bytes(address_offset, octets.as_mut_ptr(), 4);
+                Some(IpAddr::V4(Ipv4Addr::from(octets)))
+            },
+            RES_TYPE_NAME_TO_IP6_MD => {
+                let mut octets = [0_u8; 16];
+                self.buffer.get_bytes(address_offset, octets.as_mut_ptr(), 16);
+                Some(IpAddr::V6(Ipv6Addr::from(octets)))
+            },
+            _ => None,
+        }
+    }
+
+    /// Length of the name, 0 for unknown resolution types.
+    pub fn name_length(&self) -> i16 {
+        match self.name_length_offset() {
+            Some(offset) => unsafe { self.buffer.overlay_struct::<i16>(offset).read_unaligned() },
+            None => 0,
+        }
+    }
+
+    /// Name of the entry, invalid UTF-8 is replaced.
+    pub fn name(&self) -> String {
+        let mut name = vec![0_u8; self.name_length().max(0) as usize];
+
+        if let Some(offset) = self.name_length_offset() {
+            self.buffer.get_bytes(offset + 2, name.as_mut_ptr(), name.len() as Index);
+        }
+
+        String::from_utf8_lossy(&name).into_owned()
+    }
+
+    /// Length of the entry including padding, None for unknown resolution types.
+    pub fn entry_length(&self) -> Option<Index> {
+        Self::entry_length_for(self.res_type(), self.name_length().max(0) as Index)
+    }
+
+    fn name_length_offset(&self) -> Option<Index> {
+        Self::address_length(self.res_type()).map(|address_length| self.offset + ADDRESS_FIELD_OFFSET + address_length)
+    }
+
+    // Setters
+    #[inline]
+    pub fn set_flags(&mut self, value: u8) {
+        unsafe {
+            (*self.m_struct).res_flags = value;
+        }
+    }
+
+    #[inline]
+    pub fn set_udp_port(&mut self, value: u16) {
+        unsafe {
+            (*self.m_struct).udp_port = value;
+        }
+    }
+
+    #[inline]
+    pub fn set_age_in_ms(&mut self, value: i32) {
+        unsafe {
+            (*self.m_struct).age_in_ms = value;
+        }
+    }
+
+    /**
+     * Write address and name which also sets the resolution type.
+     *
+     * @param address of the node.
+     * @param name    of the node.
+     * @return length of the entry including padding.
+     */
+    pub fn set_address_and_name(&mut self, address: IpAddr, name: &[u8]) -> Index {
+        let address_offset = self.offset + ADDRESS_FIELD_OFFSET;
+        let res_type = match address {
+            IpAddr::V4(address) => {
+                self.buffer.put_bytes(address_offset, &address.octets());
+                RES_TYPE_NAME_TO_IP4_MD
+            },
+            IpAddr::V6(address) => {
+                self.buffer.put_bytes(address_offset, &address.octets());
+                RES_TYPE_NAME_TO_IP6_MD
+            },
+        };
+        unsafe {
+            (*self.m_struct).res_type = res_type;
+        }
+
+        let name_length_offset = self.name_length_offset().expect("resolution type was just set");
+        unsafe {
+            self.buffer
+                .overlay_struct::<i16>(name_length_offset)
+                .write_unaligned(name.len() as i16);
+        }
+        self.buffer.put_bytes(name_length_offset + 2, name);
+
+        let entry_length = Self::entry_length_for(res_type, name.len() as Index).expect("resolution type was just set");
+        let padding_offset = name_length_offset + 2 + name.len() as Index;
+        self.buffer
+            .set_memory(padding_offset, self.offset + entry_length - padding_offset, 0);
+
+        entry_length
+    }
+}
+
+impl fmt::Debug for ResolutionEntryFlyweight {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResolutionEntryFlyweight")
+            .field("res_type", &self.res_type())
+            .field("flags", &format_args!("{:#04x}", self.flags()))
+            .field("udp_port", &self.udp_port())
+            .field("age_in_ms", &self.age_in_ms())
+            .field("address", &self.address())
+            .field("name", &self.name())
+            .finish()
+    }
+}
@@ -0,0 +1,130 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::fmt;
+
+use crate::concurrent::atomic_buffer::AtomicBuffer;
+use crate::protocol::header_flyweight::{HeaderDefn, HeaderFlyweight};
+use crate::utils::types::Index;
+
+pub const RESPONSE_SETUP_DEFN_SIZE: Index = std::mem::size_of::<ResponseSetupDefn>() as Index;
+
+/**
+ * Flyweight for Response Setup Frames sent by a response subscription to tell the response publication
+ * which session id to use.
+ * <p>
+ *    0                   1                   2                   3
+ *    0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+ *   +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+ *   |                        Frame Length                           |
+ *   +---------------+---------------+-------------------------------+
+ *   |   Version     |     Flags     |          Type (=0x0B)         |
+ *   +---------------+---------------+-------------------------------+
+ *   |                          Session ID                           |
+ *   +---------------------------------------------------------------+
+ *   |                           Stream ID                           |
+ *   +---------------------------------------------------------------+
+ *   |                      Response Session ID                      |
+ *   +---------------------------------------------------------------+
+ */
+#[repr(C, packed(4))]
+#[derive(Copy, Clone)]
+pub struct ResponseSetupDefn {
+    header: HeaderDefn,
+    session_id: i32,
+    stream_id: i32,
+    response_session_id: i32,
+}
+
+pub struct ResponseSetupFlyweight {
+    header_flyweight: HeaderFlyweight,
+    m_struct: *mut ResponseSetupDefn, // This is actually part of above field memory space
+}
+
+impl ResponseSetupFlyweight {
+    pub fn new(buffer: AtomicBuffer, offset: Index) -> Self {
+        let header_flyweight = HeaderFlyweight::new(buffer, offset);
+        let m_struct = header_flyweight.flyweight.overlay_struct::<ResponseSetupDefn>(0);
+        Self {
+            header_flyweight,
+            m_struct,
+        }
+    }
+
+    #[inline]
+    pub fn header(&self) -> &HeaderFlyweight {
+        &self.header_flyweight
+    }
+
+    #[inline]
+    pub fn header_mut(&mut self) -> &mut HeaderFlyweight {
+        &mut self.header_flyweight
+    }
+
+    // Getters
+    #[inline]
+    pub fn session_id(&self) -> i32 {
+        unsafe { (*self.m_struct).session_id }
+    }
+
+    #[inline]
+    pub fn stream_id(&self) -> i32 {
+        unsafe { (*self.m_struct).stream_id }
+    }
+
+    #[inline]
+    pub fn response_session_id(&self) -> i32 {
+        unsafe { (*self.m_struct).response_session_id }
+    }
+
+    // Setters
+    #[inline]
+    pub fn set_session_id(&mut self, value: i32) {
+        unsafe {
+            (*self.m_struct).session_id = value;
+        }
+    }
+
+    #[inline]
+    pub fn set_stream_id(&mut self, value: i32) {
+        unsafe {
+            (*self.m_struct).stream_id = value;
+        }
+    }
+
+    #[inline]
+    pub fn set_response_session_id(&mut self, value: i32) {
+        unsafe {
+            (*self.m_struct).response_session_id = value;
+        }
+    }
+
+    #[inline]
+    pub const fn header_length() -> Index {
+        RESPONSE_SETUP_DEFN_SIZE
+    }
+}
+
+impl fmt::Debug for ResponseSetupFlyweight {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResponseSetupFlyweight")
+            .field("header", &self.header_flyweight)
+            .field("session_id", &self.session_id())
+            .field("stream_id", &self.stream_id())
+            .field("response_session_id", &self.response_session_id())
+            .finish()
+    }
+}
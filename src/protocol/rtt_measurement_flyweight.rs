@@ -0,0 +1,172 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::fmt;
+
+use crate::concurrent::atomic_buffer::AtomicBuffer;
+use crate::protocol::header_flyweight::{HeaderDefn, HeaderFlyweight};
+use crate::utils::types::Index;
+
+pub const RTT_MEASUREMENT_DEFN_SIZE: Index = std::mem::size_of::<RttMeasurementDefn>() as Index;
+
+/// Frame is a reply to a measurement request.
+pub const REPLY_FLAG: u8 = 0x80;
+
+/**
+ * Flyweight for RTT Measurement Frames
+ * <p>
+ *    0                   1                   2                   3
+ *    0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+ *   +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+ *   |                        Frame Length                           |
+ *   +---------------+-+-------------+-------------------------------+
+ *   |   Version     |R|    Flags    |          Type (=0x06)         |
+ *   +---------------+-+-------------+-------------------------------+
+ *   |                          Session ID                           |
+ *   +---------------------------------------------------------------+
+ *   |                           Stream ID                           |
+ *   +---------------------------------------------------------------+
+ *   |                        Echo Timestamp                         |
+ *   |                                                               |
+ *   +---------------------------------------------------------------+
+ *   |                        Reception Delta                        |
+ *   |                                                               |
+ *   +---------------------------------------------------------------+
+ *   |                          Receiver ID                          |
+ *   |                                                               |
+ *   +---------------------------------------------------------------+
+ */
+#[repr(C, packed(4))]
+#[derive(Copy, Clone)]
+pub struct RttMeasurementDefn {
+    header: HeaderDefn,
+    session_id: i32,
+    stream_id: i32,
+    echo_timestamp: i64,
+    reception_delta: i64,
+    receiver_id: i64,
+}
+
+pub struct RttMeasurementFlyweight {
+    header_flyweight: HeaderFlyweight,
+    m_struct: *mut RttMeasurementDefn, // This is actually part of above field memory space
+}
+
+impl RttMeasurementFlyweight {
+    pub fn new(buffer: AtomicBuffer, offset: Index) -> Self {
+        let header_flyweight = HeaderFlyweight::new(buffer, offset);
+        let m_struct = header_flyweight.flyweight.overlay_struct::<RttMeasurementDefn>(0);
+        Self {
+            header_flyweight,
+            m_struct,
+        }
+    }
+
+    #[inline]
+    pub fn header(&self) -> &HeaderFlyweight {
+        &self.header_flyweight
+    }
+
+    #[inline]
+    pub fn header_mut(&mut self) -> &mut HeaderFlyweight {
+        &mut self.header_flyweight
+    }
+
+    // Getters
+    #[inline]
+    pub fn session_id(&self) -> i32 {
+        unsafe { (*self.m_struct).session_id }
+    }
+
+    #[inline]
+    pub fn stream_id(&self) -> i32 {
+        unsafe { (*self.m_struct).stream_id }
+    }
+
+    #[inline]
+    pub fn echo_timestamp(&self) -> i64 {
+        unsafe { (*self.m_struct).echo_timestamp }
+    }
+
+    #[inline]
+    pub fn reception_delta(&self) -> i64 {
+        unsafe { (*self.m_struct).reception_delta }
+    }
+
+    #[inline]
+    pub fn receiver_id(&self) -> i64 {
+        unsafe { (*self.m_struct).receiver_id }
+    }
+
+    #[inline]
+    pub fn is_reply(&self) -> bool {
+        self.header_flyweight.flags() & REPLY_FLAG == REPLY_FLAG
+    }
+
+    // Setters
+    #[inline]
+    pub fn set_session_id(&mut self, value: i32) {
+        unsafe {
+            (*self.m_struct).session_id = value;
+        }
+    }
+
+    #[inline]
+    pub fn set_stream_id(&mut self, value: i32) {
+        unsafe {
+            (*self.m_struct).stream_id = value;
+        }
+    }
+
+    #[inline]
+    pub fn set_echo_timestamp(&mut self, value: i64) {
+        unsafe {
+            (*self.m_struct).echo_timestamp = value;
+        }
+    }
+
+    #[inline]
+    pub fn set_reception_delta(&mut self, value: i64) {
+        unsafe {
+            (*self.m_struct).reception_delta = value;
+        }
+    }
+
+    #[inline]
+    pub fn set_receiver_id(&mut self, value: i64) {
+        unsafe {
+            (*self.m_struct).receiver_id = value;
+        }
+    }
+
+    #[inline]
+    pub const fn header_length() -> Index {
+        RTT_MEASUREMENT_DEFN_SIZE
+    }
+}
+
+impl fmt::Debug for RttMeasurementFlyweight {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RttMeasurementFlyweight")
+            .field("header", &self.header_flyweight)
+            .field("session_id", &self.session_id())
+            .field("stream_id", &self.stream_id())
+            .field("echo_timestamp", &self.echo_timestamp())
+            .field("reception_delta", &self.reception_delta())
+            .field("receiver_id", &self.receiver_id())
+            .finish()
+    }
+}
@@ -14,18 +14,24 @@
  * limitations under the License.
  */
 
+use std::fmt;
+
 use crate::concurrent::atomic_buffer::AtomicBuffer;
 use crate::protocol::header_flyweight::{HeaderDefn, HeaderFlyweight};
 use crate::utils::types::Index;
 
 pub const SETUP_DEFN_SIZE: Index = std::mem::size_of::<SetupDefn>() as Index;
 
+/// Publication should send a response setup, used by response channels.
+pub const SEND_RESPONSE_SETUP_FLAG: u8 = 0x80;
+/// Publication is a group (multicast or MDC) publication.
+pub const GROUP_FLAG: u8 = 0x40;
+
 /**
  * HeaderFlyweight for Setup Frames
  * <p>
  * <a href="https://github.com/real-logic/Aeron/wiki/Protocol-Specification#stream-setup">Stream Setup</a>
  */
-
 #[repr(C, packed(4))]
 #[derive(Copy, Clone)]
 pub struct SetupDefn {
@@ -37,9 +43,9 @@ pub struct SetupDefn {
     action_term_id: i32,
     term_length: i32,
     mtu: i32,
+    ttl: i32,
 }
 
-#[allow(dead_code)]
 pub struct SetupFlyweight {
     header_flyweight: HeaderFlyweight,
     m_struct: *mut SetupDefn, // This is actually part of above field memory space
@@ -55,6 +61,16 @@ impl SetupFlyweight {
         }
     }
 
+    #[inline]
+    pub fn header(&self) -> &HeaderFlyweight {
+        &self.header_flyweight
+    }
+
+    #[inline]
+    pub fn header_mut(&mut self) -> &mut HeaderFlyweight {
+        &mut self.header_flyweight
+    }
+
     // Getters
     #[inline]
     pub fn term_offset(&self) -> i32 {
@@ -91,6 +107,11 @@ impl SetupFlyweight {
         unsafe { (*self.m_struct).mtu }
     }
 
+    #[inline]
+    pub fn ttl(&self) -> i32 {
+        unsafe { (*self.m_struct).ttl }
+    }
+
     // Setters
     #[inline]
     pub fn set_term_offset(&mut self, value: i32) {
@@ -141,8 +162,31 @@ impl SetupFlyweight {
         }
     }
 
+    #[inline]
+    pub fn set_ttl(&mut self, value: i32) {
+        unsafe {
+            (*self.m_struct).ttl = value;
+        }
+    }
+
     #[inline]
     pub const fn header_length() -> Index {
         SETUP_DEFN_SIZE
     }
 }
+
+impl fmt::Debug for SetupFlyweight {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SetupFlyweight")
+            .field("header", &self.header_flyweight)
+            .field("term_offset", &self.term_offset())
+            .field("session_id", &self.session_id())
+            .field("stream_id", &self.stream_id())
+            .field("initial_term_id", &self.initial_term_id())
+            .field("action_term_id", &self.action_term_id())
+            .field("term_length", &self.term_length())
+            .field("mtu", &self.mtu())
+            .field("ttl", &self.ttl())
+            .finish()
+    }
+}
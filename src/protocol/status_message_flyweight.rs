@@ -14,11 +14,22 @@
  * limitations under the License.
  */
 
+use std::fmt;
+
 use crate::concurrent::atomic_buffer::AtomicBuffer;
 use crate::protocol::header_flyweight::{HeaderDefn, HeaderFlyweight};
 use crate::utils::types::Index;
 
 pub const STATUS_MESSAGE_DEFN_SIZE: Index = std::mem::size_of::<StatusMessageDefn>() as Index;
+/// Offset of the optional receiver id following the fixed fields.
+pub const RECEIVER_ID_FIELD_OFFSET: Index = STATUS_MESSAGE_DEFN_SIZE;
+/// Offset of the optional group tag used as application specific feedback by tagged flow control.
+pub const GROUP_TAG_FIELD_OFFSET: Index = RECEIVER_ID_FIELD_OFFSET + 8;
+
+/// Receiver asks the publication to send a setup frame.
+pub const SEND_SETUP_FLAG: u8 = 0x80;
+/// Receiver has consumed the end of stream.
+pub const END_OF_STREAM_FLAG: u8 = 0x40;
 
 /**
  * Flow/Congestion control message to send feedback from subscriptions to publications.
@@ -45,7 +56,6 @@ pub const STATUS_MESSAGE_DEFN_SIZE: Index = std::mem::size_of::<StatusMessageDef
  *  ...                                                              |
  *   +---------------------------------------------------------------+
  */
-
 #[repr(C, packed(4))]
 #[derive(Copy, Clone)]
 pub struct StatusMessageDefn {
@@ -57,7 +67,6 @@ pub struct StatusMessageDefn {
     receiver_window: i32,
 }
 
-#[allow(dead_code)]
 pub struct StatusMessageFlyweight {
     header_flyweight: HeaderFlyweight,
     m_struct: *mut StatusMessageDefn, // This is actually part of above field memory space
@@ -73,6 +82,16 @@ impl StatusMessageFlyweight {
         }
     }
 
+    #[inline]
+    pub fn header(&self) -> &HeaderFlyweight {
+        &self.header_flyweight
+    }
+
+    #[inline]
+    pub fn header_mut(&mut self) -> &mut HeaderFlyweight {
+        &mut self.header_flyweight
+    }
+
     // Getters
     #[inline]
     pub fn session_id(&self) -> i32 {
@@ -99,6 +118,26 @@ impl StatusMessageFlyweight {
         unsafe { (*self.m_struct).receiver_window }
     }
 
+    /// Receiver id if the frame is long enough to hold it.
+    #[inline]
+    pub fn receiver_id(&self) -> Option<i64> {
+        if self.header_flyweight.frame_length() >= RECEIVER_ID_FIELD_OFFSET + 8 {
+            Some(self.header_flyweight.flyweight.get_unaligned::<i64>(RECEIVER_ID_FIELD_OFFSET))
+        } else {
+            None
+        }
+    }
+
+    /// Group tag if the frame is long enough to hold it.
+    #[inline]
+    pub fn group_tag(&self) -> Option<i64> {
+        if self.header_flyweight.frame_length() >= GROUP_TAG_FIELD_OFFSET + 8 {
+            Some(self.header_flyweight.flyweight.get_unaligned::<i64>(GROUP_TAG_FIELD_OFFSET))
+        } else {
+            None
+        }
+    }
+
     // Setters
     #[inline]
     pub fn set_session_id(&mut self, value: i32) {
@@ -135,8 +174,39 @@ impl StatusMessageFlyweight {
         }
     }
 
+    /// Write the receiver id, frame length has to be set to cover it.
+    #[inline]
+    pub fn set_receiver_id(&mut self, value: i64) {
+        self.header_flyweight
+            .flyweight
+            .put_unaligned::<i64>(RECEIVER_ID_FIELD_OFFSET, value);
+    }
+
+    /// Write the group tag, frame length has to be set to cover it.
+    #[inline]
+    pub fn set_group_tag(&mut self, value: i64) {
+        self.header_flyweight
+            .flyweight
+            .put_unaligned::<i64>(GROUP_TAG_FIELD_OFFSET, value);
+    }
+
     #[inline]
     pub const fn header_length() -> Index {
         STATUS_MESSAGE_DEFN_SIZE
     }
 }
+
+impl fmt::Debug for StatusMessageFlyweight {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StatusMessageFlyweight")
+            .field("header", &self.header_flyweight)
+            .field("session_id", &self.session_id())
+            .field("stream_id", &self.stream_id())
+            .field("consumption_term_id", &self.consumption_term_id())
+            .field("consumption_term_offset", &self.consumption_term_offset())
+            .field("receiver_window", &self.receiver_window())
+            .field("receiver_id", &self.receiver_id())
+            .field("group_tag", &self.group_tag())
+            .finish()
+    }
+}
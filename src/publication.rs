@@ -748,6 +748,7 @@ mod tests {
 
     use lazy_static::lazy_static;
 
+    use crate::channel_status_registry::ErroredChannel;
     use crate::client_conductor::ClientConductor;
     use crate::concurrent::atomic_buffer::{AlignedBuffer, AtomicBuffer};
     use crate::concurrent::broadcast::broadcast_buffer_descriptor;
@@ -819,6 +820,8 @@ mod tests {
 
     fn on_close_client_handler() {}
 
+    fn on_channel_errored_handler(_errored_channel: &ErroredChannel) {}
+
     #[allow(dead_code)]
     struct PublicationTest {
         src: AlignedBuffer,
@@ -885,6 +888,7 @@ mod tests {
                 Box::new(on_available_counter_handler),
                 Box::new(on_unavailable_counter_handler),
                 Box::new(on_close_client_handler),
+                Box::new(on_channel_errored_handler),
                 DRIVER_TIMEOUT_MS,
                 RESOURCE_LINGER_TIMEOUT_MS,
                 INTER_SERVICE_TIMEOUT_MS,
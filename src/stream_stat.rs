@@ -0,0 +1,237 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::collections::BTreeMap;
+
+use crate::concurrent::counters::CountersReader;
+use crate::driver_counters::DriverCounter;
+
+/// Identity of a stream as seen by the driver: session, stream and channel.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StreamKey {
+    pub session_id: i32,
+    pub stream_id: i32,
+    pub channel: String,
+}
+
+/// Position of a subscriber (sub-pos counter) on a stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubscriberPosition {
+    pub registration_id: i64,
+    pub counter_id: i32,
+    pub position: i64,
+}
+
+/**
+ * Positions of all driver counters of a stream. Counters are None when the driver does not track
+ * them for this stream, e.g. there is no sender for IPC or no publisher in a receiving driver.
+ */
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StreamPositions {
+    pub publisher_position: Option<i64>,
+    pub publisher_limit: Option<i64>,
+    pub sender_position: Option<i64>,
+    pub sender_limit: Option<i64>,
+    pub receiver_hwm: Option<i64>,
+    pub receiver_position: Option<i64>,
+    /// Subscribers ordered by registration id.
+    pub subscribers: Vec<SubscriberPosition>,
+}
+
+impl StreamPositions {
+    /// Bytes published but not yet sent by the sender.
+    pub fn publisher_backlog(&self) -> Option<i64> {
+        Some(self.publisher_position? - self.sender_position?)
+    }
+
+    /// Bytes sent but not yet received, only known when sender and receiver share the driver.
+    pub fn sender_backlog(&self) -> Option<i64> {
+        Some(self.sender_position? - self.receiver_hwm?)
+    }
+
+    /**
+     * Position subscribers consume up to: the receiver high water mark for network streams and the
+     * publisher position for IPC streams.
+     */
+    pub fn available_position(&self) -> Option<i64> {
+        self.receiver_hwm.or(self.publisher_position)
+    }
+
+    /// Bytes available to the subscriber but not yet consumed by it.
+    pub fn subscriber_backlog(&self, subscriber: &SubscriberPosition) -> Option<i64> {
+        Some(self.available_position()? - subscriber.position)
+    }
+
+    /**
+     * Subscribers which are behind the available position by more than the threshold.
+     *
+     * @param threshold_bytes backlog above which a subscriber is considered slow.
+     * @return (subscriber, backlog) of slow subscribers.
+     */
+    pub fn slow_subscribers(&self, threshold_bytes: i64) -> Vec<(&SubscriberPosition, i64)> {
+        self.subscribers
+            .iter()
+            .filter_map(|subscriber| {
+                self.subscriber_backlog(subscriber)
+                    .filter(|backlog| *backlog > threshold_bytes)
+                    .map(|backlog| (subscriber, backlog))
+            })
+            .collect()
+    }
+}
+
+/**
+ * Group the stream position counters (pub-pos, pub-lmt, snd-pos, snd-lmt, rcv-hwm, rcv-pos and
+ * sub-pos) by session, stream and channel.
+ *
+ * @param counters_reader to read the counters from.
+ * @return positions of each stream ordered by stream key.
+ */
+pub fn stream_positions(counters_reader: &CountersReader) -> BTreeMap<StreamKey, StreamPositions> {
+    let mut streams: BTreeMap<StreamKey, StreamPositions> = BTreeMap::new();
+
+    counters_reader.for_each(|counter_id, type_id, key_buffer, _label| {
+        let counter = DriverCounter::decode(type_id, key_buffer);
+        let (registration_id, key) = match counter.stream_key() {
+            Some(key) => (key.registration_id, StreamKey {
+                session_id: key.session_id,
+                stream_id: key.stream_id,
+                channel: key.channel.clone(),
+            }),
+            None => return,
+        };
+
+        let position = counters_reader.counter_value(counter_id).unwrap_or_default() as i64;
+        let stream = streams.entry(key).or_default();
+
+        match counter {
+            DriverCounter::PublisherPosition(_) => stream.publisher_position = Some(position),
+            DriverCounter::PublisherLimit(_) => stream.publisher_limit = Some(position),
+            DriverCounter::SenderPosition(_) => stream.sender_position = Some(position),
+            DriverCounter::SenderLimit(_) => stream.sender_limit = Some(position),
+            DriverCounter::ReceiverHwm(_) => stream.receiver_hwm = Some(position),
+            DriverCounter::ReceiverPosition(_) => stream.receiver_position = Some(position),
+            DriverCounter::SubscriberPosition(_) => stream.subscribers.push(SubscriberPosition {
+                registration_id,
+                counter_id,
+                position,
+            }),
+            _ => {},
+        }
+    });
+
+    streams.retain(|_, stream| *stream != StreamPositions::default());
+
+    for stream in streams.values_mut() {
+        stream.subscribers.sort_by_key(|subscriber| subscriber.registration_id);
+    }
+
+    streams
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::concurrent::atomic_buffer::AtomicBuffer;
+    use crate::concurrent::counters::CountersFixture;
+    use crate::driver_counters::{
+        PUBLISHER_LIMIT_TYPE_ID, PUBLISHER_POSITION_TYPE_ID, RECEIVER_HWM_TYPE_ID, SENDER_BPE_TYPE_ID, SENDER_POSITION_TYPE_ID,
+        STREAM_COUNTER_CHANNEL_OFFSET, STREAM_COUNTER_REGISTRATION_ID_OFFSET, STREAM_COUNTER_SESSION_ID_OFFSET,
+        STREAM_COUNTER_STREAM_ID_OFFSET, SUBSCRIBER_POSITION_TYPE_ID,
+    };
+
+    const NUM_COUNTERS: i32 = 16;
+    const UDP_CHANNEL: &str = "aeron:udp?endpoint=localhost:40123";
+    const IPC_CHANNEL: &str = "aeron:ipc";
+
+    fn add_position(
+        fixture: &mut CountersFixture,
+        type_id: i32,
+        registration_id: i64,
+        session_id: i32,
+        channel: &str,
+        position: i64,
+    ) -> i32 {
+        let id = fixture
+            .manager
+            .allocate_opt(
+                type_id,
+                None,
+                Some(|key_buffer: &mut AtomicBuffer| {
+                    key_buffer.put::<i64>(STREAM_COUNTER_REGISTRATION_ID_OFFSET, registration_id);
+                    key_buffer.put::<i32>(STREAM_COUNTER_SESSION_ID_OFFSET, session_id);
+                    key_buffer.put::<i32>(STREAM_COUNTER_STREAM_ID_OFFSET, 1001);
+                    key_buffer.put_string(STREAM_COUNTER_CHANNEL_OFFSET, channel.as_bytes());
+                }),
+                "position",
+            )
+            .unwrap();
+        fixture.manager.set_counter_value(id, position as u64);
+        id
+    }
+
+    #[test]
+    fn should_group_positions_by_stream_and_compute_backlog_per_hop() {
+        let mut fixture = CountersFixture::new(NUM_COUNTERS);
+        add_position(&mut fixture, PUBLISHER_POSITION_TYPE_ID, 3, 77, UDP_CHANNEL, 4096);
+        add_position(&mut fixture, PUBLISHER_LIMIT_TYPE_ID, 3, 77, UDP_CHANNEL, 65536);
+        add_position(&mut fixture, SENDER_POSITION_TYPE_ID, 3, 77, UDP_CHANNEL, 3072);
+        add_position(&mut fixture, RECEIVER_HWM_TYPE_ID, 8, 77, UDP_CHANNEL, 2048);
+        let fast_id = add_position(&mut fixture, SUBSCRIBER_POSITION_TYPE_ID, 12, 77, UDP_CHANNEL, 2048);
+        let slow_id = add_position(&mut fixture, SUBSCRIBER_POSITION_TYPE_ID, 9, 77, UDP_CHANNEL, 0);
+        add_position(&mut fixture, SENDER_BPE_TYPE_ID, 3, 77, UDP_CHANNEL, 5);
+
+        let streams = stream_positions(&fixture.reader());
+
+        assert_eq!(streams.len(), 1);
+        let (key, stream) = streams.iter().next().unwrap();
+        assert_eq!(key.session_id, 77);
+        assert_eq!(key.stream_id, 1001);
+        assert_eq!(key.channel, UDP_CHANNEL);
+        assert_eq!(stream.publisher_limit, Some(65536));
+        assert_eq!(stream.publisher_backlog(), Some(1024));
+        assert_eq!(stream.sender_backlog(), Some(1024));
+        assert_eq!(stream.subscribers.len(), 2);
+        assert_eq!(stream.subscribers[0].counter_id, slow_id);
+        assert_eq!(stream.subscribers[1].counter_id, fast_id);
+        assert_eq!(stream.subscriber_backlog(&stream.subscribers[0]), Some(2048));
+        assert_eq!(stream.subscriber_backlog(&stream.subscribers[1]), Some(0));
+
+        let slow = stream.slow_subscribers(1024);
+        assert_eq!(slow.len(), 1);
+        assert_eq!(slow[0].0.counter_id, slow_id);
+        assert_eq!(slow[0].1, 2048);
+    }
+
+    #[test]
+    fn should_use_publisher_position_for_ipc_subscribers() {
+        let mut fixture = CountersFixture::new(NUM_COUNTERS);
+        add_position(&mut fixture, PUBLISHER_POSITION_TYPE_ID, 3, 77, IPC_CHANNEL, 8192);
+        add_position(&mut fixture, SUBSCRIBER_POSITION_TYPE_ID, 4, 77, IPC_CHANNEL, 4096);
+        add_position(&mut fixture, SUBSCRIBER_POSITION_TYPE_ID, 5, 78, UDP_CHANNEL, 0);
+
+        let streams = stream_positions(&fixture.reader());
+
+        assert_eq!(streams.len(), 2);
+        let ipc = streams.values().next().unwrap();
+        assert_eq!(ipc.sender_position, None);
+        assert_eq!(ipc.publisher_backlog(), None);
+        assert_eq!(ipc.subscriber_backlog(&ipc.subscribers[0]), Some(4096));
+
+        let udp = streams.values().nth(1).unwrap();
+        assert_eq!(udp.subscriber_backlog(&udp.subscribers[0]), None);
+    }
+}
@@ -14,4 +14,205 @@
  * limitations under the License.
  */
 
-// TODO: Implement if necessary
+use std::ffi::OsString;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use crate::concurrent::atomic_buffer::AtomicBuffer;
+use crate::concurrent::logbuffer::term_reader::ErrorHandler;
+use crate::utils::bit_utils::align;
+use crate::utils::errors::{error_log_descriptor, AeronError};
+use crate::utils::memory_mapped_file::MemoryMappedFile;
+use crate::utils::types::{Index, Moment};
+
+type EpochClock = fn() -> Moment;
+
+struct DistinctObservation {
+    encoded_error: String,
+    offset: Index,
+}
+
+struct Observations {
+    distinct: Vec<DistinctObservation>,
+    next_offset: Index,
+}
+
+/**
+ * Writer of a distinct error log in the format described in error_log_descriptor, so it can be read with
+ * error_log_reader the same way as the error log of the driver.
+ *
+ * Errors are distinct by their encoded message. Repeated observations only increment the observation
+ * count and update the last observation timestamp of the existing record.
+ *
+ * This struct is threadsafe to be used from multiple logging threads.
+ */
+pub struct DistinctErrorLog {
+    buffer: AtomicBuffer,
+    clock: EpochClock,
+    observations: Mutex<Observations>,
+    _file: Option<MemoryMappedFile>,
+}
+
+impl DistinctErrorLog {
+    pub fn new(buffer: AtomicBuffer, clock: EpochClock) -> Self {
+        Self {
+            buffer,
+            clock,
+            observations: Mutex::new(Observations {
+                distinct: Vec::new(),
+                next_offset: 0,
+            }),
+            _file: None,
+        }
+    }
+
+    /**
+     * Create a new memory mapped file of the given capacity and log errors into it.
+     *
+     * @param path     of the file to create.
+     * @param capacity of the log in bytes.
+     * @param clock    giving the time of observations in ms since epoch.
+     */
+    pub fn create_file<P: AsRef<Path> + Into<OsString>>(path: P, capacity: Index, clock: EpochClock) -> Result<Self, AeronError> {
+        let file = MemoryMappedFile::create_new(path, 0, capacity)?;
+        let mut log = Self::new(file.atomic_buffer(0, file.memory_size()), clock);
+        log._file = Some(file);
+
+        Ok(log)
+    }
+
+    pub fn buffer(&self) -> AtomicBuffer {
+        self.buffer
+    }
+
+    /**
+     * Record an observation of an error.
+     *
+     * @param error to record.
+     * @return true if the observation was recorded, false if there is not enough space in the log.
+     */
+    pub fn record(&self, error: &AeronError) -> bool {
+        self.record_message(&error.to_string())
+    }
+
+    /**
+     * Record an observation of an error given by its encoded message.
+     *
+     * @param encoded_error message identifying the distinct error.
+     * @return true if the observation was recorded, false if there is not enough space in the log.
+     */
+    pub fn record_message(&self, encoded_error: &str) -> bool {
+        let timestamp = (self.clock)() as i64;
+        let mut observations = self.observations.lock().expect("Mutex poisoned");
+
+        if let Some(observation) = observations
+            .distinct
+            .iter()
+            .find(|observation| observation.encoded_error == encoded_error)
+        {
+            let offset = observation.offset;
+            let count_offset = offset + *error_log_descriptor::OBSERVATION_COUNT_OFFSET;
+
+            self.buffer
+                .put_ordered::<i32>(count_offset, self.buffer.get::<i32>(count_offset).wrapping_add(1));
+            self.buffer
+                .put_ordered::<i64>(offset + *error_log_descriptor::LAST_OBSERVATION_TIMESTAMP_OFFSET, timestamp);
+
+            return true;
+        }
+
+        let offset = observations.next_offset;
+        let length = error_log_descriptor::HEADER_LENGTH + encoded_error.len() as Index;
+
+        if offset + length > self.buffer.capacity() {
+            return false;
+        }
+
+        self.buffer
+            .put_bytes(offset + error_log_descriptor::ENCODED_ERROR_OFFSET, encoded_error.as_bytes());
+        self.buffer
+            .put::<i64>(offset + *error_log_descriptor::FIRST_OBSERVATION_TIMESTAMP_OFFSET, timestamp);
+        self.buffer
+            .put::<i64>(offset + *error_log_descriptor::LAST_OBSERVATION_TIMESTAMP_OFFSET, timestamp);
+        self.buffer
+            .put::<i32>(offset + *error_log_descriptor::OBSERVATION_COUNT_OFFSET, 1);
+        // Length is written last as readers stop at the first record with zero length
+        self.buffer
+            .put_ordered::<i32>(offset + *error_log_descriptor::LENGTH_OFFSET, length);
+
+        observations.next_offset = offset + align(length, error_log_descriptor::RECORD_ALIGNMENT);
+        observations.distinct.push(DistinctObservation {
+            encoded_error: String::from(encoded_error),
+            offset,
+        });
+
+        true
+    }
+}
+
+/**
+ * Error handler recording errors into the log, e.g. to be set with Context::set_error_handler.
+ * Errors which do not fit into the log any more are written to the log output instead.
+ *
+ * @param log to record the errors into.
+ * @return error handler.
+ */
+pub fn error_handler(log: Arc<DistinctErrorLog>) -> impl ErrorHandler + Send + 'static {
+    move |error: AeronError| {
+        if !log.record(&error) {
+            log::error!("error log is full, error not recorded: {}", error);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    use super::*;
+    use crate::concurrent::atomic_buffer::AlignedBuffer;
+    use crate::utils::errors::error_log_reader::{self, ErrorLogEntry};
+    use crate::utils::errors::IllegalStateError;
+
+    static NOW_MS: AtomicU64 = AtomicU64::new(1000);
+
+    fn test_clock() -> Moment {
+        NOW_MS.load(Ordering::SeqCst)
+    }
+
+    #[test]
+    fn should_record_distinct_errors_and_count_observations() {
+        let aligned = AlignedBuffer::with_capacity(1024);
+        let log = DistinctErrorLog::new(AtomicBuffer::from_aligned(&aligned), test_clock);
+
+        NOW_MS.store(1000, Ordering::SeqCst);
+        assert!(log.record_message("first error"));
+        assert!(log.record(&AeronError::IllegalState(IllegalStateError::CouldNotWriteCommandToDriver)));
+        NOW_MS.store(2000, Ordering::SeqCst);
+        assert!(log.record_message("first error"));
+
+        let entries: Vec<ErrorLogEntry> = error_log_reader::entries(log.buffer(), 0).collect();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].encoded_exception.to_str().unwrap(), "first error");
+        assert_eq!(entries[0].observation_count, 2);
+        assert_eq!(entries[0].first_observation_timestamp, 1000);
+        assert_eq!(entries[0].last_observation_timestamp, 2000);
+        assert_eq!(entries[1].observation_count, 1);
+        assert_eq!(
+            entries[1].encoded_exception.to_str().unwrap(),
+            AeronError::IllegalState(IllegalStateError::CouldNotWriteCommandToDriver).to_string()
+        );
+    }
+
+    #[test]
+    fn should_reject_new_error_when_log_is_full() {
+        let aligned = AlignedBuffer::with_capacity(64);
+        let log = DistinctErrorLog::new(AtomicBuffer::from_aligned(&aligned), test_clock);
+
+        assert!(log.record_message("fits into the log"));
+        assert!(!log.record_message("does not fit into the log any more"));
+        assert!(log.record_message("fits into the log"));
+        assert_eq!(error_log_reader::entries(log.buffer(), 0).count(), 1);
+    }
+}
@@ -19,6 +19,78 @@ use std::ffi::CString;
 use crate::concurrent::atomic_buffer::AtomicBuffer;
 use crate::utils::bit_utils::align;
 use crate::utils::errors::error_log_descriptor;
+use crate::utils::types::Index;
+
+/// Distinct error observation read from the error log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorLogEntry {
+    /// Offset of the record in the log. Records do not move so the offset identifies the distinct error.
+    pub offset: Index,
+    pub observation_count: i32,
+    pub first_observation_timestamp: i64,
+    pub last_observation_timestamp: i64,
+    pub encoded_exception: CString,
+}
+
+/// Iterator over the records of an error log, see entries().
+pub struct ErrorLogEntries {
+    buffer: AtomicBuffer,
+    offset: Index,
+    since_timestamp: i64,
+}
+
+impl Iterator for ErrorLogEntries {
+    type Item = ErrorLogEntry;
+
+    fn next(&mut self) -> Option<ErrorLogEntry> {
+        while self.offset < self.buffer.capacity() {
+            let offset = self.offset;
+            let length = self.buffer.get_volatile::<i32>(offset + *error_log_descriptor::LENGTH_OFFSET);
+
+            if length == 0 {
+                return None;
+            }
+
+            self.offset += align(length, error_log_descriptor::RECORD_ALIGNMENT);
+
+            let last_observation_timestamp = self
+                .buffer
+                .get_volatile::<i64>(offset + *error_log_descriptor::LAST_OBSERVATION_TIMESTAMP_OFFSET);
+
+            if last_observation_timestamp >= self.since_timestamp {
+                let entry = unsafe { *self.buffer.overlay_struct::<error_log_descriptor::ErrorLogEntryDefn>(offset) };
+
+                return Some(ErrorLogEntry {
+                    offset,
+                    observation_count: entry.observation_count,
+                    first_observation_timestamp: entry.first_observation_timestamp,
+                    last_observation_timestamp,
+                    encoded_exception: self.buffer.get_string_without_length(
+                        offset + error_log_descriptor::ENCODED_ERROR_OFFSET,
+                        length - error_log_descriptor::HEADER_LENGTH,
+                    ),
+                });
+            }
+        }
+
+        None
+    }
+}
+
+/**
+ * Iterate over the distinct errors in the log which were last observed at or after a given time.
+ *
+ * @param buffer          containing the error log.
+ * @param since_timestamp in ms since epoch for filtering entries by last observation time.
+ * @return iterator over the matching entries.
+ */
+pub fn entries(buffer: AtomicBuffer, since_timestamp: i64) -> ErrorLogEntries {
+    ErrorLogEntries {
+        buffer,
+        offset: 0,
+        since_timestamp,
+    }
+}
 
 /**
 Handler have four arguments:
@@ -33,40 +105,59 @@ pub fn read<T>(buffer: AtomicBuffer, mut consumer: T, since_timestamp: i64) -> i
 where
     T: FnMut(i32, i64, i64, CString),
 {
-    let mut entries = 0;
-    let mut offset = 0;
-    let capacity = buffer.capacity();
+    let mut entries_read = 0;
 
-    while offset < capacity {
-        let length = buffer.get_volatile::<i32>(offset + *error_log_descriptor::LENGTH_OFFSET);
+    for entry in entries(buffer, since_timestamp) {
+        entries_read += 1;
+        consumer(
+            entry.observation_count,
+            entry.first_observation_timestamp,
+            entry.last_observation_timestamp,
+            entry.encoded_exception,
+        );
+    }
 
-        if length == 0 {
-            break;
-        }
+    entries_read
+}
 
-        let last_observation_timestamp =
-            buffer.get_volatile::<i64>(offset + *error_log_descriptor::LAST_OBSERVATION_TIMESTAMP_OFFSET);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::concurrent::atomic_buffer::AlignedBuffer;
 
-        if last_observation_timestamp >= since_timestamp {
-            let entry = buffer.overlay_struct::<error_log_descriptor::ErrorLogEntryDefn>(offset);
+    fn put_entry(buffer: &AtomicBuffer, offset: Index, count: i32, first: i64, last: i64, error: &str) -> Index {
+        let length = error_log_descriptor::HEADER_LENGTH + error.len() as Index;
 
-            entries += 1;
+        buffer.put_bytes(offset + error_log_descriptor::ENCODED_ERROR_OFFSET, error.as_bytes());
+        buffer.put::<i32>(offset + *error_log_descriptor::OBSERVATION_COUNT_OFFSET, count);
+        buffer.put::<i64>(offset + *error_log_descriptor::FIRST_OBSERVATION_TIMESTAMP_OFFSET, first);
+        buffer.put::<i64>(offset + *error_log_descriptor::LAST_OBSERVATION_TIMESTAMP_OFFSET, last);
+        buffer.put::<i32>(offset + *error_log_descriptor::LENGTH_OFFSET, length);
 
-            unsafe {
-                let entry = *entry;
-                consumer(
-                    entry.observation_count,
-                    entry.first_observation_timestamp,
-                    last_observation_timestamp,
-                    buffer.get_string_without_length(
-                        offset + error_log_descriptor::ENCODED_ERROR_OFFSET,
-                        length - error_log_descriptor::HEADER_LENGTH,
-                    ),
-                );
-            }
-        }
+        offset + align(length, error_log_descriptor::RECORD_ALIGNMENT)
+    }
+
+    #[test]
+    fn should_iterate_entries_last_observed_since_timestamp() {
+        let aligned = AlignedBuffer::with_capacity(1024);
+        let buffer = AtomicBuffer::from_aligned(&aligned);
+
+        let second_offset = put_entry(&buffer, 0, 3, 100, 200, "first error");
+        put_entry(&buffer, second_offset, 1, 300, 300, "second error");
+
+        let all: Vec<ErrorLogEntry> = entries(buffer, 0).collect();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].offset, 0);
+        assert_eq!(all[0].observation_count, 3);
+        assert_eq!(all[0].first_observation_timestamp, 100);
+        assert_eq!(all[0].last_observation_timestamp, 200);
+        assert_eq!(all[0].encoded_exception.to_str().unwrap(), "first error");
+
+        let recent: Vec<ErrorLogEntry> = entries(buffer, 250).collect();
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].offset, second_offset);
+        assert_eq!(recent[0].encoded_exception.to_str().unwrap(), "second error");
 
-        offset += align(length, error_log_descriptor::RECORD_ALIGNMENT);
+        assert_eq!(read(buffer, |_, _, _, _| {}, 250), 1);
     }
-    entries
 }
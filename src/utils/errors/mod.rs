@@ -19,6 +19,7 @@ use std::io;
 
 use thiserror::Error;
 
+use crate::aeron::CloseSummary;
 use crate::channel_uri::State;
 use crate::client_conductor::RegistrationStatus;
 use crate::concurrent::broadcast::BroadcastTransmitError;
@@ -47,16 +48,31 @@ pub enum AeronError {
     DriverTimeout(#[from] DriverInteractionError),
     #[error("ReentrantException: Client cannot be invoked within callback")]
     ReentrantException,
-    #[error("RegistrationException: {0}")]
-    RegistrationException(i32, String),
-    #[error("ChannelEndpointException. For msg with correlation ID = {0} error occurred: {1}")]
-    ChannelEndpointException(i64, String), // correlation ID + error message
+    #[error("RegistrationException: {code:?} for correlation_id={correlation_id} channel='{channel}' stream_id={stream_id}: {message}")]
+    RegistrationException {
+        code: AeronErrorCode,
+        message: String,
+        channel: String,
+        stream_id: i32,
+        correlation_id: i64,
+    },
+    #[error("ChannelEndpointException: {code:?} for registration_id={registration_id} channel='{channel}' stream_id={stream_id} (status indicator {correlation_id}): {message}")]
+    ChannelEndpointException {
+        code: AeronErrorCode,
+        message: String,
+        channel: String,
+        stream_id: i32,
+        registration_id: i64,
+        correlation_id: i64, // offending command correlation ID, equal to channel status indicator ID
+    },
     #[error("ClientTimeoutException from driver")]
     ClientTimeoutException,
     #[error("BroadcastTransmitError: {0:?}")]
     BroadcastTransmitError(#[from] BroadcastTransmitError),
     #[error("RingBufferError: {0:?}")]
     RingBuffer(#[from] RingBufferError),
+    #[error("Malformed frame: {0}")]
+    MalformedFrame(#[from] MalformedFrameError),
     #[error("Offer failed because publisher is not connected to subscriber")]
     NotConnected,
     #[error("Offer failed due to back pressure")]
@@ -69,12 +85,16 @@ pub enum AeronError {
     MaxPositionExceeded,
     #[error("Unknown code {0} on getting position")]
     UnknownCode(i64),
+    #[error("Client closed with errors: {:?}", .0.errors)]
+    CloseIncomplete(Box<CloseSummary>),
 }
 
 #[derive(Error, Debug)]
 pub enum IllegalStateError {
     #[error("Action possibly delayed: expected_term_id={expected_term_id} term_id={term_id}")]
     ActionPossiblyDelayed { term_id: i32, expected_term_id: i32 },
+    #[error("App counters file version is not supported: file={file_version} app={app_version}")]
+    AppCountersVersionNotSupported { file_version: i32, app_version: i32 },
     #[error("Couldn't write command to driver")]
     CouldNotWriteCommandToDriver,
     #[error("Encountered '{c}' within media definition at index {index} in '{uri}'")]
@@ -85,6 +105,10 @@ pub enum IllegalStateError {
     FrameHeaderLengthMustBeEqualToDataOffset { length: Index, data_offset: Index },
     #[error("Invalid end of key at index {index} in '{uri}'")]
     InvalidEndOfKey { index: usize, uri: String },
+    #[error("Log buffer file of {file_length} bytes is shorter than the {required_length} bytes of its layout")]
+    LogBufferFileTooShort { file_length: i64, required_length: i64 },
+    #[error("{0} log buffers are still in use by the application")]
+    LogBuffersStillInUse(usize),
     #[error("Length overflow: {0}")]
     LengthOverflow(i32),
     #[error("Max capacity was reached: {0}")]
@@ -109,6 +133,36 @@ pub enum IllegalStateError {
     TermLengthIsNotPowerOfTwo(i32),
 }
 
+#[derive(Error, Debug)]
+pub enum MalformedFrameError {
+    #[error("Buffer of {length} bytes is shorter than the {min_length} bytes header of {frame_type}")]
+    BufferTooShort {
+        frame_type: String,
+        length: Index,
+        min_length: Index,
+    },
+    #[error("Error message length {error_length} does not fit into frame of {frame_length} bytes")]
+    ErrorMessageLengthOutOfRange { error_length: i32, frame_length: i32 },
+    #[error("Frame length {frame_length} exceeds the {length} bytes available")]
+    FrameLengthExceedsBuffer { frame_length: i32, length: Index },
+    #[error("Frame length {frame_length} is less than the {min_length} bytes header of {frame_type}")]
+    FrameLengthTooShort {
+        frame_type: String,
+        frame_length: i32,
+        min_length: Index,
+    },
+    #[error("Resolution entry of {entry_length} bytes exceeds the {length} bytes left in frame")]
+    ResolutionEntryExceedsFrame { entry_length: Index, length: Index },
+    #[error("Unknown frame type {0:#06x}")]
+    UnknownFrameType(u16),
+    #[error("Unknown resolution type {0}")]
+    UnknownResolutionType(i8),
+    #[error("Flags {flags:#04x} are not supported by {frame_type}")]
+    UnsupportedFlags { frame_type: String, flags: u8 },
+    #[error("Unsupported protocol version {0}")]
+    UnsupportedVersion(u8),
+}
+
 #[derive(Error, Debug)]
 pub enum DriverInteractionError {
     #[error("CnC file is created but not initialised: {file_name}")]
@@ -146,8 +200,14 @@ pub enum IllegalArgumentError {
     EncodedMessageExceedsMaxMessageLength { length: i32, max_message_length: i32 },
     #[error("Encoded message exceeds max_payload_length of {max_payload_length}, length={length}")]
     EncodedMessageExceedsMaxPayloadLength { length: i32, max_payload_length: i32 },
+    #[error("Invalid channel parameter {name}={value}: {reason}")]
+    InvalidChannelParameter { name: String, value: String, reason: String },
     #[error("Invalid control mode: {0}")]
     InvalidControlMode(String),
+    #[error("Invalid loss proxy config at line {line}: {reason}")]
+    InvalidProxyConfig { line: usize, reason: String },
+    #[error("Invalid loss proxy rule: {0}")]
+    InvalidProxyRule(String),
     #[error("Invalid media: {0}")]
     InvalidMedia(String),
     #[error("Invalid prefix: {0}")]
@@ -172,12 +232,20 @@ pub enum IllegalArgumentError {
         left_bound: i64,
         right_bound: i64,
     },
+    #[error("Position {position} is out of range {left_bound} - {right_bound}")]
+    PositionOutOfRange {
+        position: i64,
+        left_bound: i64,
+        right_bound: i64,
+    },
     #[error("No more input found, state={state:?}")]
     NoMoreInputFound { state: State },
     #[error("Term offset is not in range 0-1g: {0}")]
     TermOffsetNotInRange(u32),
     #[error("Term offset is not a multiple of FRAME_ALIGNMENT= {frame_alignment}: offset= {term_offset}")]
     TermOffsetNotMultipleOfFrameAlignment { term_offset: u32, frame_alignment: Index },
+    #[error("Termination token is too long: length= {token_length}, limit= {limit}")]
+    TerminationTokenTooLong { token_length: usize, limit: Index },
     #[error("Unable to allocate counter, metadata buffer is full")]
     UnableAllocateCounterBecauseMetadataBufferFull,
     #[error("Unable to allocate counter, values buffer is full")]
@@ -222,6 +290,8 @@ pub enum GenericError {
     PublicationAlreadyDropped,
     #[error("Publication not found")]
     PublicationNotFound,
+    #[error("Socket error on {address}: {error}")]
+    SocketError { address: String, error: io::Error },
     #[error("String to CString conversion failed for endpoint_channel")]
     StringToCStringConversionFailed,
     #[error("Subscription already dropped")]
@@ -238,6 +308,16 @@ pub enum GenericError {
     UnknownRegistrationId(i64),
 }
 
+impl AeronError {
+    /// Error code reported by the driver, if this error originates from a driver error response.
+    pub fn error_code(&self) -> Option<AeronErrorCode> {
+        match self {
+            AeronError::RegistrationException { code, .. } | AeronError::ChannelEndpointException { code, .. } => Some(*code),
+            _ => None,
+        }
+    }
+}
+
 impl PartialEq for AeronError {
     fn eq(&self, other: &Self) -> bool {
         // Errors are equal if they have same type regardless the content of data (error message) inside
@@ -256,16 +336,99 @@ impl Hash for AeronError {
     }
 }
 
-#[repr(C)]
-#[derive(Debug)]
+/// Error codes sent by the driver in ERROR responses. Values match the driver side ErrorCode.
+#[repr(i32)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum AeronErrorCode {
+    UnknownCodeValue = -1,
     GenericError = 0,
     InvalidChannel = 1,
     UnknownSubscription = 2,
     UnknownPublication = 3,
     ChannelEndpointError = 4,
     UnknownCounter = 5,
-    UnknownCommandTypeId = 10,
-    MalformedCommand = 11,
-    ErrorNotSupplied = 12,
+    UnknownCommandTypeId = 6,
+    MalformedCommand = 7,
+    NotSupported = 8,
+    UnknownHost = 9,
+    ResourceTemporarilyUnavailable = 10,
+    StorageSpace = 11,
+    ImageRejected = 12,
+    PublicationRevoked = 13,
+}
+
+impl AeronErrorCode {
+    /// Maps raw code value from ErrorResponseFlyweight to the enum. Unknown values become UnknownCodeValue.
+    pub fn from_code(code: i32) -> Self {
+        match code {
+            0 => AeronErrorCode::GenericError,
+            1 => AeronErrorCode::InvalidChannel,
+            2 => AeronErrorCode::UnknownSubscription,
+            3 => AeronErrorCode::UnknownPublication,
+            4 => AeronErrorCode::ChannelEndpointError,
+            5 => AeronErrorCode::UnknownCounter,
+            6 => AeronErrorCode::UnknownCommandTypeId,
+            7 => AeronErrorCode::MalformedCommand,
+            8 => AeronErrorCode::NotSupported,
+            9 => AeronErrorCode::UnknownHost,
+            10 => AeronErrorCode::ResourceTemporarilyUnavailable,
+            11 => AeronErrorCode::StorageSpace,
+            12 => AeronErrorCode::ImageRejected,
+            13 => AeronErrorCode::PublicationRevoked,
+            _ => AeronErrorCode::UnknownCodeValue,
+        }
+    }
+
+    pub fn code(&self) -> i32 {
+        *self as i32
+    }
+
+    /// True if the driver refused the request because of a temporary condition and the same
+    /// request may succeed if retried later. Other codes indicate bad configuration or usage.
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            AeronErrorCode::ResourceTemporarilyUnavailable | AeronErrorCode::StorageSpace
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::error_response_flyweight::*;
+
+    #[test]
+    fn error_code_should_match_error_response_codes() {
+        assert_eq!(
+            AeronErrorCode::from_code(ERROR_CODE_GENERIC_ERROR),
+            AeronErrorCode::GenericError
+        );
+        assert_eq!(
+            AeronErrorCode::from_code(ERROR_CODE_INVALID_CHANNEL),
+            AeronErrorCode::InvalidChannel
+        );
+        assert_eq!(
+            AeronErrorCode::from_code(ERROR_CODE_NOT_SUPPORTED),
+            AeronErrorCode::NotSupported
+        );
+        assert_eq!(
+            AeronErrorCode::from_code(ERROR_CODE_RESOURCE_TEMPORARILY_UNAVAILABLE),
+            AeronErrorCode::ResourceTemporarilyUnavailable
+        );
+        assert_eq!(
+            AeronErrorCode::from_code(ERROR_CODE_PUBLICATION_REVOKED),
+            AeronErrorCode::PublicationRevoked
+        );
+        assert_eq!(AeronErrorCode::from_code(1000), AeronErrorCode::UnknownCodeValue);
+        assert_eq!(AeronErrorCode::StorageSpace.code(), ERROR_CODE_STORAGE_SPACE);
+    }
+
+    #[test]
+    fn error_code_should_tell_transient_from_configuration_errors() {
+        assert!(AeronErrorCode::ResourceTemporarilyUnavailable.is_transient());
+        assert!(AeronErrorCode::StorageSpace.is_transient());
+        assert!(!AeronErrorCode::InvalidChannel.is_transient());
+        assert!(!AeronErrorCode::UnknownSubscription.is_transient());
+    }
 }
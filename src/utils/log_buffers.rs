@@ -6,7 +6,7 @@ use crate::concurrent::logbuffer::log_buffer_descriptor::{
     self, check_page_size, check_term_length, page_size, term_length, PARTITION_COUNT,
 };
 use crate::log;
-use crate::utils::errors::AeronError;
+use crate::utils::errors::{AeronError, IllegalStateError};
 use crate::utils::memory_mapped_file::MemoryMappedFile;
 use crate::utils::types::Index;
 
@@ -41,14 +41,36 @@ impl LogBuffers {
     pub(crate) fn from_existing<P: std::fmt::Display + AsRef<Path> + Into<OsString>>(
         file_path: P,
         pre_touch: bool,
+    ) -> Result<Self, AeronError> {
+        Self::map_existing(file_path, pre_touch, false)
+    }
+
+    /// Map the log buffer file read only, e.g. to inspect the log of a running driver without writing to it.
+    pub(crate) fn from_existing_read_only<P: std::fmt::Display + AsRef<Path> + Into<OsString>>(
+        file_path: P,
+    ) -> Result<Self, AeronError> {
+        Self::map_existing(file_path, false, true)
+    }
+
+    fn map_existing<P: std::fmt::Display + AsRef<Path> + Into<OsString>>(
+        file_path: P,
+        pre_touch: bool,
+        read_only: bool,
     ) -> Result<Self, AeronError> {
         assert_eq!(log_buffer_descriptor::PARTITION_COUNT, 3);
 
-        log!(trace, "from_existing: file_path {}, pre_touch {}", &file_path, pre_touch);
+        log!(
+            trace,
+            "from_existing: file_path {}, pre_touch {}, read_only {}",
+            &file_path,
+            pre_touch,
+            read_only
+        );
 
         let log_len = MemoryMappedFile::get_file_size(&file_path)?;
+        check_log_length(log_len as i64, log_buffer_descriptor::LOG_META_DATA_LENGTH as i64)?;
 
-        let memory_mapped_file = MemoryMappedFile::map_existing(file_path, false).expect("todo");
+        let memory_mapped_file = MemoryMappedFile::map_existing(file_path, read_only)?;
 
         let meta_buffer = memory_mapped_file.atomic_buffer(
             (log_len as Index) - log_buffer_descriptor::LOG_META_DATA_LENGTH,
@@ -60,6 +82,10 @@ impl LogBuffers {
 
         check_term_length(term_length)?;
         check_page_size(page_size)?;
+        check_log_length(
+            log_len as i64,
+            (PARTITION_COUNT * term_length) as i64 + log_buffer_descriptor::LOG_META_DATA_LENGTH as i64,
+        )?;
 
         let mut buffers: Vec<AtomicBuffer> = Vec::with_capacity((PARTITION_COUNT + 1) as usize);
 
@@ -96,3 +122,15 @@ impl LogBuffers {
         self.buffers[index as usize]
     }
 }
+
+fn check_log_length(file_length: i64, required_length: i64) -> Result<(), AeronError> {
+    if file_length < required_length {
+        return Err(IllegalStateError::LogBufferFileTooShort {
+            file_length,
+            required_length,
+        }
+        .into());
+    }
+
+    Ok(())
+}
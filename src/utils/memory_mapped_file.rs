@@ -19,15 +19,22 @@ use std::ffi::OsString;
 use std::fs::{self, OpenOptions};
 use std::path::Path;
 
-use memmap::MmapMut;
+use memmap::{Mmap, MmapMut};
 
 use crate::concurrent::atomic_buffer::AtomicBuffer;
 use crate::utils::errors::AeronError;
 use crate::utils::types::Index;
 
+/// Mapping of a file opened read only can't be written, writing through its AtomicBuffer faults.
+#[derive(Debug)]
+enum Mapping {
+    ReadOnly(Mmap),
+    ReadWrite(MmapMut),
+}
+
 #[derive(Debug)]
 pub struct FileHandle {
-    mmap: MmapMut,
+    mmap: Mapping,
     file_path: OsString,
 }
 
@@ -40,8 +47,13 @@ impl FileHandle {
             .open(&file_path)
             .map_err(AeronError::MemMappedFileError)?;
 
-        unsafe { MmapMut::map_mut(&file) }
-            .map_err(AeronError::MemMappedFileError)
+        let mmap = if read_only {
+            unsafe { Mmap::map(&file) }.map(Mapping::ReadOnly)
+        } else {
+            unsafe { MmapMut::map_mut(&file) }.map(Mapping::ReadWrite)
+        };
+
+        mmap.map_err(AeronError::MemMappedFileError)
             .map(move |mmap| Self { mmap, file_path })
     }
 
@@ -58,7 +70,17 @@ impl FileHandle {
 
         unsafe { MmapMut::map_mut(&file) }
             .map_err(AeronError::MemMappedFileError)
-            .map(move |mmap| Self { mmap, file_path })
+            .map(move |mmap| Self {
+                mmap: Mapping::ReadWrite(mmap),
+                file_path,
+            })
+    }
+
+    fn as_mut_ptr(&mut self) -> *mut u8 {
+        match &mut self.mmap {
+            Mapping::ReadOnly(mmap) => mmap.as_ptr() as *mut u8,
+            Mapping::ReadWrite(mmap) => mmap.as_mut_ptr(),
+        }
     }
 }
 
@@ -98,7 +120,7 @@ impl MemoryMappedFile {
         }
 
         let mmf = Self {
-            ptr: fd.mmap.as_mut_ptr(),
+            ptr: fd.as_mut_ptr(),
             fd,
             memory_size: length,
         };
@@ -148,7 +170,8 @@ mod tests {
 
     #[test]
     fn test_creating_file() {
-        MemoryMappedFile::create_new(Path::new("abc.file"), 0, 128).unwrap();
+        let tmp_dir = tempfile::tempdir().unwrap();
+        MemoryMappedFile::create_new(tmp_dir.path().join("abc.file"), 0, 128).unwrap();
     }
 
     #[allow(dead_code)]
@@ -208,4 +231,23 @@ mod tests {
             assert_eq!(option, (n & 0xff) as u8)
         }
     }
+
+    #[test]
+    fn should_map_read_only_file() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let file_path = tmp_dir.path().join("read_only.file");
+
+        {
+            let file = MemoryMappedFile::create_new(file_path.clone(), 0, 64).unwrap();
+            file.atomic_buffer(0, 64).put::<i64>(8, 42);
+        }
+
+        let mut permissions = fs::metadata(&file_path).unwrap().permissions();
+        permissions.set_readonly(true);
+        fs::set_permissions(&file_path, permissions).unwrap();
+
+        let file = MemoryMappedFile::map_existing(file_path, true).unwrap();
+        assert_eq!(file.memory_size(), 64);
+        assert_eq!(file.atomic_buffer(0, 64).get::<i64>(8), 42);
+    }
 }
@@ -99,6 +99,67 @@ pub fn dealloc_buffer_aligned(buff_ptr: *mut u8, len: Index) {
     }
 }
 
+/// Escapes the string to be embedded into JSON string literal (without surrounding quotes).
+pub fn json_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Quotes the CSV field if it contains separators, quotes or line breaks.
+pub fn csv_escape(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        String::from(value)
+    }
+}
+
+/// Lines of 16 bytes as offset, hex and printable ASCII, e.g. for payloads of frames and messages.
+pub fn hex_dump(bytes: &[u8]) -> String {
+    let mut dump = String::with_capacity(bytes.len() * 4 + 16);
+
+    for (line, chunk) in bytes.chunks(16).enumerate() {
+        if line > 0 {
+            dump.push('\n');
+        }
+
+        dump.push_str(&format!("{:08x} ", line * 16));
+        for index in 0..16 {
+            match chunk.get(index) {
+                Some(byte) => dump.push_str(&format!(" {:02x}", byte)),
+                None => dump.push_str("   "),
+            }
+        }
+
+        dump.push_str("  |");
+        dump.push_str(&printable_ascii(chunk));
+        dump.push('|');
+    }
+
+    dump
+}
+
+/// Bytes as ASCII with '.' for bytes which aren't printable.
+pub fn printable_ascii(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|&byte| if byte.is_ascii_graphic() || byte == b' ' { byte as char } else { '.' })
+        .collect()
+}
+
 /// This struct is used to set bool flag to true till the end of scope and
 /// set the flag to false when dropped.
 pub struct CallbackGuard<'a> {
@@ -117,3 +178,27 @@ impl<'a> Drop for CallbackGuard<'a> {
         *self.is_in_callback = false;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_escape_json_and_csv_values() {
+        assert_eq!(json_escape("a\"b\\c\n\u{1}"), "a\\\"b\\\\c\\n\\u0001");
+        assert_eq!(csv_escape("plain"), "plain");
+        assert_eq!(csv_escape("a,\"b\""), "\"a,\"\"b\"\"\"");
+    }
+
+    #[test]
+    fn should_dump_bytes_as_hex_and_ascii() {
+        let bytes: Vec<u8> = (0x30..0x42).collect();
+
+        assert_eq!(
+            hex_dump(&bytes),
+            "00000000  30 31 32 33 34 35 36 37 38 39 3a 3b 3c 3d 3e 3f  |0123456789:;<=>?|\n\
+             00000010  40 41                                            |@A|"
+        );
+        assert_eq!(printable_ascii(b"a\x00b\n"), "a.b.");
+    }
+}
@@ -119,7 +119,14 @@ fn test_counter_create() {
         counter_on_a_side = aeron_a.find_counter(counter_id);
     }
 
-    assert!(!counter_on_a_side.unwrap().is_closed());
+    let counter = counter_on_a_side.unwrap();
+    assert!(!counter.is_closed());
+
+    counter.set_ordered(5);
+    counter.increment();
+    assert_eq!(counter.get_and_add(4), 6);
+    assert!(counter.compare_and_set(10, 11));
+    assert_eq!(counter.get(), 11);
 
     // Both instances informed about counter creation
     assert!(HANDLER_A_CALLED.load(Ordering::SeqCst));